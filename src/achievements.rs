@@ -0,0 +1,65 @@
+//! Achievements
+//!
+//! Long goals that add up over many runs, like slaying 100 monsters with weapons. Each
+//! finished run's event log is tallied into the profile's counters, and the
+//! `achievements` screen shows how far along each one is.
+
+use crate::logic::{Action, Game};
+use crate::profile::Profile;
+
+pub struct Achievement {
+    pub name: &'static str,
+    pub goal: &'static str,
+    pub target: u32,
+    /// Where the profile stands on it
+    pub progress: fn(&Profile) -> u32,
+}
+
+pub const ACHIEVEMENTS: &[Achievement] = &[
+    Achievement {
+        name: "Monster Hunter",
+        goal: "Slay 100 monsters with weapons",
+        target: 100,
+        progress: |p| p.weapon_kills,
+    },
+    Achievement {
+        name: "Delver",
+        goal: "Face 250 rooms",
+        target: 250,
+        progress: |p| p.rooms_faced,
+    },
+    Achievement {
+        name: "Field Medic",
+        goal: "Heal 500 HP",
+        target: 500,
+        progress: |p| p.hp_healed,
+    },
+    Achievement {
+        name: "Veteran",
+        goal: "Finish 50 runs",
+        target: 50,
+        progress: |p| p.wins + p.losses,
+    },
+    Achievement {
+        name: "Champion",
+        goal: "Survive the dungeon 10 times",
+        target: 10,
+        progress: |p| p.wins,
+    },
+];
+
+/// Add a finished run's weapon kills, rooms faced and healing to the profile
+pub fn tally(profile: &mut Profile, game: &Game) {
+    let mut hp = game.starting_health();
+    for event in &game.events {
+        match event.action {
+            Action::Fight { weapon: Some(_) } => profile.weapon_kills += 1,
+            Action::Face => profile.rooms_faced += 1,
+            _ => {}
+        }
+        if event.health > hp {
+            profile.hp_healed += (event.health - hp) as u32;
+        }
+        hp = event.health;
+    }
+}
@@ -0,0 +1,90 @@
+//! Room advisor
+//!
+//! Ranks the cards in the room for the `ranks` display: monsters by the damage they'd do
+//! you right now, potions and weapons by what they'd give you right now. It says which
+//! card is scariest and which is the best pickup, not what to play, so the order you take
+//! them in is still yours to work out.
+
+use crate::logic::{Armor, Card, Game, Mutator};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rank {
+    /// 1 is the monster that would hurt most
+    Threat(usize),
+    /// 1 is the pickup worth most
+    Pickup(usize),
+}
+
+impl Rank {
+    /// Short tag for a room card, e.g. `T1`
+    pub fn tag(self) -> String {
+        match self {
+            Rank::Threat(n) => format!("T{n}"),
+            Rank::Pickup(n) => format!("P{n}"),
+        }
+    }
+}
+
+/// A rank for each occupied room slot
+pub fn room_ranks(game: &Game) -> [Option<Rank>; 4] {
+    let mut threats: Vec<(usize, i32)> = Vec::new();
+    let mut pickups: Vec<(usize, i32)> = Vec::new();
+    for i in 0..4 {
+        let Some(card) = game.visible_card(i) else {
+            continue;
+        };
+        if card.is_monster() {
+            threats.push((i, damage(game, card)));
+        } else {
+            pickups.push((i, worth(game, card)));
+        }
+    }
+
+    let mut ranks = [None; 4];
+    // Stable sorts: equal cards keep slot order
+    threats.sort_by_key(|(_, dmg)| -dmg);
+    pickups.sort_by_key(|(_, worth)| -worth);
+    for (place, (i, _)) in threats.into_iter().enumerate() {
+        ranks[i] = Some(Rank::Threat(place + 1));
+    }
+    for (place, (i, _)) in pickups.into_iter().enumerate() {
+        ranks[i] = Some(Rank::Pickup(place + 1));
+    }
+    ranks
+}
+
+/// The least this monster would cost you now, after any armor
+pub fn damage(game: &Game, monster: Card) -> i32 {
+    let dmg = if game.can_use_weapon_on(monster) {
+        game.weapon_damage(monster)
+    } else {
+        game.monster_strength(monster)
+    };
+    dmg - game.armor_soaks(dmg)
+}
+
+/// HP a potion would really heal, or how far a weapon beats the one in hand
+fn worth(game: &Game, card: Card) -> i32 {
+    let value = i32::from(card.value);
+    if card.suit == 'H' {
+        if game.potions_this_room >= game.rules.potion_limit {
+            return 0;
+        }
+        let heal = if game.has(Mutator::HalfPotions) {
+            (value + 1) / 2
+        } else {
+            value
+        };
+        return heal.min(game.max_health - game.health);
+    }
+    // Armor is worth what it can soak up, less what's left of the set being worn
+    if game.is_armor(card) {
+        let fresh = Armor::new(card).left();
+        return i32::from(fresh) - game.armor.map_or(0, |a| i32::from(a.left()));
+    }
+    // Against a fresh weapon only the difference counts; a used one is treated as spent
+    match game.weapon() {
+        Some(w) if game.last_slain().is_none() => value - i32::from(w.value),
+        _ => value,
+    }
+}
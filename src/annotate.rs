@@ -0,0 +1,170 @@
+//! Batch annotation of exported runs (`--annotate DIR`)
+//!
+//! Every run exported into DIR is dealt again from its seed and replayed move by move.
+//! At each real decision (more than one legal move) the solver weighs every option, and
+//! the move played is marked against the best, the way a chess annotator would: `?` when
+//! something else did better, `??` for a blunder. Each run gets a PGN-style text file
+//! beside its export, headed by tags for the seed, rules, result and blunder count, so a
+//! coach or streamer can go through a pile of runs offline.
+
+use std::fs;
+use std::path::Path;
+
+use crate::command;
+use crate::export::{self, Exported};
+use crate::logic::{Action, Game, GameState};
+use crate::solver::{self, Estimate};
+
+/// Rollouts per move at each decision unless `--rollouts` says otherwise. Enough to
+/// separate a blunder from the best move; `analyze` in game uses more.
+pub const ROLLOUTS: u32 = 20;
+
+/// A move whose odds are this far behind the best (in tenths of a percent), or that's
+/// no likelier to survive and this many points worse on average, is a blunder
+const BLUNDER_PERMILLE: u32 = 100;
+const BLUNDER_SCORE: i64 = 10;
+
+/// An annotated run, and its tallies for the summary
+struct Annotated {
+    text: String,
+    decisions: usize,
+    blunders: usize,
+    /// The move the replay stopped matching the export at, if it did
+    stopped: Option<usize>,
+}
+
+/// Annotate every `.json` export in `dir`, writing `NAME.annotated.txt` beside each.
+/// Returns a line per export saying how it went.
+pub fn annotate_dir(dir: &Path, rollouts: u32) -> Result<String, String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("couldn't read {}: {e}", dir.display()))?;
+    let mut exports: Vec<_> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|x| x == "json"))
+        .collect();
+    if exports.is_empty() {
+        return Err(format!("no exported runs (*.json) in {}", dir.display()));
+    }
+    exports.sort();
+
+    let mut report = String::new();
+    for path in exports {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let out = path.with_extension("annotated.txt");
+        let done = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| export::decode(&text))
+            .map(|exported| annotate(&exported, rollouts))
+            .and_then(|a| {
+                fs::write(&out, &a.text).map_err(|e| e.to_string())?;
+                Ok(a)
+            });
+        report += &match done {
+            Ok(a) => format!(
+                "{name}: {} decisions, {} blunders{} -> {}\n",
+                a.decisions,
+                a.blunders,
+                a.stopped
+                    .map(|turn| format!(", stops matching at move {turn}"))
+                    .unwrap_or_default(),
+                out.file_name().unwrap_or_default().to_string_lossy()
+            ),
+            Err(e) => format!("{name}: skipped, {e}\n"),
+        };
+    }
+    Ok(report)
+}
+
+fn annotate(exported: &Exported, rollouts: u32) -> Annotated {
+    let mut game = exported.deal();
+    let mut moves: Vec<String> = Vec::new();
+    let mut decisions = 0;
+    let mut blunders = 0;
+    let mut stopped = None;
+
+    for (i, &action) in exported.moves.iter().enumerate() {
+        let turn = i + 1;
+        if game.illegal_reason(action).is_some() {
+            stopped = Some(turn);
+            break;
+        }
+        let played = command::describe_action(action, &game);
+        let line = if game.legal_actions().len() > 1 {
+            decisions += 1;
+            let estimates = solver::analyze(&game, rollouts);
+            let (mark, comment) = judge(&game, action, &estimates);
+            if mark == "??" {
+                blunders += 1;
+            }
+            format!("{turn}. {}{mark} {{{played}: {comment}}}", action.code())
+        } else {
+            format!("{turn}. {} {{{played}}}", action.code())
+        };
+        moves.push(line);
+        game.apply(action);
+        game.settle_death();
+    }
+
+    let result = match stopped {
+        Some(_) => "*".to_string(),
+        None if game.state != GameState::GameOver => "unfinished".to_string(),
+        None if game.survived => format!("survived, score {}", game.final_score()),
+        None => format!("died, score {}", game.final_score()),
+    };
+    let tags = [
+        ("Seed", exported.seed.to_string()),
+        ("Date", exported.date.clone()),
+        ("Rules", exported.rules.summary()),
+        ("Result", result),
+        ("Decisions", decisions.to_string()),
+        ("Blunders", blunders.to_string()),
+        ("Rollouts", rollouts.to_string()),
+    ];
+    let mut text: String = tags
+        .iter()
+        .map(|(k, v)| format!("[{k} \"{}\"]\n", v.replace('"', "'")))
+        .collect();
+    text += "\n";
+    for line in &moves {
+        text += line;
+        text += "\n";
+    }
+    if let Some(turn) = stopped {
+        text += &format!(
+            "{{The replay stops matching the export at move {turn}, so the rest isn't annotated.}}\n"
+        );
+    }
+    Annotated {
+        text,
+        decisions,
+        blunders,
+        stopped,
+    }
+}
+
+/// How `action` measures up against the best of `estimates`: its mark (`""`, `?` or
+/// `??`) and the comment that goes with it
+fn judge(game: &Game, action: Action, estimates: &[Estimate]) -> (&'static str, String) {
+    let (Some(best), Some(played)) = (
+        estimates.first(),
+        estimates.iter().find(|e| e.action == action),
+    ) else {
+        return ("", String::new());
+    };
+    if best.action == action
+        || (best.permille(), best.mean_score()) == (played.permille(), played.mean_score())
+    {
+        return ("", format!("{}, the best", played.summary()));
+    }
+    let blunder = best.permille() >= played.permille() + BLUNDER_PERMILLE
+        || (best.permille() == played.permille()
+            && best.mean_score() >= played.mean_score() + BLUNDER_SCORE);
+    (
+        if blunder { "??" } else { "?" },
+        format!(
+            "{}. Best was {}: {}",
+            played.summary(),
+            command::describe_action(best.action, game),
+            best.summary()
+        ),
+    )
+}
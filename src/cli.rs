@@ -0,0 +1,202 @@
+//! Command-line flags
+//!
+//! Flags override `config.toml`, so most of them are `Option`s applied on top of it.
+
+use std::env;
+use std::path::PathBuf;
+
+use crate::config::Config;
+use crate::pacing::LowPower;
+use crate::rules::KNOBS;
+use crate::solver;
+use crate::theme::{self, Theme};
+use crate::ui::QuitMode;
+
+const USAGE: &str = "\
+Usage: scoundrel [OPTIONS]
+
+Options:
+  --portable          Keep config, saves and logs in a folder beside the binary
+  --paths             Print where config, saves and logs are stored, then exit
+  --rule KEY=VALUE    Play a variant rule (repeatable), e.g. --rule equal-fight=chip
+  --theme NAME        Color theme (default, high-contrast)
+  --quit MODE         What Ctrl+Q does mid-run: instant, confirm (default) or save
+  --solid-bg          Paint panel backgrounds (for translucent/image terminal backgrounds)
+  --streamer          Streamer mode: hide the seed and tooltips, add a panel for viewers
+  --speedrun          Time the run with room splits, and keep best times per seed
+  --low-power         Lower the frame rate and sleep while idle (automatic on battery)
+  --seed N            Deal the first run from seed N, to replay or share a dungeon
+  --script FILE       Play an input script headless and print each frame (for bug reports)
+  --annotate DIR      Mark the best move and blunders in every run exported into DIR
+  --rollouts N        Games the solver plays per move for --annotate (default 20)
+  --observe FILE      Keep FILE up to date with the game's state (for overlays and bots)
+  --broadcast ADDR    Send the game's state to viewers connecting to ADDR, e.g. 0.0.0.0:7878
+  --mirror ADDR       Show a read-only copy of a game started with --broadcast ADDR
+  -h, --help          Show this help
+
+Rules:";
+
+#[derive(Clone, Debug, Default)]
+pub struct Args {
+    pub portable: bool,
+    pub print_paths: bool,
+    pub help: bool,
+    /// `--rule` pairs, checked when applied so the error points at the flag
+    pub rules: Vec<(String, String)>,
+    pub theme: Option<Theme>,
+    pub solid_backgrounds: bool,
+    pub streamer_mode: bool,
+    pub speedrun: bool,
+    pub low_power: bool,
+    pub quit_mode: Option<QuitMode>,
+    pub seed: Option<u64>,
+    pub script: Option<PathBuf>,
+    pub annotate: Option<PathBuf>,
+    pub rollouts: Option<u32>,
+    pub observe: Option<PathBuf>,
+    pub broadcast: Option<String>,
+    pub mirror: Option<String>,
+    /// `--bisect BOT WHEN`
+    #[cfg(feature = "dev")]
+    pub bisect: Option<(String, String)>,
+}
+
+impl Args {
+    pub fn from_env() -> Result<Self, String> {
+        Self::parse(env::args().skip(1))
+    }
+
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut out = Self::default();
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--portable" => out.portable = true,
+                "--paths" => out.print_paths = true,
+                "--rule" => {
+                    let pair = args.next().ok_or("--rule needs KEY=VALUE")?;
+                    let (key, value) = pair
+                        .split_once('=')
+                        .ok_or_else(|| format!("--rule expects KEY=VALUE, got '{pair}'"))?;
+                    out.rules
+                        .push((key.trim().to_string(), value.trim().to_string()));
+                }
+                "--theme" => {
+                    let name = args.next().ok_or("--theme needs a NAME")?;
+                    let t = theme::by_name(&name).ok_or_else(|| {
+                        format!("unknown theme '{name}' (known: {})", theme::names())
+                    })?;
+                    out.theme = Some(t.clone());
+                }
+                "--quit" => {
+                    let mode = args.next().ok_or("--quit needs a MODE")?;
+                    let q = QuitMode::parse(&mode).ok_or_else(|| {
+                        format!("bad quit mode '{mode}' (expected {})", QuitMode::VALUES)
+                    })?;
+                    out.quit_mode = Some(q);
+                }
+                "--solid-bg" => out.solid_backgrounds = true,
+                "--streamer" => out.streamer_mode = true,
+                "--speedrun" => out.speedrun = true,
+                "--low-power" => out.low_power = true,
+                "--seed" => {
+                    let seed = args.next().ok_or("--seed needs a number")?;
+                    out.seed = Some(
+                        seed.parse()
+                            .map_err(|_| format!("bad seed '{seed}' (expected a whole number)"))?,
+                    );
+                }
+                "--script" => {
+                    let path = args.next().ok_or("--script needs a FILE")?;
+                    out.script = Some(PathBuf::from(path));
+                }
+                "--annotate" => {
+                    let dir = args.next().ok_or("--annotate needs a DIR")?;
+                    out.annotate = Some(PathBuf::from(dir));
+                }
+                "--rollouts" => {
+                    let n = args.next().ok_or("--rollouts needs a number")?;
+                    out.rollouts = Some(
+                        n.parse()
+                            .ok()
+                            .filter(|n| (1..=solver::ROLLOUTS_MAX).contains(n))
+                            .ok_or_else(|| {
+                                format!(
+                                    "--rollouts expects 1 to {}, got '{n}'",
+                                    solver::ROLLOUTS_MAX
+                                )
+                            })?,
+                    );
+                }
+                "--observe" => {
+                    let path = args.next().ok_or("--observe needs a FILE")?;
+                    out.observe = Some(PathBuf::from(path));
+                }
+                "--broadcast" => {
+                    out.broadcast = Some(args.next().ok_or("--broadcast needs an ADDR")?);
+                }
+                "--mirror" => {
+                    out.mirror = Some(args.next().ok_or("--mirror needs an ADDR")?);
+                }
+                #[cfg(feature = "dev")]
+                "--bisect" => {
+                    let bot = args.next().ok_or("--bisect needs a BOT and WHEN")?;
+                    let when = args.next().ok_or("--bisect needs a BOT and WHEN")?;
+                    out.bisect = Some((bot, when));
+                }
+                "-h" | "--help" => out.help = true,
+                other => return Err(format!("unrecognized argument: {other}")),
+            }
+        }
+
+        Ok(out)
+    }
+
+    /// Layer the flags over settings loaded from the config file
+    pub fn apply(&self, config: &mut Config) -> Result<(), String> {
+        for (key, value) in &self.rules {
+            config.rules.set(key, value)?;
+        }
+        if let Some(theme) = &self.theme {
+            config.theme = theme.clone();
+        }
+        if self.solid_backgrounds {
+            config.solid_backgrounds = true;
+        }
+        if self.streamer_mode {
+            config.streamer_mode = true;
+        }
+        if self.speedrun {
+            config.speedrun = true;
+        }
+        if self.low_power {
+            config.low_power = LowPower::On;
+        }
+        if let Some(mode) = self.quit_mode {
+            config.quit_mode = mode;
+        }
+        if self.seed.is_some() {
+            config.seed = self.seed;
+        }
+        Ok(())
+    }
+}
+
+/// Full help text, including every rule knob and its accepted values
+pub fn usage() -> String {
+    let mut out = USAGE.to_string();
+    for knob in KNOBS {
+        out.push_str(&format!("\n  {:<18}  {}", knob.key, knob.values));
+    }
+    #[cfg(feature = "dev")]
+    out.push_str(&format!(
+        "\n\nDeveloper options:\n  \
+         --bisect BOT WHEN   Find the first seed (from --seed, or 0) where BOT's game matches WHEN\n\
+         {:<22}and print its actions. BOT: {}; WHEN: {}",
+        "",
+        crate::dev::BOTS,
+        crate::dev::PREDICATES
+    ));
+    out
+}
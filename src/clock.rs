@@ -0,0 +1,99 @@
+//! Wall clock
+//!
+//! Anything that stamps a time (the event log and whatever reads it back) asks here,
+//! so every timestamp in a run comes from the same source and unit.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Milliseconds since the Unix epoch (0 if the system clock is set before 1970)
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Time actually spent playing a run. It only runs while the UI says the run is in front
+/// of the player (`AppState::clock_counts`), so menus and dialogs don't eat into it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PlayClock {
+    /// Time from before the last pause
+    banked_ms: u64,
+    /// `now_ms` when it last started running; `None` while paused
+    running_since: Option<u64>,
+}
+
+impl PlayClock {
+    /// A paused clock showing `ms`, e.g. for a loaded run
+    pub fn paused_at(ms: u64) -> Self {
+        Self {
+            banked_ms: ms,
+            running_since: None,
+        }
+    }
+
+    pub fn elapsed_ms(&self, now: u64) -> u64 {
+        self.banked_ms + self.running_since.map_or(0, |t| now.saturating_sub(t))
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running_since.is_some()
+    }
+
+    /// Start or stop the clock; does nothing if it's already that way
+    pub fn set_running(&mut self, running: bool, now: u64) {
+        match (running, self.running_since) {
+            (true, None) => self.running_since = Some(now),
+            (false, Some(_)) => *self = Self::paused_at(self.elapsed_ms(now)),
+            _ => {}
+        }
+    }
+}
+
+/// Start of a `YYYY-MM-DD` day (UTC) as a `now_ms` timestamp; the inverse of `date`
+pub fn parse_date(text: &str) -> Option<u64> {
+    let mut parts = text.splitn(3, '-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: i64 = parts
+        .next()?
+        .parse()
+        .ok()
+        .filter(|m| (1..=12).contains(m))?;
+    let day: i64 = parts
+        .next()?
+        .parse()
+        .ok()
+        .filter(|d| (1..=31).contains(d))?;
+    // Howard Hinnant's days-from-civil algorithm
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+    u64::try_from(days).ok().map(|d| d * 86_400_000)
+}
+
+/// Calendar date (UTC) of a `now_ms` timestamp, e.g. `2026-10-16`. Screens show dates
+/// through `locale::date` instead; this is the form files and typed dates use.
+pub fn date(ms: u64) -> String {
+    let (year, month, day) = civil(ms);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// `(year, month, day)` (UTC) of a `now_ms` timestamp
+pub fn civil(ms: u64) -> (i64, i64, i64) {
+    // Howard Hinnant's days-to-civil algorithm
+    let days = (ms / 86_400_000) as i64;
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
@@ -0,0 +1,83 @@
+//! Score pace coach
+//!
+//! A run started with `target N` gets a word at every room choice: where the run is headed
+//! next to the score the player is after, and whether this room can be faced or is better
+//! skipped. The projection is rough on purpose: this room's monsters at what they'd cost
+//! right now (see `advisor::damage`), its best potion, and every potion still to come as
+//! the most you could end on.
+
+use crate::advisor;
+use crate::logic::{Game, GameState};
+
+/// What the coach says about the room on the table, if the run has a target
+pub fn nudge(game: &Game) -> Option<String> {
+    let target = game.target?;
+    if game.state != GameState::RoomChoice {
+        return None;
+    }
+
+    // Only `room-interactions` cards get played, so the worst of the rest can wait. A
+    // face-down card is left out, unknown as it is.
+    let room: Vec<_> = (0..4).filter_map(|i| game.visible_card(i)).collect();
+    let mut costs: Vec<i32> = room
+        .iter()
+        .filter(|c| c.is_monster())
+        .map(|&c| advisor::damage(game, c))
+        .collect();
+    costs.sort_unstable();
+    let left_behind = room
+        .len()
+        .saturating_sub(usize::from(game.rules.room_interactions));
+    let cost: i32 = costs[..costs.len().saturating_sub(left_behind)]
+        .iter()
+        .sum();
+    let heal = room
+        .iter()
+        .filter(|c| c.suit == 'H')
+        .map(|c| i32::from(c.value))
+        .max()
+        .unwrap_or(0);
+    let after = (game.health - cost + heal).min(game.max_health);
+
+    let ceiling = best_case(game);
+    let skip = game.skip_allowed();
+    Some(if ceiling < target {
+        format!("Coach: {target} is out of reach now (best case {ceiling}). Play to survive.")
+    } else if after < target && skip && cost > 0 {
+        format!(
+            "Coach: this room could cost {cost} and leave you at {after}, under {target}. A skip keeps you on pace."
+        )
+    } else if after < target {
+        format!(
+            "Coach: this room could leave you at {after}, under {target}. Make every weapon count."
+        )
+    } else if skip {
+        format!(
+            "Coach: about {after} after this room, {} over {target}. You can afford to face it and keep the skip.",
+            after - target
+        )
+    } else {
+        format!("Coach: about {after} after this room against {target}. On pace.")
+    })
+}
+
+/// The most the run could still end on: every potion left drunk, up to max health, plus
+/// the biggest one again under `potion-bonus`
+fn best_case(game: &Game) -> i32 {
+    let potions: Vec<i32> = game
+        .room_slots
+        .iter()
+        .flatten()
+        .chain(game.deck.iter())
+        .chain(game.floors_ahead.iter().flatten())
+        .filter(|c| c.suit == 'H')
+        .map(|c| i32::from(c.value))
+        .collect();
+    let healed = (game.health + potions.iter().sum::<i32>()).min(game.max_health);
+    let bonus = if game.rules.potion_bonus {
+        potions.iter().copied().max().unwrap_or(0)
+    } else {
+        0
+    };
+    healed + bonus
+}
@@ -0,0 +1,774 @@
+//! Typed command parsing
+//!
+//! Input is tokenized, then interpreted against the current `GameState`, since the same word
+//! can mean different things on different screens (`s` is "start" on the menu and "skip" in
+//! a room). `submit_command` and the live preview under the input box both go through
+//! `parse`, so what the preview promises is exactly what Enter does.
+
+use crate::logic::{self, Action, Card, Game, GameState, Mutator, Relic, Ware};
+use crate::render::{card_face, card_text};
+use crate::scores::{self, Query};
+use crate::sim;
+use crate::solver;
+use crate::text;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Command {
+    /// An in-dungeon decision handled by `Game::apply`
+    Game(Action),
+
+    Start,
+    /// Start a marathon session from the main menu
+    Marathon,
+    /// Open the custom game screen
+    Custom,
+    /// Open the sandbox, to set up a position by hand
+    Sandbox,
+    /// Show this week's challenge briefing
+    Weekly,
+    /// Start a run under the relaxed casual rules
+    Casual,
+    /// Turn the main menu's difficulty advice off for good
+    Dismiss,
+    /// Start a run that deals a new lap whenever the dungeon is cleared
+    Endless,
+    /// Start a run with no saves, loads or retries
+    Ironman,
+    /// Start a run on a rung of the ascension ladder, the highest unlocked unless given
+    Ascend(Option<u32>),
+    /// After a win, a fresh dungeon carrying this relic (or what's in hand) forward
+    Plus(Option<Relic>),
+    /// Start a run with mutators, named or as a friend's code; none lists them
+    Mutators(Vec<String>),
+    /// Start a run aiming for this score, with the pace coach on
+    Target(i32),
+    /// End the marathon session in progress
+    Stop,
+    /// Watch bundled example game `n` (0-based)
+    Watch(usize),
+    /// Answer to the launch-time "Resume previous run?" prompt
+    Resume(bool),
+    /// Ask before banishing the card in room slot `n` (0-based)
+    Banish(usize),
+    /// Ask before hammering both weapons into one in the shop
+    Forge,
+
+    Exit,
+    Restart,
+    /// Deal the same dungeon again, same seed and rules
+    Retry,
+    Save,
+    Load,
+    Contrast,
+    Scores,
+    /// Show the score table for just the runs asked for
+    ScoresFor(Query),
+    /// Tag the run that just ended, or clear its tag
+    Tag(Option<String>),
+    Achievements,
+    /// Write the finished run to a JSON file
+    Export,
+    /// Copy a chat-friendly result block
+    Share,
+    /// Show this run's seed, or deal a new run from the given one
+    Seed(Option<u64>),
+    /// Look at the discard pile, as far as `discard-view` allows
+    Discard,
+    /// Look in the pack (`inventory` rule)
+    Inventory,
+    /// Recommend a move for the room on the table, and say why
+    Hint,
+    /// Play every legal move out this many times in the background and report the odds
+    Analyze(u32),
+    /// Show or hide the notes panel
+    Notes,
+    /// Show or hide the threat / pickup ranks on room cards
+    Ranks,
+    /// Show or hide the HP change of picking each room card
+    Preview,
+    /// Play this many bot games in the background
+    Simulate(u32),
+    /// Show or hide the move log
+    Log,
+    /// Show how much space saves, scores and exports take up
+    Storage,
+    /// Delete old exports now, down to what `[storage]` keeps
+    Prune,
+}
+
+/// Lowercased, whitespace-separated words, accents joined onto their letters
+pub fn tokenize(input: &str) -> Vec<String> {
+    input
+        .split_whitespace()
+        .map(|w| text::compose(&w.to_lowercase()))
+        .collect()
+}
+
+/// Interpret `input` for the current state, in the player's vocabulary. `None` means it
+/// isn't a command here.
+pub fn parse(input: &str, game: &Game, vocab: &Vocabulary) -> Option<Command> {
+    let tokens = tokenize(input);
+    let words: Vec<&str> = tokens.iter().map(String::as_str).collect();
+    let is = |verb: Verb, word: &str| vocab.means(verb, word);
+
+    // Always available, whatever the screen
+    let global = match words.as_slice() {
+        [w] if is(Verb::Exit, w) => Some(Command::Exit),
+        [w] if is(Verb::Restart, w) => Some(Command::Restart),
+        ["save"] => Some(Command::Save),
+        ["load"] => Some(Command::Load),
+        ["contrast"] => Some(Command::Contrast),
+        ["scores"] => Some(Command::Scores),
+        ["scores", rest @ ..] => Query::parse(rest).map(Command::ScoresFor),
+        ["achievements"] => Some(Command::Achievements),
+        ["stop"] => Some(Command::Stop),
+        ["discard" | "graveyard"] => Some(Command::Discard),
+        ["notes"] => Some(Command::Notes),
+        ["ranks"] => Some(Command::Ranks),
+        ["preview"] => Some(Command::Preview),
+        ["log"] => Some(Command::Log),
+        ["storage"] => Some(Command::Storage),
+        ["storage", "prune"] => Some(Command::Prune),
+        ["simulate"] => Some(Command::Simulate(sim::BATCH_GAMES)),
+        ["simulate", n] => n
+            .parse()
+            .ok()
+            .filter(|n| (1..=sim::BATCH_MAX).contains(n))
+            .map(Command::Simulate),
+        ["seed"] => Some(Command::Seed(None)),
+        ["seed", n] => n.parse().ok().map(|n| Command::Seed(Some(n))),
+        _ => None,
+    };
+    if global.is_some() {
+        return global;
+    }
+
+    match (game.state, words.as_slice()) {
+        (GameState::MainMenu, [w]) if is(Verb::Start, w) => Some(Command::Start),
+        (GameState::MainMenu, ["marathon"]) => Some(Command::Marathon),
+        (GameState::MainMenu, ["custom"]) => Some(Command::Custom),
+        (GameState::MainMenu, ["sandbox"]) => Some(Command::Sandbox),
+        (GameState::MainMenu, ["weekly"]) => Some(Command::Weekly),
+        (GameState::MainMenu, ["endless"]) => Some(Command::Endless),
+        (GameState::MainMenu, ["ironman"]) => Some(Command::Ironman),
+        (GameState::MainMenu, ["ascend"]) => Some(Command::Ascend(None)),
+        (GameState::MainMenu, ["mutators", rest @ ..]) => Some(Command::Mutators(
+            rest.iter().map(|w| w.to_string()).collect(),
+        )),
+        (GameState::MainMenu, ["ascend", n]) => n
+            .parse()
+            .ok()
+            .filter(|n| (1..=logic::ASCENSIONS.len() as u32).contains(n))
+            .map(|n| Command::Ascend(Some(n))),
+        (GameState::MainMenu, ["target", n]) => n.parse().ok().map(Command::Target),
+        (GameState::MainMenu, ["casual"]) => Some(Command::Casual),
+        (GameState::MainMenu, ["dismiss"]) => Some(Command::Dismiss),
+        (GameState::MainMenu, ["watch"]) => Some(Command::Watch(0)),
+        (GameState::MainMenu, ["watch", n]) => match n.parse::<usize>() {
+            Ok(n) if n >= 1 => Some(Command::Watch(n - 1)),
+            _ => None,
+        },
+
+        (GameState::GameOver, ["retry"]) => Some(Command::Retry),
+        (GameState::GameOver, ["plus"]) => Some(Command::Plus(None)),
+        (GameState::GameOver, ["plus", "armor"]) => Some(Command::Plus(Some(Relic::Armor))),
+        (GameState::GameOver, ["plus", "1"]) => Some(Command::Plus(Some(Relic::Weapon(0)))),
+        (GameState::GameOver, ["plus", "2"]) => Some(Command::Plus(Some(Relic::Weapon(1)))),
+        (_, ["retry"]) if game.in_progress() => Some(Command::Retry),
+        (_, ["inv"]) if game.in_progress() => Some(Command::Inventory),
+        (_, ["hint"]) if game.in_progress() => Some(Command::Hint),
+        (_, ["analyze"]) if game.in_progress() => Some(Command::Analyze(solver::ROLLOUTS)),
+        (_, ["analyze", n]) if game.in_progress() => n
+            .parse()
+            .ok()
+            .filter(|n| (1..=solver::ROLLOUTS_MAX).contains(n))
+            .map(Command::Analyze),
+        (_, ["inv", n]) if game.in_progress() => match n.parse::<usize>() {
+            Ok(n) if n >= 1 => Some(Command::Game(Action::Stash(n - 1))),
+            _ => None,
+        },
+        (_, ["use", n]) if game.in_progress() => match n.parse::<usize>() {
+            Ok(n) if n >= 1 => Some(Command::Game(Action::Use(n - 1))),
+            _ => None,
+        },
+        (GameState::GameOver, ["tag", "none"]) => Some(Command::Tag(None)),
+        (GameState::GameOver, ["tag", t]) if scores::valid_tag(t) => {
+            Some(Command::Tag(Some(t.to_string())))
+        }
+        (GameState::GameOver, ["export"]) => Some(Command::Export),
+        (GameState::GameOver, ["share"]) => Some(Command::Share),
+
+        (GameState::ResumePrompt, [w]) if is(Verb::Yes, w) => Some(Command::Resume(true)),
+        (GameState::ResumePrompt, [w]) if is(Verb::No, w) => Some(Command::Resume(false)),
+
+        (GameState::RoomChoice, [w]) if is(Verb::Face, w) => Some(Command::Game(Action::Face)),
+        (GameState::RoomChoice, [w]) if is(Verb::Skip, w) => Some(Command::Game(Action::Skip)),
+        (GameState::RoomChoice, [w]) if is(Verb::Reshuffle, w) => {
+            Some(Command::Game(Action::Reshuffle))
+        }
+        (GameState::RoomChoice, ["mulligan"]) => Some(Command::Game(Action::Mulligan)),
+
+        (GameState::CardSelection, ["banish", n]) => match n.parse::<usize>() {
+            Ok(n) if n >= 1 => Some(Command::Banish(n - 1)),
+            _ => None,
+        },
+        (GameState::CardSelection, [n]) => match n.parse::<usize>() {
+            Ok(n) if n >= 1 => Some(Command::Game(Action::Play(n - 1))),
+            _ => None,
+        },
+
+        (GameState::CardInteraction, [w]) if game.awaiting_weapon_choice && is(Verb::Yes, w) => {
+            Some(Command::Game(game.weapon_answer()))
+        }
+        (GameState::CardInteraction, [w]) if game.awaiting_weapon_choice && is(Verb::No, w) => {
+            Some(Command::Game(Action::Fight { weapon: None }))
+        }
+        // `dual-wield`: which hand to fight with
+        (GameState::CardInteraction, [n @ ("1" | "2")])
+            if game.awaiting_weapon_choice && game.rules.dual_wield =>
+        {
+            let hand = if *n == "1" { 0 } else { 1 };
+            Some(Command::Game(Action::Fight { weapon: Some(hand) }))
+        }
+        (GameState::CardInteraction, [w])
+            if !game.awaiting_weapon_choice && is(Verb::Continue, w) =>
+        {
+            Some(Command::Game(Action::Continue))
+        }
+
+        (GameState::Trap, [w]) if is(Verb::Continue, w) => Some(Command::Game(Action::Continue)),
+
+        (GameState::Shop, ["leave"]) => Some(Command::Game(Action::Leave)),
+        (GameState::Shop, ["forge"] | ["buy", "forge"]) => Some(Command::Forge),
+        (GameState::Shop, [w]) if is(Verb::Continue, w) => Some(Command::Game(Action::Leave)),
+        (GameState::Shop, [w] | ["buy", w]) => {
+            Ware::from_code(w).map(|w| Command::Game(Action::Buy(w)))
+        }
+
+        _ => None,
+    }
+}
+
+/// Short imperative description, e.g. `play card 3 (9󱢱)`
+pub fn describe(command: Command, game: &Game) -> String {
+    match command {
+        Command::Game(action) => describe_action(action, game),
+        Command::Start => "enter the dungeon".to_string(),
+        Command::Marathon => "start a marathon: runs back to back, scores add up".to_string(),
+        Command::Stop => "end the marathon and see the summary".to_string(),
+        Command::Custom => "build a custom game from the variant rules".to_string(),
+        Command::Sandbox => "set up a position by hand and practice it".to_string(),
+        Command::Weekly => "see this week's challenge".to_string(),
+        Command::Casual => {
+            "enter a gentler dungeon: more potions, and rest between rooms".to_string()
+        }
+        Command::Dismiss => "stop the difficulty tips on the main menu".to_string(),
+        Command::Endless => "enter an endless dungeon: survive as many laps as you can".to_string(),
+        Command::Target(n) => format!("enter the dungeon aiming for a score of {n}, with a coach"),
+        Command::Ironman => "enter the dungeon for keeps: no saves, loads or retries".to_string(),
+        Command::Ascend(None) => {
+            "climb the ascension ladder from the highest rung unlocked".to_string()
+        }
+        Command::Ascend(Some(n)) => format!("enter the dungeon at ascension {n}"),
+        Command::Mutators(words) if words.is_empty() => "list the mutators".to_string(),
+        Command::Mutators(words) => match words.as_slice() {
+            [code] if Mutator::from_code(code).is_none() => {
+                format!(
+                    "enter the dungeon with the mutators in code {}",
+                    code.to_uppercase()
+                )
+            }
+            names => format!("enter the dungeon with {}", names.join(", ")),
+        },
+        Command::Watch(n) => format!("watch example game {}", n + 1),
+        Command::Banish(i) => describe_action(Action::Banish(i), game),
+        Command::Forge => describe_action(Action::Buy(Ware::Forge), game),
+        Command::Resume(true) => "resume your previous run".to_string(),
+        Command::Resume(false) => "discard your previous run".to_string(),
+        Command::Exit if game.in_progress() => "quit (the run is autosaved)".to_string(),
+        Command::Exit => "quit".to_string(),
+        Command::Restart => "start over with a new dungeon".to_string(),
+        Command::Retry => "start this same dungeon over".to_string(),
+        Command::Plus(None) => "go on to New Game+ with what's in hand".to_string(),
+        Command::Plus(Some(Relic::Armor)) => "go on to New Game+ with your armor".to_string(),
+        Command::Plus(Some(Relic::Weapon(hand))) => {
+            format!("go on to New Game+ with the weapon in hand {}", hand + 1)
+        }
+        Command::Save => "save this run".to_string(),
+        Command::Load => "load your saved run".to_string(),
+        Command::Contrast => "toggle the contrast audit".to_string(),
+        Command::Scores => "toggle the high score table".to_string(),
+        Command::ScoresFor(query) if query.is_empty() => "show the scores of every run".to_string(),
+        Command::ScoresFor(query) => format!("show the scores of runs: {}", query.describe()),
+        Command::Tag(Some(tag)) => format!("tag this run '{tag}'"),
+        Command::Tag(None) => "take this run's tag off".to_string(),
+        Command::Achievements => "show how far along your achievements are".to_string(),
+        Command::Discard => "look at the discard pile".to_string(),
+        Command::Inventory => "look in your pack".to_string(),
+        Command::Hint => "suggest a move".to_string(),
+        Command::Analyze(n) => format!("play every move out {n} times and compare the odds"),
+        Command::Notes => "show or hide your notes (Tab switches to them)".to_string(),
+        Command::Ranks => "show or hide which room cards hurt most and help most".to_string(),
+        Command::Preview => "show or hide what picking each room card does to your HP".to_string(),
+        Command::Log => "show every card played and room skipped this run".to_string(),
+        Command::Storage => "show how much space your saves and exports take up".to_string(),
+        Command::Prune => "delete old exports, down to what the config keeps".to_string(),
+        Command::Simulate(n) => format!("simulate {n} bot games of your rules in the background"),
+        Command::Seed(None) => "show this dungeon's seed".to_string(),
+        Command::Seed(Some(n)) => format!("deal a new dungeon from seed {n}"),
+        Command::Export => "write this run's summary to a JSON file".to_string(),
+        Command::Share => "copy a shareable result to the clipboard".to_string(),
+    }
+}
+
+pub fn describe_action(action: Action, game: &Game) -> String {
+    match action {
+        Action::Face => "face the room".to_string(),
+        Action::Skip if game.rules.treasure_score => match game.treasures_in_room().as_slice() {
+            [] => "skip the room".to_string(),
+            left => {
+                let cards: Vec<String> = left.iter().map(|&c| card_text(c)).collect();
+                format!("skip the room, leaving {} behind", cards.join(" "))
+            }
+        },
+        Action::Skip => "skip the room".to_string(),
+        Action::Reshuffle => "reshuffle the room into the dungeon".to_string(),
+        Action::Mulligan => "take a mulligan on the opening room".to_string(),
+        Action::Banish(i) if game.is_hidden(i) => {
+            format!("banish card {} (face down) from the game", i + 1)
+        }
+        Action::Banish(i) if game.is_dark(i) => match game.room_slots.get(i).copied().flatten() {
+            Some(card) => format!(
+                "banish card {} ({}, dark) from the game",
+                i + 1,
+                card_face(card, false)
+            ),
+            None => format!("banish card {} from the game", i + 1),
+        },
+        Action::Banish(i) => match game.room_slots.get(i).copied().flatten() {
+            Some(card) => format!("banish card {} ({}) from the game", i + 1, card_text(card)),
+            None => format!("banish card {} from the game", i + 1),
+        },
+        Action::Play(i) if game.is_hidden(i) => format!("play card {} (face down)", i + 1),
+        Action::Play(i) if game.is_dark(i) => match game.room_slots.get(i).copied().flatten() {
+            Some(card) => format!("play card {} ({}, dark)", i + 1, card_face(card, false)),
+            None => format!("play card {}", i + 1),
+        },
+        Action::Play(i) => match game.room_slots.get(i).copied().flatten() {
+            Some(card) => format!("play card {} ({})", i + 1, card_text(card)),
+            None => format!("play card {}", i + 1),
+        },
+        Action::Stash(i) => match game.visible_card(i) {
+            Some(card) => format!("stash card {} ({}) in your pack", i + 1, card_text(card)),
+            None => format!("stash card {} in your pack", i + 1),
+        },
+        Action::Use(i) => match game.inventory.get(i) {
+            Some(&card) => format!("use {} from your pack", card_text(card)),
+            None => format!("use card {} from your pack", i + 1),
+        },
+        Action::Fight { weapon: Some(hand) } => {
+            match game.loadout.hands.get(hand).copied().flatten() {
+                Some(w) if game.rules.dual_wield => format!("fight with {}", card_text(w.card)),
+                _ => "fight with your weapon".to_string(),
+            }
+        }
+        Action::Fight { weapon: None } => "fight bare-handed".to_string(),
+        Action::Continue => "continue".to_string(),
+        Action::Buy(ware) => format!("buy: {} ({} gold)", ware.description(), ware.price()),
+        Action::Leave => "leave the shop".to_string(),
+    }
+}
+
+/// Live interpretation of half-typed input, shown under the command box before Enter
+pub fn preview(input: &str, game: &Game, vocab: &Vocabulary) -> Option<String> {
+    if input.trim().is_empty() {
+        return None;
+    }
+
+    let line = match parse(input, game, vocab) {
+        Some(Command::Game(action)) => match game.illegal_reason(action) {
+            None => format!("→ {}", describe_action(action, game)),
+            Some(reason) => format!("→ {} ✗ {reason}", describe_action(action, game)),
+        },
+        Some(command) => format!("→ {}", describe(command, game)),
+        None => "→ unknown command".to_string(),
+    };
+    Some(line)
+}
+
+// ==============================
+// Vocabulary
+// ==============================
+
+/// The commands whose words can be changed under `[commands]` in config.toml. Card
+/// numbers and the rest of the commands stay as they are.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verb {
+    Start,
+    Face,
+    Skip,
+    Reshuffle,
+    /// Fight with the weapon, or resume the previous run
+    Yes,
+    /// Fight bare-handed, or discard the previous run
+    No,
+    Continue,
+    Restart,
+    Exit,
+}
+
+impl Verb {
+    pub const ALL: [Verb; 9] = [
+        Verb::Start,
+        Verb::Face,
+        Verb::Skip,
+        Verb::Reshuffle,
+        Verb::Yes,
+        Verb::No,
+        Verb::Continue,
+        Verb::Restart,
+        Verb::Exit,
+    ];
+
+    /// Its key under `[commands]`
+    pub fn name(self) -> &'static str {
+        match self {
+            Verb::Start => "start",
+            Verb::Face => "face",
+            Verb::Skip => "skip",
+            Verb::Reshuffle => "reshuffle",
+            Verb::Yes => "yes",
+            Verb::No => "no",
+            Verb::Continue => "continue",
+            Verb::Restart => "restart",
+            Verb::Exit => "exit",
+        }
+    }
+
+    fn by_name(name: &str) -> Option<Verb> {
+        Verb::ALL.into_iter().find(|v| v.name() == name)
+    }
+}
+
+/// Verbs that can be typed on the same screen, so mustn't share a word. `restart` and
+/// `exit` work everywhere and are added to each.
+const SCREENS: &[&[Verb]] = &[
+    &[Verb::Start],
+    &[Verb::Yes, Verb::No],
+    &[Verb::Face, Verb::Skip, Verb::Reshuffle],
+    &[Verb::Yes, Verb::No, Verb::Continue],
+];
+
+/// Fixed commands a verb can't take the word of
+const RESERVED: &[&str] = &[
+    "save",
+    "load",
+    "contrast",
+    "scores",
+    "achievements",
+    "stop",
+    "discard",
+    "graveyard",
+    "notes",
+    "ranks",
+    "preview",
+    "log",
+    "storage",
+    "simulate",
+    "seed",
+    "marathon",
+    "custom",
+    "sandbox",
+    "weekly",
+    "casual",
+    "dismiss",
+    "endless",
+    "ironman",
+    "ascend",
+    "mutators",
+    "target",
+    "mulligan",
+    "banish",
+    "watch",
+    "export",
+    "share",
+    "retry",
+    "plus",
+    "tag",
+    "buy",
+    "heal",
+    "repair",
+    "token",
+    "forge",
+    "leave",
+    "inv",
+    "use",
+    "hint",
+    "analyze",
+];
+
+/// The words each verb answers to, in `Verb::ALL` order. The first is the one hints show.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Vocabulary {
+    words: [Vec<String>; 9],
+}
+
+/// Built-in vocabularies, in `Verb::ALL` order
+const STANDARD: [&[&str]; 9] = [
+    &["start", "s"],
+    &["f", "face"],
+    &["s", "skip"],
+    &["r", "reshuffle"],
+    &["y"],
+    &["n"],
+    &["ok"],
+    &["restart"],
+    &["exit", "quit"],
+];
+const LETTERS: [&[&str]; 9] = [
+    &["s"],
+    &["f"],
+    &["s"],
+    &["r"],
+    &["y"],
+    &["n"],
+    &["c"],
+    &["restart"],
+    &["exit", "quit"],
+];
+const WORDS: [&[&str]; 9] = [
+    &["start"],
+    &["face"],
+    &["skip"],
+    &["reshuffle"],
+    &["yes"],
+    &["no"],
+    &["continue"],
+    &["restart"],
+    &["exit", "quit"],
+];
+
+impl Default for Vocabulary {
+    fn default() -> Self {
+        Self::from_lists(STANDARD)
+    }
+}
+
+impl Vocabulary {
+    pub const STYLES: &str = "standard | letters | words";
+
+    /// A built-in vocabulary: `standard` takes the letters and the words, `letters` only
+    /// the single letters, `words` only the full words. `restart` and `exit` stay words
+    /// in all of them, so they're hard to hit by accident.
+    pub fn style(name: &str) -> Option<Self> {
+        let lists = match name {
+            "standard" => STANDARD,
+            "letters" => LETTERS,
+            "words" => WORDS,
+            _ => return None,
+        };
+        Some(Self::from_lists(lists))
+    }
+
+    fn from_lists(lists: [&[&str]; 9]) -> Self {
+        Self {
+            words: lists.map(|l| l.iter().map(|w| w.to_string()).collect()),
+        }
+    }
+
+    pub fn words(&self, verb: Verb) -> &[String] {
+        &self.words[verb as usize]
+    }
+
+    /// The word hints use for `verb`
+    pub fn first(&self, verb: Verb) -> &str {
+        self.words(verb).first().map_or("", String::as_str)
+    }
+
+    pub fn means(&self, verb: Verb, word: &str) -> bool {
+        self.words(verb).iter().any(|w| w == word)
+    }
+
+    /// Replace a verb's words, e.g. `set("face", "f, fight")`. Words are separated by
+    /// commas or spaces.
+    pub fn set(&mut self, name: &str, words: &str) -> Result<(), String> {
+        let verb = Verb::by_name(name).ok_or_else(|| {
+            let known: Vec<&str> = Verb::ALL.iter().map(|v| v.name()).collect();
+            format!("unknown command '{name}' (known: {})", known.join(", "))
+        })?;
+        let words: Vec<String> = words
+            .split([',', ' '])
+            .filter(|w| !w.is_empty())
+            .map(|w| text::compose(&w.to_lowercase()))
+            .collect();
+        if words.is_empty() {
+            return Err(format!("'{name}' needs at least one word"));
+        }
+        for w in &words {
+            if w.parse::<usize>().is_ok() {
+                return Err(format!("'{w}' would be read as a card number"));
+            }
+            if RESERVED.contains(&w.as_str()) {
+                return Err(format!("'{w}' is already a command of its own"));
+            }
+        }
+        self.words[verb as usize] = words;
+        Ok(())
+    }
+
+    /// No word may mean two things on the same screen
+    pub fn check(&self) -> Result<(), String> {
+        for screen in SCREENS {
+            let mut verbs = screen.to_vec();
+            verbs.extend([Verb::Restart, Verb::Exit]);
+            for (i, a) in verbs.iter().enumerate() {
+                for b in &verbs[i + 1..] {
+                    if let Some(w) = self.words(*a).iter().find(|w| self.means(*b, w)) {
+                        return Err(format!(
+                            "'{w}' can't mean both {} and {}",
+                            a.name(),
+                            b.name()
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// ==============================
+// Custom game screen
+// ==============================
+
+/// What can be typed on the custom game screen, where `Command`s don't apply
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CustomCommand {
+    /// `equal-fight chip`, checked when applied so the error names the knob
+    Set(String, String),
+    Save(String),
+    Load(String),
+    Delete(String),
+    /// Back to the standard rules
+    Reset,
+    /// Show the share code for the rules on screen
+    Code,
+    /// Load rules from a pasted share code
+    Import(String),
+    /// Simulate bot games of the rules on screen
+    Test,
+    Play,
+    Back,
+}
+
+pub fn parse_custom(input: &str) -> Option<CustomCommand> {
+    let tokens = tokenize(input);
+    let words: Vec<&str> = tokens.iter().map(String::as_str).collect();
+
+    let command = match words.as_slice() {
+        ["play" | "start"] => CustomCommand::Play,
+        ["back"] => CustomCommand::Back,
+        ["reset"] => CustomCommand::Reset,
+        ["code"] => CustomCommand::Code,
+        ["test"] => CustomCommand::Test,
+        [code]
+            if code
+                .strip_prefix("sc")
+                .is_some_and(|v| v.starts_with(|c: char| c.is_ascii_digit())) =>
+        {
+            CustomCommand::Import(code.to_string())
+        }
+        ["save", name] => CustomCommand::Save(name.to_string()),
+        ["load", name] => CustomCommand::Load(name.to_string()),
+        ["delete", name] => CustomCommand::Delete(name.to_string()),
+        [key, value] | ["set", key, value] => {
+            CustomCommand::Set(key.to_string(), value.to_string())
+        }
+        _ => return None,
+    };
+    Some(command)
+}
+
+// ==============================
+// Sandbox screen
+// ==============================
+
+/// Where a clicked card goes on the sandbox screen
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Target {
+    Room,
+    Deck,
+    Weapon,
+}
+
+impl Target {
+    /// Where the card ends up, e.g. "Clicked cards now go to the room"
+    pub fn label(self) -> &'static str {
+        match self {
+            Target::Room => "the room",
+            Target::Deck => "the deck",
+            Target::Weapon => "your hand",
+        }
+    }
+}
+
+/// What can be typed on the sandbox screen. Card codes are kept as typed and checked
+/// when applied, so a typo gets named.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SandboxCommand {
+    Health(String),
+    /// `weapon 7d` or `weapon 7d 9` (already used, last on a 9); `weapon none` to unequip
+    Weapon(String, Option<String>),
+    Room(Vec<String>),
+    /// The top of the deck, first drawn first
+    Deck(Vec<String>),
+    /// `room`, `deck` or `weapon` on its own: where clicked cards go
+    Pick(Target),
+    Fill(bool),
+    /// Take cards back out, wherever they were placed
+    Remove(Vec<String>),
+    Clear,
+    Play,
+    Back,
+}
+
+pub fn parse_sandbox(input: &str) -> Option<SandboxCommand> {
+    let tokens = tokenize(input);
+    let words: Vec<&str> = tokens.iter().map(String::as_str).collect();
+    let owned = |cards: &[&str]| cards.iter().map(|c| c.to_string()).collect();
+
+    let command = match words.as_slice() {
+        ["play" | "start"] => SandboxCommand::Play,
+        ["back"] => SandboxCommand::Back,
+        ["clear"] => SandboxCommand::Clear,
+        ["hp" | "health", n] => SandboxCommand::Health(n.to_string()),
+        ["room"] => SandboxCommand::Pick(Target::Room),
+        ["deck"] => SandboxCommand::Pick(Target::Deck),
+        ["weapon"] => SandboxCommand::Pick(Target::Weapon),
+        ["weapon", card] => SandboxCommand::Weapon(card.to_string(), None),
+        ["weapon", card, last] => SandboxCommand::Weapon(card.to_string(), Some(last.to_string())),
+        ["fill", "on" | "yes"] => SandboxCommand::Fill(true),
+        ["fill", "off" | "no"] => SandboxCommand::Fill(false),
+        ["room", cards @ ..] => SandboxCommand::Room(owned(cards)),
+        ["deck", cards @ ..] => SandboxCommand::Deck(owned(cards)),
+        ["remove" | "rm", cards @ ..] if !cards.is_empty() => SandboxCommand::Remove(owned(cards)),
+        _ => return None,
+    };
+    Some(command)
+}
+
+/// Card codes typed on the sandbox screen, each checked against the deck
+pub fn parse_cards(codes: &[String]) -> Result<Vec<Card>, String> {
+    let deck = logic::full_deck();
+    let mut cards = Vec::with_capacity(codes.len());
+    for code in codes {
+        let card = Card::from_code(code)
+            .filter(|c| deck.contains(c))
+            .ok_or_else(|| format!("'{code}' isn't a card in this deck (try e.g. 7d, 10s, qc)."))?;
+        if cards.contains(&card) {
+            return Err(format!("{} is listed twice.", card_text(card)));
+        }
+        cards.push(card);
+    }
+    Ok(cards)
+}
@@ -0,0 +1,721 @@
+//! Settings file
+//!
+//! `config.toml` lives in the config dir (`scoundrel --paths` shows where). Every key is
+//! optional and anything missing keeps its built-in default; command-line flags win over
+//! the file. Only the small slice of TOML we need is understood: `[sections]`,
+//! `key = value` with strings, integers and booleans, and `#` comments.
+//!
+//! House rules can go in a `rules.toml` next to it instead of under `[rules]`, which makes
+//! them easy to swap or pass around. Its knobs win over `[rules]`:
+//!
+//! ```toml
+//! max-health = 25
+//! potion-limit = 2
+//! skips = "always"
+//! ```
+//!
+//! ```toml
+//! [display]
+//! theme = "default"
+//! glyphs = "unicode"
+//! frame_rate = 60
+//! solid_backgrounds = false
+//! streamer_mode = false
+//! bell = true
+//! tooltips = "overlay"   # or "message"
+//! room_ranks = false
+//! hp_preview = false
+//! speedrun = false
+//! low_power = "auto"
+//!
+//! [colors]
+//! card_red = "#ff5f5f"
+//! muted = "light-gray on black"
+//!
+//! [backgrounds]
+//! room = "#101828"
+//!
+//! [borders]              # single | double | rounded | ascii, and/or left | center | right
+//! status = "rounded"
+//! room = "double center"
+//!
+//! [keys]
+//! quit = "ctrl+q"        # or "none"; also save, load, scores, achievements, discard,
+//! save = "ctrl+s"        # notes, ranks and log, unbound unless set here
+//! quit_mode = "confirm"
+//!
+//! [rules]
+//! equal-fight = "chip"
+//! room-reshuffles = 1
+//!
+//! [commands]
+//! style = "words"        # standard | letters | words
+//! face = "face, fight"   # replaces the style's words for that command
+//! ```
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use minui::prelude::{Event, KeyKind};
+
+use crate::command::{Command, Vocabulary};
+use crate::locale::{self, Locale};
+use crate::logic::Acknowledge;
+use crate::pacing::LowPower;
+use crate::render::{self, Glyphs};
+use crate::rules::Ruleset;
+use crate::storage::Retention;
+use crate::theme::{self, BorderStyle, Theme};
+use crate::ui::{QuitMode, TooltipMode};
+
+pub const FILE_NAME: &str = "config.toml";
+pub const RULES_FILE_NAME: &str = "rules.toml";
+
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub theme: Theme,
+    pub glyphs: &'static Glyphs,
+    /// How numbers and dates are written
+    pub locale: &'static Locale,
+    pub frame_rate: u32,
+    pub solid_backgrounds: bool,
+    /// Hide the seed and card tooltips, and show a panel for viewers
+    pub streamer_mode: bool,
+    /// Ring the terminal bell for cues like a weapon going dead weight
+    pub bell: bool,
+    /// Card tooltips by the mouse, or in the Message panel
+    pub tooltips: TooltipMode,
+    /// Tag room cards with their threat / pickup rank (`ranks` toggles it in game)
+    pub room_ranks: bool,
+    /// Show what picking each room card would do to your health (`preview` toggles it)
+    pub hp_preview: bool,
+    /// Speedrun timer and room splits in the Status panel
+    pub speedrun: bool,
+    /// Fewer frames and no idle redraws; `Auto` follows the battery
+    pub low_power: LowPower,
+
+    pub keys: Keys,
+    pub quit_mode: QuitMode,
+    /// When a resolved card waits for Enter
+    pub acknowledge: Acknowledge,
+    /// What the main commands are typed as (`[commands]`)
+    pub commands: Vocabulary,
+
+    /// How many old exports to keep (`[storage]`)
+    pub retention: Retention,
+
+    /// Rules a new run starts with
+    pub rules: Ruleset,
+    /// Deal the first run from this seed (`--seed` only, not read from the file)
+    pub seed: Option<u64>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            theme: theme::DEFAULT,
+            glyphs: &render::NERD_GLYPHS,
+            locale: &locale::EN,
+            frame_rate: 60,
+            solid_backgrounds: false,
+            streamer_mode: false,
+            bell: true,
+            tooltips: TooltipMode::default(),
+            room_ranks: false,
+            hp_preview: false,
+            speedrun: false,
+            low_power: LowPower::Auto,
+            keys: Keys::default(),
+            quit_mode: QuitMode::default(),
+            acknowledge: Acknowledge::default(),
+            commands: Vocabulary::default(),
+            retention: Retention::default(),
+            rules: Ruleset::default(),
+            seed: None,
+        }
+    }
+}
+
+impl Config {
+    /// Read `config.toml`, then `rules.toml`, from `dir`. Missing files just mean defaults.
+    pub fn load(dir: &Path) -> Result<Self, String> {
+        let path = dir.join(FILE_NAME);
+        let mut config = match read_if_present(&path)? {
+            Some(text) => Self::parse(&text).map_err(|e| format!("{}:{e}", path.display()))?,
+            None => Self::default(),
+        };
+
+        let path = dir.join(RULES_FILE_NAME);
+        if let Some(text) = read_if_present(&path)? {
+            config
+                .apply_house_rules(&text)
+                .map_err(|e| format!("{}:{e}", path.display()))?;
+        }
+        Ok(config)
+    }
+
+    /// `rules.toml` holds bare knobs (a `[rules]` header is allowed too)
+    fn apply_house_rules(&mut self, text: &str) -> Result<(), String> {
+        for e in parse_toml(text)? {
+            match e.section.as_str() {
+                "" | "rules" => self
+                    .rules
+                    .set(&e.key, &e.value.to_string())
+                    .map_err(|msg| format!("{}: {msg}", e.line))?,
+                section => {
+                    return Err(format!(
+                        "{}: [{section}] belongs in {FILE_NAME}; this file only holds rules",
+                        e.line
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Errors are prefixed with the line number, e.g. `3: unknown key 'colour'`
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut config = Self::default();
+
+        // The theme has to be picked before [colors] can tweak it, wherever it appears
+        let entries = parse_toml(text)?;
+        if let Some(e) = entries
+            .iter()
+            .find(|e| e.section == "display" && e.key == "theme")
+        {
+            let name = e
+                .value
+                .as_str()
+                .map_err(|msg| format!("{}: {msg}", e.line))?;
+            let t = theme::by_name(name).ok_or_else(|| {
+                format!(
+                    "{}: unknown theme '{name}' (known: {})",
+                    e.line,
+                    theme::names()
+                )
+            })?;
+            config.theme = t.clone();
+        }
+        // Likewise the command style, before single commands are reworded
+        if let Some(e) = entries
+            .iter()
+            .find(|e| e.section == "commands" && e.key == "style")
+        {
+            let name = e
+                .value
+                .as_str()
+                .map_err(|msg| format!("{}: {msg}", e.line))?;
+            config.commands = Vocabulary::style(name).ok_or_else(|| {
+                format!(
+                    "{}: unknown command style '{name}' (known: {})",
+                    e.line,
+                    Vocabulary::STYLES
+                )
+            })?;
+        }
+
+        for e in &entries {
+            config
+                .apply(e)
+                .map_err(|msg| format!("{}: {msg}", e.line))?;
+        }
+        config
+            .commands
+            .check()
+            .map_err(|msg| format!(" [commands]: {msg}"))?;
+        config
+            .keys
+            .check()
+            .map_err(|msg| format!(" [keys]: {msg}"))?;
+        Ok(config)
+    }
+
+    pub fn frame_interval(&self) -> Duration {
+        Duration::from_millis(1000 / u64::from(self.frame_rate))
+    }
+
+    fn apply(&mut self, e: &Entry) -> Result<(), String> {
+        match (e.section.as_str(), e.key.as_str()) {
+            ("display", "theme") => {} // handled up front
+            ("display", "glyphs") => {
+                let name = e.value.as_str()?;
+                self.glyphs = render::glyphs_by_name(name).ok_or_else(|| {
+                    format!(
+                        "unknown glyph set '{name}' (known: {})",
+                        render::glyph_set_names()
+                    )
+                })?;
+            }
+            ("display", "locale") => {
+                let name = e.value.as_str()?;
+                self.locale = locale::by_name(name).ok_or_else(|| {
+                    format!("unknown locale '{name}' (known: {})", locale::names())
+                })?;
+            }
+            ("display", "frame_rate") => {
+                self.frame_rate = e
+                    .value
+                    .as_int()?
+                    .try_into()
+                    .ok()
+                    .filter(|fps| (1..=240).contains(fps))
+                    .ok_or("frame_rate must be between 1 and 240")?;
+            }
+            ("display", "solid_backgrounds") => self.solid_backgrounds = e.value.as_bool()?,
+            ("display", "streamer_mode") => self.streamer_mode = e.value.as_bool()?,
+            ("display", "bell") => self.bell = e.value.as_bool()?,
+            ("display", "tooltips") => {
+                let value = e.value.as_str()?;
+                self.tooltips = TooltipMode::parse(value).ok_or_else(|| {
+                    format!("bad tooltips '{value}' (expected {})", TooltipMode::VALUES)
+                })?;
+            }
+            ("display", "room_ranks") => self.room_ranks = e.value.as_bool()?,
+            ("display", "hp_preview") => self.hp_preview = e.value.as_bool()?,
+            ("display", "speedrun") => self.speedrun = e.value.as_bool()?,
+            ("display", "low_power") => {
+                self.low_power = match &e.value {
+                    Value::Bool(true) => LowPower::On,
+                    Value::Bool(false) => LowPower::Off,
+                    Value::Str(s) if s == "auto" => LowPower::Auto,
+                    other => {
+                        return Err(format!(
+                            "bad low_power '{other}' (expected {})",
+                            LowPower::VALUES
+                        ));
+                    }
+                };
+            }
+
+            ("colors", role) => {
+                let value = e.value.as_str()?;
+                let pair = theme::parse_pair(value)
+                    .ok_or_else(|| format!("bad color '{value}' for {role}"))?;
+                *self
+                    .theme
+                    .role_mut(role)
+                    .ok_or_else(|| format!("unknown color role '{role}'"))? = pair;
+            }
+            ("backgrounds", panel) => {
+                let value = e.value.as_str()?;
+                let color = theme::parse_color(value)
+                    .ok_or_else(|| format!("bad color '{value}' for {panel}"))?;
+                *self
+                    .theme
+                    .background_mut(panel)
+                    .ok_or_else(|| format!("unknown panel '{panel}'"))? = color;
+            }
+            ("borders", panel) => {
+                let value = e.value.as_str()?;
+                let frame = self
+                    .theme
+                    .frame_mut(panel)
+                    .ok_or_else(|| format!("unknown panel '{panel}'"))?;
+                *frame = frame.parse_over(value).ok_or_else(|| {
+                    format!(
+                        "bad border '{value}' for {panel} (expected {}, and/or left | center | right)",
+                        BorderStyle::VALUES
+                    )
+                })?;
+            }
+
+            ("storage", "keep") => {
+                self.retention.keep = e
+                    .value
+                    .as_int()?
+                    .try_into()
+                    .map_err(|_| "keep can't be negative")?;
+            }
+            ("storage", "max_megabytes") => {
+                self.retention.max_mb = e
+                    .value
+                    .as_int()?
+                    .try_into()
+                    .map_err(|_| "max_megabytes can't be negative")?;
+            }
+
+            ("keys", "quit_mode") => {
+                let value = e.value.as_str()?;
+                self.quit_mode = QuitMode::parse(value).ok_or_else(|| {
+                    format!("bad quit_mode '{value}' (expected {})", QuitMode::VALUES)
+                })?;
+            }
+            ("keys", "acknowledge") => {
+                let value = e.value.as_str()?;
+                self.acknowledge = Acknowledge::parse(value).ok_or_else(|| {
+                    format!(
+                        "bad acknowledge '{value}' (expected {})",
+                        Acknowledge::VALUES
+                    )
+                })?;
+            }
+
+            ("keys", name) => {
+                let action = KeyAction::by_name(name).ok_or_else(|| {
+                    let known: Vec<&str> = KeyAction::ALL.iter().map(|a| a.name()).collect();
+                    format!(
+                        "unknown key '{name}' in [keys] (known: {}, quit_mode, acknowledge)",
+                        known.join(", ")
+                    )
+                })?;
+                let value = e.value.as_str()?;
+                let binding = match value {
+                    "none" => None,
+                    _ => Some(KeyBinding::parse(value).ok_or_else(|| {
+                        format!("bad key '{value}' (expected e.g. \"ctrl+q\", or \"none\")")
+                    })?),
+                };
+                self.keys.bind(action, binding);
+            }
+
+            ("commands", "style") => {} // handled up front
+            ("commands", verb) => self.commands.set(verb, e.value.as_str()?)?,
+
+            // Knob values may be written as strings or bare numbers
+            ("rules", knob) => self.rules.set(knob, &e.value.to_string())?,
+
+            ("", key) => return Err(format!("'{key}' must be inside a [section]")),
+            (section, key) => return Err(format!("unknown key '{key}' in [{section}]")),
+        }
+        Ok(())
+    }
+}
+
+fn read_if_present(path: &Path) -> Result<Option<String>, String> {
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(Some(text)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("{}: {e}", path.display())),
+    }
+}
+
+// ==============================
+// Keys
+// ==============================
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyBinding {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub key: char,
+}
+
+impl KeyBinding {
+    /// `"ctrl+q"`, `"alt+x"`, `"ctrl+alt+k"`, ...
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut binding = KeyBinding {
+            ctrl: false,
+            alt: false,
+            key: ' ',
+        };
+
+        let lower = s.to_lowercase();
+        let mut parts: Vec<&str> = lower.split('+').map(str::trim).collect();
+        let key = parts.pop()?;
+        for m in parts {
+            match m {
+                "ctrl" => binding.ctrl = true,
+                "alt" => binding.alt = true,
+                _ => return None,
+            }
+        }
+
+        let mut chars = key.chars();
+        binding.key = chars.next()?;
+        if chars.next().is_some() || !(binding.ctrl || binding.alt) {
+            // Bare letters would be eaten by the command box
+            return None;
+        }
+        Some(binding)
+    }
+
+    pub fn matches(&self, event: &Event) -> bool {
+        match event {
+            Event::KeyWithModifiers(k) => {
+                k.key == KeyKind::Char(self.key)
+                    && k.mods.ctrl == self.ctrl
+                    && k.mods.alt == self.alt
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            f.write_str("ctrl+")?;
+        }
+        if self.alt {
+            f.write_str("alt+")?;
+        }
+        write!(f, "{}", self.key)
+    }
+}
+
+/// What a shortcut key can do. Apart from quitting, each runs the command of the same name
+/// as if it had been typed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyAction {
+    Quit,
+    Save,
+    Load,
+    Scores,
+    Achievements,
+    Discard,
+    Notes,
+    Ranks,
+    Log,
+}
+
+impl KeyAction {
+    pub const ALL: [KeyAction; 9] = [
+        KeyAction::Quit,
+        KeyAction::Save,
+        KeyAction::Load,
+        KeyAction::Scores,
+        KeyAction::Achievements,
+        KeyAction::Discard,
+        KeyAction::Notes,
+        KeyAction::Ranks,
+        KeyAction::Log,
+    ];
+
+    /// Its key under `[keys]`
+    pub fn name(self) -> &'static str {
+        match self {
+            KeyAction::Quit => "quit",
+            KeyAction::Save => "save",
+            KeyAction::Load => "load",
+            KeyAction::Scores => "scores",
+            KeyAction::Achievements => "achievements",
+            KeyAction::Discard => "discard",
+            KeyAction::Notes => "notes",
+            KeyAction::Ranks => "ranks",
+            KeyAction::Log => "log",
+        }
+    }
+
+    pub fn by_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|a| a.name() == name)
+    }
+
+    /// The command it stands for; quitting has its own handling
+    pub fn command(self) -> Option<Command> {
+        Some(match self {
+            KeyAction::Quit => return None,
+            KeyAction::Save => Command::Save,
+            KeyAction::Load => Command::Load,
+            KeyAction::Scores => Command::Scores,
+            KeyAction::Achievements => Command::Achievements,
+            KeyAction::Discard => Command::Discard,
+            KeyAction::Notes => Command::Notes,
+            KeyAction::Ranks => Command::Ranks,
+            KeyAction::Log => Command::Log,
+        })
+    }
+}
+
+/// Keys a terminal sends as something else, so a binding to one could never be pressed
+const TERMINAL_KEYS: [(char, &str); 5] = [
+    ('m', "Enter"),
+    ('j', "Enter"),
+    ('i', "Tab"),
+    ('h', "Backspace"),
+    ('[', "Esc"),
+];
+
+/// Shortcut keys, as a table of bindings rather than one field each. Only `quit` is bound
+/// out of the box; `"none"` unbinds.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Keys {
+    bindings: Vec<(KeyAction, KeyBinding)>,
+}
+
+impl Default for Keys {
+    fn default() -> Self {
+        Self {
+            bindings: vec![(
+                KeyAction::Quit,
+                KeyBinding {
+                    ctrl: true,
+                    alt: false,
+                    key: 'q',
+                },
+            )],
+        }
+    }
+}
+
+impl Keys {
+    pub fn bind(&mut self, action: KeyAction, binding: Option<KeyBinding>) {
+        self.bindings.retain(|(a, _)| *a != action);
+        self.bindings.extend(binding.map(|b| (action, b)));
+    }
+
+    /// The action `event` is the key for, if any
+    pub fn action_for(&self, event: &Event) -> Option<KeyAction> {
+        self.bindings
+            .iter()
+            .find(|(_, b)| b.matches(event))
+            .map(|&(a, _)| a)
+    }
+
+    /// Two actions on one key would leave one of them dead, and so would a key the terminal
+    /// turns into Enter, Tab and the like
+    pub fn check(&self) -> Result<(), String> {
+        for (i, (a, key)) in self.bindings.iter().enumerate() {
+            if let Some((b, _)) = self.bindings[i + 1..].iter().find(|(_, k)| k == key) {
+                return Err(format!(
+                    "{key} is bound to both {} and {}; give one of them another key, or \"none\"",
+                    a.name(),
+                    b.name()
+                ));
+            }
+            if let Some((_, sent)) = TERMINAL_KEYS
+                .iter()
+                .find(|(c, _)| key.ctrl && !key.alt && *c == key.key)
+            {
+                return Err(format!(
+                    "{key} reaches the game as {sent}, so {} could never be pressed; pick another key",
+                    a.name()
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+// ==============================
+// TOML subset
+// ==============================
+
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+    Str(String),
+    Int(i64),
+    Bool(bool),
+}
+
+impl Value {
+    fn as_str(&self) -> Result<&str, String> {
+        match self {
+            Value::Str(s) => Ok(s),
+            _ => Err("expected a quoted string".to_string()),
+        }
+    }
+
+    fn as_int(&self) -> Result<i64, String> {
+        match self {
+            Value::Int(n) => Ok(*n),
+            _ => Err("expected a number".to_string()),
+        }
+    }
+
+    fn as_bool(&self) -> Result<bool, String> {
+        match self {
+            Value::Bool(b) => Ok(*b),
+            _ => Err("expected true or false".to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Str(s) => write!(f, "{s}"),
+            Value::Int(n) => write!(f, "{n}"),
+            Value::Bool(b) => write!(f, "{b}"),
+        }
+    }
+}
+
+struct Entry {
+    section: String,
+    key: String,
+    value: Value,
+    /// 1-based, for error messages
+    line: usize,
+}
+
+fn parse_toml(text: &str) -> Result<Vec<Entry>, String> {
+    let mut section = String::new();
+    let mut entries = Vec::new();
+
+    for (i, raw) in text.lines().enumerate() {
+        let line = i + 1;
+        let l = strip_comment(raw).trim();
+        if l.is_empty() {
+            continue;
+        }
+
+        if let Some(name) = l.strip_prefix('[') {
+            section = name
+                .strip_suffix(']')
+                .ok_or_else(|| format!("{line}: unclosed section header"))?
+                .trim()
+                .to_string();
+            continue;
+        }
+
+        let (key, value) = l
+            .split_once('=')
+            .ok_or_else(|| format!("{line}: expected key = value"))?;
+        let key = key.trim().trim_matches('"').to_string();
+        let value = parse_value(value.trim()).ok_or_else(|| format!("{line}: bad value"))?;
+        entries.push(Entry {
+            section: section.clone(),
+            key,
+            value,
+            line,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Drop a trailing `# comment`, leaving `#` inside strings (hex colors) alone
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn parse_value(s: &str) -> Option<Value> {
+    if let Some(inner) = s.strip_prefix('"') {
+        let inner = inner.strip_suffix('"')?;
+        let mut out = String::new();
+        let mut chars = inner.chars();
+        while let Some(c) = chars.next() {
+            if c == '\\' {
+                match chars.next()? {
+                    'n' => out.push('\n'),
+                    't' => out.push('\t'),
+                    c @ ('"' | '\\') => out.push(c),
+                    _ => return None,
+                }
+            } else {
+                out.push(c);
+            }
+        }
+        return Some(Value::Str(out));
+    }
+
+    match s {
+        "true" => Some(Value::Bool(true)),
+        "false" => Some(Value::Bool(false)),
+        _ => s.replace('_', "").parse().ok().map(Value::Int),
+    }
+}
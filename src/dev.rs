@@ -0,0 +1,258 @@
+//! Developer conveniences, only built with `--features dev`
+//!
+//! `HotReload` polls `config.toml` and `rules.toml` for changes so theme and variant authors can tweak
+//! colors and rules without restarting. It's a cheap mtime check a couple of times a
+//! second rather than a real file watcher, which keeps it dependency-free.
+//!
+//! `bisect` hunts for a bug report's dungeon: it has a bot play seed after seed until one
+//! game does what was asked for (panics, scores under -40, ...), and prints that seed
+//! with the actions that got there.
+
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+
+use crate::cli::Args;
+use crate::config::{self, Config};
+use crate::logic::{Action, Game, GameState};
+use crate::rules::Ruleset;
+use crate::sim;
+
+const POLL_EVERY: Duration = Duration::from_millis(500);
+
+pub struct HotReload {
+    dir: PathBuf,
+    /// Re-applied over every reload so flags keep winning over the file
+    args: Args,
+    seen: [Option<SystemTime>; 2],
+    next_check: Instant,
+}
+
+impl HotReload {
+    pub fn new(dir: &Path, args: Args) -> Self {
+        let mut reload = HotReload {
+            dir: dir.to_path_buf(),
+            args,
+            seen: [None, None],
+            next_check: Instant::now(),
+        };
+        reload.seen = reload.modified();
+        reload
+    }
+
+    fn modified(&self) -> [Option<SystemTime>; 2] {
+        [config::FILE_NAME, config::RULES_FILE_NAME].map(|name| {
+            fs::metadata(self.dir.join(name))
+                .and_then(|m| m.modified())
+                .ok()
+        })
+    }
+
+    /// `Some` with the freshly loaded config when either file changed since the last look
+    pub fn poll(&mut self) -> Option<Result<Config, String>> {
+        if Instant::now() < self.next_check {
+            return None;
+        }
+        self.next_check = Instant::now() + POLL_EVERY;
+
+        let modified = self.modified();
+        if modified == self.seen {
+            return None;
+        }
+        self.seen = modified;
+
+        let reloaded = Config::load(&self.dir).and_then(|mut config| {
+            self.args.apply(&mut config)?;
+            Ok(config)
+        });
+        Some(reloaded)
+    }
+}
+
+// ==============================
+// Seed bisection
+// ==============================
+
+/// Seeds `bisect` tries before giving up
+const BISECT_SEEDS: u64 = 100_000;
+/// A game that hasn't ended by now counts as stuck
+const MAX_ACTIONS: usize = 2000;
+
+pub const BOTS: &str = "sim | random | first";
+pub const PREDICATES: &str = "panic | stuck | dies | survives | score<N | score>N";
+
+/// Who plays the games
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Bot {
+    /// The `simulate` bot
+    Sim,
+    /// Any legal action, from an rng seeded with the game's seed
+    Random,
+    /// The first legal action, every time
+    First,
+}
+
+impl Bot {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "sim" => Some(Bot::Sim),
+            "random" => Some(Bot::Random),
+            "first" => Some(Bot::First),
+            _ => None,
+        }
+    }
+
+    fn choose(self, game: &Game, rng: &mut StdRng) -> Action {
+        let legal = game.legal_actions();
+        match self {
+            Bot::Sim => sim::choose(game),
+            Bot::Random => legal.choose(rng).copied().unwrap_or(Action::Continue),
+            Bot::First => legal.first().copied().unwrap_or(Action::Continue),
+        }
+    }
+}
+
+/// What the game being looked for did
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Predicate {
+    Panics,
+    /// Still going after `MAX_ACTIONS`
+    Stuck,
+    Dies,
+    Survives,
+    ScoreBelow(i32),
+    ScoreAbove(i32),
+}
+
+impl Predicate {
+    fn parse(s: &str) -> Option<Self> {
+        let s = s.replace(' ', "");
+        match s.as_str() {
+            "panic" => Some(Predicate::Panics),
+            "stuck" => Some(Predicate::Stuck),
+            "dies" => Some(Predicate::Dies),
+            "survives" => Some(Predicate::Survives),
+            _ => {
+                if let Some(n) = s.strip_prefix("score<") {
+                    n.parse().ok().map(Predicate::ScoreBelow)
+                } else {
+                    s.strip_prefix("score>")?
+                        .parse()
+                        .ok()
+                        .map(Predicate::ScoreAbove)
+                }
+            }
+        }
+    }
+
+    fn holds(self, ending: &Ending) -> bool {
+        match (self, ending) {
+            (Predicate::Panics, Ending::Panicked(_)) => true,
+            (Predicate::Stuck, Ending::Stuck) => true,
+            (Predicate::Dies, Ending::Over { survived, .. }) => !survived,
+            (Predicate::Survives, Ending::Over { survived, .. }) => *survived,
+            (Predicate::ScoreBelow(n), Ending::Over { score, .. }) => *score < n,
+            (Predicate::ScoreAbove(n), Ending::Over { score, .. }) => *score > n,
+            _ => false,
+        }
+    }
+}
+
+/// How a bot's game ended
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Ending {
+    Panicked(String),
+    Stuck,
+    Over { survived: bool, score: i32 },
+}
+
+impl Ending {
+    fn describe(&self) -> String {
+        match self {
+            Ending::Panicked(why) => format!("panicked: {why}"),
+            Ending::Stuck => format!("still going after {MAX_ACTIONS} actions"),
+            Ending::Over {
+                survived: true,
+                score,
+            } => format!("survived, score {score}"),
+            Ending::Over {
+                survived: false,
+                score,
+            } => format!("died, score {score}"),
+        }
+    }
+}
+
+/// Play `seed` through with `bot`. The actions end with the one that panicked, if one did.
+fn play(rules: &Ruleset, seed: u64, bot: Bot) -> (Vec<Action>, Ending) {
+    let mut actions = Vec::new();
+    let mut rng = StdRng::seed_from_u64(seed);
+    let played = panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut game = Game::with_seed(rules.clone(), seed);
+        game.start();
+        for _ in 0..MAX_ACTIONS {
+            if game.state == GameState::GameOver {
+                return Ending::Over {
+                    survived: game.survived,
+                    score: game.final_score(),
+                };
+            }
+            let action = bot.choose(&game, &mut rng);
+            actions.push(action);
+            let _ = game.apply(action);
+            game.settle_death();
+        }
+        Ending::Stuck
+    }));
+    let ending = played.unwrap_or_else(|payload| {
+        let why = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "(no message)".to_string());
+        Ending::Panicked(why)
+    });
+    (actions, ending)
+}
+
+/// `--bisect BOT WHEN`: the lowest seed from `first` on whose game `WHEN` holds, as a
+/// report to paste into a bug. Panics on the way are caught (and kept quiet) so they can be
+/// looked for too.
+pub fn bisect(rules: &Ruleset, first: u64, bot: &str, when: &str) -> Result<String, String> {
+    let bot = Bot::parse(bot).ok_or_else(|| format!("unknown bot '{bot}' (known: {BOTS})"))?;
+    let predicate = Predicate::parse(when)
+        .ok_or_else(|| format!("can't look for '{when}' (try {PREDICATES})"))?;
+
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let found = (first..first.saturating_add(BISECT_SEEDS)).find_map(|seed| {
+        let (actions, ending) = play(rules, seed, bot);
+        predicate.holds(&ending).then_some((seed, actions, ending))
+    });
+    panic::set_hook(hook);
+
+    let (seed, actions, ending) = found.ok_or_else(|| {
+        format!(
+            "no game from seed {first} to {} matched",
+            first + BISECT_SEEDS - 1
+        )
+    })?;
+    let codes: Vec<String> = actions.iter().map(|a| a.code()).collect();
+    let rules: Vec<String> = rules
+        .differences()
+        .iter()
+        .map(|(k, v)| format!(" --rule {k}={v}"))
+        .collect();
+    Ok(format!(
+        "seed {seed}: {}\nactions ({}): {}\nplay it: scoundrel --seed {seed}{}\n",
+        ending.describe(),
+        codes.len(),
+        codes.join(","),
+        rules.concat()
+    ))
+}
@@ -0,0 +1,283 @@
+//! Machine-readable run summaries
+//!
+//! `export` on the Game Over screen writes the finished run as a small JSON object, for
+//! tooling, and as a CSV with a row per card played, for spreadsheets. Both are written
+//! by hand since they're flat. The JSON carries every move and what the run was dealt
+//! from, so `decode` can read it back and `--annotate` play it over.
+
+use std::io;
+
+use crate::clock;
+use crate::logic::{Action, Card, Game, Mutator};
+use crate::rules::Ruleset;
+use crate::storage::Storage;
+
+/// Storage key for a run's export, named after when it started so runs don't overwrite
+/// each other
+fn key_for(game: &Game, extension: &str) -> String {
+    format!("exports/run-{}.{extension}", game.started_at_ms)
+}
+
+/// Writes both exports; returns the JSON's key (the CSV sits beside it)
+pub fn write(storage: &dyn Storage, game: &Game) -> io::Result<String> {
+    storage.write(&key_for(game, "csv"), &encode_csv(game))?;
+    let key = key_for(game, "json");
+    storage.write(&key, &encode(game))?;
+    Ok(key)
+}
+
+/// `hp_timeline` starts with the HP the run began with, then one entry per action
+pub fn encode(game: &Game) -> String {
+    let rules: Vec<String> = game
+        .rules
+        .pairs()
+        .iter()
+        .map(|(k, v)| format!("{}: {}", json_string(k), json_string(v)))
+        .collect();
+
+    let mutators: Vec<String> = game
+        .mutators
+        .iter()
+        .map(|m| json_string(m.code()))
+        .collect();
+
+    let mut timeline = vec![game.starting_health()];
+    timeline.extend(game.events.iter().map(|e| e.health));
+    let timeline: Vec<String> = timeline.iter().map(i32::to_string).collect();
+
+    let moves: Vec<String> = game
+        .events
+        .iter()
+        .map(|e| json_string(&e.action.code()))
+        .collect();
+
+    let fields = [
+        ("seed", game.seed.to_string()),
+        ("started_at_ms", game.started_at_ms.to_string()),
+        ("date", json_string(&clock::date(game.started_at_ms))),
+        ("rules", format!("{{{}}}", rules.join(", "))),
+        ("mutators", format!("[{}]", mutators.join(", "))),
+        ("endless", game.endless.to_string()),
+        ("plus", game.plus.to_string()),
+        (
+            "relic",
+            game.relic
+                .map_or("null".to_string(), |c| json_string(&c.code())),
+        ),
+        ("survived", game.survived.to_string()),
+        ("score", game.final_score().to_string()),
+        ("cards_remaining", game.cards_remaining().to_string()),
+        ("actions", game.events.len().to_string()),
+        ("hp_timeline", format!("[{}]", timeline.join(", "))),
+        ("moves", format!("[{}]", moves.join(", "))),
+    ];
+
+    let body: Vec<String> = fields
+        .iter()
+        .map(|(k, v)| format!("  {}: {v}", json_string(k)))
+        .collect();
+    format!("{{\n{}\n}}\n", body.join(",\n"))
+}
+
+// ==============================
+// CSV
+// ==============================
+
+const CSV_HEADER: &str = "turn,room,slot,card,choice,damage,hp_after,weapon,weapon_last_slain";
+
+/// One row per card played. A monster's row covers the weapon prompt that followed, so
+/// `choice` is how it was fought and `hp_after` is once it was dealt with.
+pub fn encode_csv(game: &Game) -> String {
+    let mut rows = vec![CSV_HEADER.to_string()];
+    let mut health_before = game.starting_health();
+    for (i, event) in game.events.iter().enumerate() {
+        let (Action::Play(slot), Some(card)) = (event.action, event.card) else {
+            health_before = event.health;
+            continue;
+        };
+        // The weapon prompt, if this card raised one
+        let answer = game
+            .events
+            .get(i + 1)
+            .filter(|next| matches!(next.action, Action::Fight { .. }));
+        let choice = match (answer, card.suit) {
+            (Some(answer), _) => answer.action.code(),
+            (None, 'S' | 'C') => "fight-bare".to_string(),
+            (None, 'D') => "equip".to_string(),
+            (None, _) => "drink".to_string(),
+        };
+        let after = answer.unwrap_or(event);
+        rows.push(
+            [
+                event.turn.to_string(),
+                event.room.to_string(),
+                (slot + 1).to_string(),
+                card.code(),
+                choice,
+                (health_before - after.health).max(0).to_string(),
+                after.health.to_string(),
+                after.weapon.map(|w| w.code()).unwrap_or_default(),
+                after.last_slain.map(|v| v.to_string()).unwrap_or_default(),
+            ]
+            .join(","),
+        );
+        health_before = after.health;
+    }
+    rows.join("\n") + "\n"
+}
+
+// ==============================
+// Reading back
+// ==============================
+
+/// What an export says about how its run was dealt, and the moves made in it
+#[derive(Clone, Debug)]
+pub struct Exported {
+    pub seed: u64,
+    pub date: String,
+    pub rules: Ruleset,
+    pub mutators: Vec<Mutator>,
+    pub endless: bool,
+    pub plus: u32,
+    pub relic: Option<Card>,
+    pub moves: Vec<Action>,
+}
+
+impl Exported {
+    /// The run as it was dealt, before its first move
+    pub fn deal(&self) -> Game {
+        let mut game = Game::with_seed(self.rules.clone(), self.seed);
+        game.mutators = self.mutators.clone();
+        game.endless = self.endless;
+        game.plus = self.plus;
+        game.relic = self.relic;
+        game.begin();
+        game
+    }
+}
+
+/// Read back a JSON export. Only what `encode` writes, one field per line, is understood;
+/// exports from before moves were recorded are refused.
+pub fn decode(text: &str) -> Result<Exported, String> {
+    let field = |key: &str| {
+        let prefix = format!("{}: ", json_string(key));
+        text.lines()
+            .find_map(|l| l.trim().strip_prefix(&prefix))
+            .map(|v| v.trim_end_matches(','))
+            .ok_or_else(|| format!("no '{key}' field"))
+    };
+    let number = |key: &str| {
+        field(key)?
+            .parse::<u64>()
+            .map_err(|_| format!("bad '{key}'"))
+    };
+
+    let moves =
+        field("moves").map_err(|_| "no moves recorded (exported before they were)".to_string())?;
+    let moves = json_strings(moves)
+        .iter()
+        .map(|code| Action::from_code(code).ok_or_else(|| format!("unknown move '{code}'")))
+        .collect::<Result<_, _>>()?;
+    let mut rules = Ruleset::default();
+    for pair in json_strings(field("rules")?).chunks(2) {
+        if let [key, value] = pair {
+            rules.set(key, value)?;
+        }
+    }
+    let mutators = json_strings(field("mutators")?)
+        .iter()
+        .map(|code| Mutator::from_code(code).ok_or_else(|| format!("unknown mutator '{code}'")))
+        .collect::<Result<_, _>>()?;
+    let relic = match field("relic")? {
+        "null" => None,
+        code => {
+            let code = json_strings(code).concat();
+            Some(Card::from_code(&code).ok_or_else(|| format!("bad relic '{code}'"))?)
+        }
+    };
+
+    Ok(Exported {
+        seed: number("seed")?,
+        date: json_strings(field("date")?).concat(),
+        rules,
+        mutators,
+        endless: field("endless")? == "true",
+        plus: number("plus")? as u32,
+        relic,
+        moves,
+    })
+}
+
+/// Every string in a line of JSON, in order, unescaped: `{"a": "b"}` gives `a`, `b`
+fn json_strings(line: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut chars = line.chars();
+    while chars.by_ref().any(|c| c == '"') {
+        let mut s = String::new();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => break,
+                '\\' => match chars.next() {
+                    Some('n') => s.push('\n'),
+                    Some('u') => {
+                        let hex: String = chars.by_ref().take(4).collect();
+                        s.extend(u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32));
+                    }
+                    Some(c) => s.push(c),
+                    None => {}
+                },
+                c => s.push(c),
+            }
+        }
+        out.push(s);
+    }
+    out
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::GameState;
+    use crate::sim;
+
+    #[test]
+    fn export_reads_back_and_replays_to_the_same_end() {
+        let rules = Ruleset {
+            max_health: 30,
+            ..Ruleset::default()
+        };
+        let mut game = Game::with_seed(rules, 11);
+        game.start();
+        sim::play_out(&mut game);
+        assert_eq!(game.state, GameState::GameOver);
+
+        let exported = decode(&encode(&game)).unwrap();
+        assert_eq!(exported.seed, 11);
+        assert_eq!(exported.rules.max_health, 30);
+        assert_eq!(exported.moves.len(), game.events.len());
+
+        let mut replayed = exported.deal();
+        for &action in &exported.moves {
+            assert_eq!(replayed.illegal_reason(action), None);
+            replayed.apply(action);
+            replayed.settle_death();
+        }
+        assert_eq!(replayed.health, game.health);
+        assert_eq!(replayed.final_score(), game.final_score());
+    }
+}
@@ -0,0 +1,84 @@
+//! Gamepad input, only built with `--features gamepad`
+//!
+//! Reads the Linux joystick interface (`/dev/input/js*`) on a background thread, so it
+//! needs no extra crates and works from a plain terminal (Steam Deck included). Buttons
+//! come through as `Button`s; `ui::gamepad_command` maps them onto the same commands the
+//! keyboard and mouse use. Elsewhere `Gamepad::open` just finds nothing.
+
+use std::fs::File;
+use std::io::Read;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Button {
+    Left,
+    Right,
+    /// Confirm
+    A,
+    /// Back out / decline
+    B,
+    /// Skip the room
+    X,
+}
+
+pub struct Gamepad {
+    rx: Receiver<Button>,
+}
+
+impl Gamepad {
+    /// The first joystick device that opens, if any
+    pub fn open() -> Option<Self> {
+        let file = (0..4).find_map(|n| File::open(format!("/dev/input/js{n}")).ok())?;
+
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let mut file = file;
+            let mut buf = [0u8; 8];
+            // Ends when the pad is unplugged or the app has gone away
+            while file.read_exact(&mut buf).is_ok() {
+                if let Some(button) = decode(buf)
+                    && tx.send(button).is_err()
+                {
+                    break;
+                }
+            }
+        });
+
+        Some(Gamepad { rx })
+    }
+
+    /// Presses since the last call
+    pub fn drain(&self) -> Vec<Button> {
+        self.rx.try_iter().collect()
+    }
+}
+
+/// `struct js_event { u32 time; i16 value; u8 type; u8 number; }`
+fn decode(buf: [u8; 8]) -> Option<Button> {
+    const BUTTON: u8 = 0x01;
+    const AXIS: u8 = 0x02;
+    // Synthetic "initial state" events sent when the device is opened
+    const INIT: u8 = 0x80;
+
+    let value = i16::from_le_bytes([buf[4], buf[5]]);
+    let kind = buf[6];
+    let number = buf[7];
+    if kind & INIT != 0 {
+        return None;
+    }
+
+    // Xbox-style layout, which is what xpad and Steam Input present
+    match (kind, number) {
+        (BUTTON, 0) if value == 1 => Some(Button::A),
+        (BUTTON, 1) if value == 1 => Some(Button::B),
+        (BUTTON, 2) if value == 1 => Some(Button::X),
+        // D-pad as buttons (xpad's dpad_to_buttons)
+        (BUTTON, 13) if value == 1 => Some(Button::Left),
+        (BUTTON, 14) if value == 1 => Some(Button::Right),
+        // D-pad as a hat axis
+        (AXIS, 6) if value < 0 => Some(Button::Left),
+        (AXIS, 6) if value > 0 => Some(Button::Right),
+        _ => None,
+    }
+}
@@ -0,0 +1,386 @@
+//! Best-move hints
+//!
+//! `hint` plays the room on the table out every way it can go and recommends the first
+//! step of the best line, with the reason it wins: the damage a pick costs, how a weapon
+//! dulls from one kill to the next, when a potion is worth drinking. The lines are played
+//! on a copy of the game through `Game::apply`, so the rules always match the real thing,
+//! and only cards the player can see are in it: face-down and dark cards are left out.
+
+use crate::logic::{Action, Card, Game, GameState};
+use crate::render::card_text;
+
+/// Deep enough for a whole room: four cards, each with a weapon choice and a continue
+const LOOKAHEAD: u32 = 12;
+
+/// What to do next, and why
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hint {
+    pub action: Action,
+    pub reason: String,
+}
+
+/// The best move right now, or `None` when there's nothing to decide (menus, the shop,
+/// a finished run)
+pub fn hint(game: &Game) -> Option<Hint> {
+    let seen = visible_only(game);
+    let blind = (0..4).all(|i| game.visible_card(i).is_none());
+    match game.state {
+        GameState::RoomChoice | GameState::CardSelection if blind => Some(Hint {
+            action: match game.state {
+                GameState::RoomChoice => Action::Face,
+                _ => Action::Play((0..4).find(|&i| game.room_slots[i].is_some())?),
+            },
+            reason: "You can't see a single card here, so it's anyone's guess.".to_string(),
+        }),
+        GameState::RoomChoice => Some(room_choice(game, &seen)),
+        GameState::CardSelection => play(game, &seen),
+        GameState::CardInteraction if game.awaiting_weapon_choice => play(game, &seen),
+        GameState::CardInteraction | GameState::Trap => Some(Hint {
+            action: Action::Continue,
+            reason: "Nothing to weigh up here: press Enter to carry on.".to_string(),
+        }),
+        _ => None,
+    }
+}
+
+// ==============================
+// Search
+// ==============================
+
+/// One way of playing the room out, and how well things stand at the end of it
+struct Line {
+    score: i32,
+    health: i32,
+    died: bool,
+    actions: Vec<Action>,
+}
+
+/// The game as the player sees it: cards they can't see aren't there to be played
+fn visible_only(game: &Game) -> Game {
+    let mut seen = game.clone();
+    for i in 0..4 {
+        if game.visible_card(i).is_none() {
+            seen.room_slots[i] = None;
+        }
+    }
+    seen.hidden = [false; 4];
+    seen.dark = [false; 4];
+    seen
+}
+
+/// The best line from here, first of the equals in slot order
+fn best_line(game: &Game, depth: u32) -> Line {
+    let end = || Line {
+        score: standing(game),
+        health: game.health,
+        died: game.state == GameState::GameOver && !game.survived,
+        actions: Vec::new(),
+    };
+    if depth == 0 || game.state == GameState::GameOver {
+        return end();
+    }
+    let options: Vec<Action> = match game.state {
+        GameState::CardSelection => (0..4)
+            .filter(|&i| game.room_slots[i].is_some())
+            .map(Action::Play)
+            .collect(),
+        GameState::CardInteraction if game.awaiting_weapon_choice => game.legal_actions(),
+        GameState::CardInteraction | GameState::Trap => vec![Action::Continue],
+        _ => Vec::new(),
+    };
+    options
+        .into_iter()
+        .rev()
+        .map(|action| {
+            let mut next = game.clone();
+            next.apply(action);
+            next.settle_death();
+            let mut line = best_line(&next, depth - 1);
+            line.actions.insert(0, action);
+            line
+        })
+        .max_by_key(|line| line.score)
+        .unwrap_or_else(end)
+}
+
+/// Health counts double; a weapon counts for the monsters it can still hit. Dying is the
+/// worst there is, and a win the best.
+fn standing(game: &Game) -> i32 {
+    if game.state == GameState::GameOver {
+        return if game.survived { i32::MAX } else { i32::MIN };
+    }
+    let weapon = match (game.weapon(), game.last_slain()) {
+        (Some(w), Some(last)) => i32::from(w.value.min(last.saturating_sub(1))),
+        (Some(w), None) => i32::from(w.value),
+        _ => 0,
+    };
+    game.health * 2 + weapon
+}
+
+/// A card picked along a line, and how it went
+struct Step {
+    card: Card,
+    /// Fought with a weapon (monsters only)
+    armed: bool,
+    /// HP lost to it
+    cost: i32,
+}
+
+fn steps(game: &Game, actions: &[Action]) -> Vec<Step> {
+    let mut game = game.clone();
+    let mut steps: Vec<Step> = Vec::new();
+    // A weapon prompt already up belongs to the monster it's about
+    if let Some(monster) = game.current_monster.filter(|_| game.awaiting_weapon_choice) {
+        steps.push(Step {
+            card: monster,
+            armed: false,
+            cost: 0,
+        });
+    }
+    for &action in actions {
+        let before = game.health;
+        if let Action::Play(i) = action
+            && let Some(card) = game.room_slots[i]
+        {
+            steps.push(Step {
+                card,
+                armed: false,
+                cost: 0,
+            });
+        }
+        game.apply(action);
+        if let Some(step) = steps.last_mut() {
+            step.armed |= matches!(action, Action::Fight { weapon: Some(_) });
+            step.cost += before - game.health;
+        }
+    }
+    steps
+}
+
+// ==============================
+// Reasons
+// ==============================
+
+/// Picking a card, or answering the weapon prompt: the first move of the best line
+fn play(game: &Game, seen: &Game) -> Option<Hint> {
+    let line = best_line(seen, LOOKAHEAD);
+    let &action = line.actions.first()?;
+    let steps = steps(seen, &line.actions);
+    let reason = match action {
+        Action::Fight { weapon } => fight_reason(game, weapon, &steps),
+        _ => pick_reason(game, &steps),
+    };
+    let ending = if line.died {
+        "Every line from here is fatal; this one is the least bad.".to_string()
+    } else {
+        format!("You'd end the room on {} HP.", line.health)
+    };
+    Some(Hint {
+        action,
+        reason: format!("{reason} {ending}"),
+    })
+}
+
+fn room_choice(game: &Game, seen: &Game) -> Hint {
+    let mut faced = seen.clone();
+    faced.apply(Action::Face);
+    faced.settle_death();
+    let line = best_line(&faced, LOOKAHEAD);
+    let unseen = (0..4).any(|i| game.room_slots[i].is_some() && game.visible_card(i).is_none());
+    let unseen = if unseen {
+        " (not counting what you can't see)"
+    } else {
+        ""
+    };
+    let cost = game.health - line.health;
+    let skip = game.skip_allowed();
+
+    let (action, reason) = if line.died && skip {
+        (
+            Action::Skip,
+            format!("Every way through this room kills you{unseen}."),
+        )
+    } else if line.died {
+        (
+            Action::Face,
+            format!("It could kill you{unseen}, but there's no skip to be had."),
+        )
+    } else if skip && cost * 2 >= game.health {
+        (
+            Action::Skip,
+            format!(
+                "Even played well it costs {cost} of your {} HP{unseen}.",
+                game.health
+            ),
+        )
+    } else if cost <= 0 {
+        (
+            Action::Face,
+            format!(
+                "Played well it costs you nothing{unseen}, ending on {} HP.",
+                line.health
+            ),
+        )
+    } else {
+        let keep = if skip {
+            " Keep the skip for worse."
+        } else {
+            ""
+        };
+        (
+            Action::Face,
+            format!(
+                "Played well it costs {cost} HP{unseen}, ending on {}.{keep}",
+                line.health
+            ),
+        )
+    };
+    Hint { action, reason }
+}
+
+/// Why the first card of the best line goes first
+fn pick_reason(game: &Game, steps: &[Step]) -> String {
+    let Some(first) = steps.first() else {
+        return String::new();
+    };
+    let card = first.card;
+    let chain: Vec<Card> = steps.iter().filter(|s| s.armed).map(|s| s.card).collect();
+    match card.suit {
+        'S' | 'C' if first.armed && chain.len() > 1 => format!(
+            "Biggest first: a weapon only takes weaker monsters after a kill, so the chain is {}.",
+            chain
+                .iter()
+                .map(|&c| card_text(c))
+                .collect::<Vec<_>>()
+                .join(" → ")
+        ),
+        'S' | 'C' if first.armed => format!(
+            "Your weapon takes it for {} instead of {}.",
+            first.cost,
+            game.monster_strength(card)
+        ),
+        'S' | 'C' => match chain.first() {
+            Some(&kept) => format!(
+                "Take it bare for {}, keeping your weapon fresh for the {}.",
+                first.cost,
+                card_text(kept)
+            ),
+            None => format!("Nothing you hold can take it, so it costs {}.", first.cost),
+        },
+        'D' if game.is_armor(card) => "Put it on before the hits land.".to_string(),
+        'D' if !chain.is_empty() => format!(
+            "Equip it first, so {} in this room {} it instead of you.",
+            match chain.len() {
+                1 => "a monster".to_string(),
+                n => format!("{n} monsters"),
+            },
+            if chain.len() == 1 { "meets" } else { "meet" }
+        ),
+        'D' => match (game.weapon(), game.last_slain()) {
+            (None, _) => "You've no weapon yet.".to_string(),
+            (Some(_), Some(last)) => {
+                format!("A fresh weapon: yours only takes monsters under {last} now.")
+            }
+            (Some(w), None) => format!("It beats the {} in hand.", card_text(w)),
+        },
+        _ if first.cost < 0 => format!(
+            "You're {} HP down, and it heals {} of that.",
+            game.max_health - game.health,
+            -first.cost
+        ),
+        _ if game.potions_this_room >= game.rules.potion_limit => {
+            "It heals nothing past the room's potion limit, but it uses up a pick for free."
+                .to_string()
+        }
+        _ => "At full health it heals nothing, but it uses up a pick for free.".to_string(),
+    }
+}
+
+/// Why to fight with the weapon or without it, at the weapon prompt
+fn fight_reason(game: &Game, weapon: Option<usize>, steps: &[Step]) -> String {
+    let Some(monster) = game.current_monster else {
+        return String::new();
+    };
+    let strength = game.monster_strength(monster);
+    match weapon {
+        Some(hand) => format!(
+            "The weapon takes it for {} instead of {strength}.",
+            game.hand_damage(hand, monster)
+        ),
+        None => match steps.iter().skip(1).find(|s| s.armed) {
+            Some(later) => format!(
+                "Bare-handed costs {strength} now, but keeps your weapon able to take the {}.",
+                card_text(later.card)
+            ),
+            None => {
+                format!("Bare-handed costs {strength}, and your weapon is worth more kept fresh.")
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::Setup;
+    use crate::rules::Ruleset;
+
+    fn card(code: &str) -> Card {
+        Card::from_code(code).unwrap()
+    }
+
+    /// A dungeon of exactly `room` then `deck`, at the room choice
+    fn position(health: i32, weapon: Option<&str>, room: &[&str], deck: &[&str]) -> Game {
+        let mut setup = Setup::new(Ruleset::default());
+        setup.health = health;
+        setup.weapon = weapon.map(card);
+        setup.room = room.iter().map(|c| card(c)).collect();
+        setup.deck = deck.iter().map(|c| card(c)).collect();
+        setup.fill = false;
+        Game::from_setup(&setup).unwrap()
+    }
+
+    const REST: &[&str] = &["2C", "3C", "4C", "5C", "6C", "7C", "8C"];
+
+    #[test]
+    fn a_fatal_room_is_skipped() {
+        let game = position(5, None, &["KS", "QS", "KC", "QC"], REST);
+        let hint = hint(&game).unwrap();
+        assert_eq!(hint.action, Action::Skip);
+        assert!(
+            hint.reason
+                .starts_with("Every way through this room kills you")
+        );
+    }
+
+    #[test]
+    fn the_weapon_chain_goes_biggest_first() {
+        let mut game = position(20, Some("10D"), &["3S", "5S", "9C", "4S"], REST);
+        game.apply(Action::Face);
+
+        let line = best_line(&visible_only(&game), LOOKAHEAD);
+        let picked: Vec<Card> = steps(&game, &line.actions).iter().map(|s| s.card).collect();
+        assert_eq!(picked, [card("9C"), card("5S"), card("4S")]);
+        assert_eq!(line.health, 20);
+
+        let hint = hint(&game).unwrap();
+        assert_eq!(hint.action, Action::Play(2));
+        assert!(hint.reason.starts_with("Biggest first"));
+    }
+
+    #[test]
+    fn a_face_down_card_is_left_out() {
+        // Drinking the potion first would see the room through, but it can't be seen
+        let mut game = position(5, None, &["10H", "4S", "3S", "2S"], REST);
+        game.apply(Action::Face);
+        game.hidden[0] = true;
+
+        let line = best_line(&visible_only(&game), LOOKAHEAD);
+        assert!(!line.actions.contains(&Action::Play(0)));
+        assert!(line.died);
+
+        let hint = hint(&game).unwrap();
+        assert_ne!(hint.action, Action::Play(0));
+        assert!(hint.reason.contains("Every line from here is fatal"));
+    }
+}
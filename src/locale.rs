@@ -0,0 +1,171 @@
+//! Number and date formatting
+//!
+//! Scores, percentages, timers and dates on every screen are written through here, so they
+//! all follow `locale` in the config. Only how numbers and dates are written changes; the
+//! game's text stays English, and typed dates (`scores since 2026-10-01`) and files keep
+//! the ISO form.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::clock;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DateOrder {
+    /// `2026-10-16`
+    Iso,
+    /// `10/16/2026`
+    MonthFirst,
+    /// `16/10/2026`, or with `.` between
+    DayFirst(char),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Locale {
+    pub name: &'static str,
+    /// Between thousands, if anything
+    group: Option<char>,
+    decimal: char,
+    /// Between a number and its `%`
+    percent_gap: &'static str,
+    date: DateOrder,
+}
+
+/// The default: how the game has always written things
+pub const EN: Locale = Locale {
+    name: "en",
+    group: None,
+    decimal: '.',
+    percent_gap: "",
+    date: DateOrder::Iso,
+};
+
+pub const LOCALES: &[Locale] = &[
+    EN,
+    Locale {
+        name: "en-us",
+        group: Some(','),
+        decimal: '.',
+        percent_gap: "",
+        date: DateOrder::MonthFirst,
+    },
+    Locale {
+        name: "en-gb",
+        group: Some(','),
+        decimal: '.',
+        percent_gap: "",
+        date: DateOrder::DayFirst('/'),
+    },
+    Locale {
+        name: "de",
+        group: Some('.'),
+        decimal: ',',
+        percent_gap: " ",
+        date: DateOrder::DayFirst('.'),
+    },
+    Locale {
+        name: "es",
+        group: Some('.'),
+        decimal: ',',
+        percent_gap: " ",
+        date: DateOrder::DayFirst('/'),
+    },
+    Locale {
+        name: "fr",
+        group: Some(' '),
+        decimal: ',',
+        percent_gap: " ",
+        date: DateOrder::DayFirst('/'),
+    },
+    Locale {
+        name: "pt-br",
+        group: Some('.'),
+        decimal: ',',
+        percent_gap: "",
+        date: DateOrder::DayFirst('/'),
+    },
+];
+
+static LOCALE: AtomicUsize = AtomicUsize::new(0);
+
+pub fn by_name(name: &str) -> Option<&'static Locale> {
+    LOCALES.iter().find(|l| l.name == name.to_lowercase())
+}
+
+/// Every known locale, comma-separated (for error messages)
+pub fn names() -> String {
+    LOCALES
+        .iter()
+        .map(|l| l.name)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub fn set_locale(locale: &Locale) {
+    if let Some(i) = LOCALES.iter().position(|l| l.name == locale.name) {
+        LOCALE.store(i, Ordering::Relaxed);
+    }
+}
+
+pub fn locale() -> &'static Locale {
+    &LOCALES[LOCALE.load(Ordering::Relaxed)]
+}
+
+// ==============================
+// Formatting
+// ==============================
+
+/// A whole number, e.g. `12,345` (en-us) or `-1.234` (de)
+pub fn int(n: i64) -> String {
+    let digits = n.unsigned_abs().to_string();
+    let mut out = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if let Some(sep) = locale().group
+            && i > 0
+            && (digits.len() - i).is_multiple_of(3)
+        {
+            out.push(sep);
+        }
+        out.push(c);
+    }
+    if n < 0 { format!("-{out}") } else { out }
+}
+
+/// `whole` and one decimal place, e.g. `3.1` or `3,1`
+pub fn tenths(whole: u64, tenth: u64) -> String {
+    format!("{}{}{tenth}", int(whole as i64), locale().decimal)
+}
+
+/// e.g. `41%` or `41 %`
+pub fn percent(n: u64) -> String {
+    format!("{}{}%", int(n as i64), locale().percent_gap)
+}
+
+/// A percentage to one decimal place, from parts per thousand, e.g. `3.1%`
+pub fn percent_tenths(permille: u64) -> String {
+    format!(
+        "{}{}%",
+        tenths(permille / 10, permille % 10),
+        locale().percent_gap
+    )
+}
+
+/// Calendar date (UTC) of a `clock::now_ms` timestamp
+pub fn date(ms: u64) -> String {
+    let (year, month, day) = clock::civil(ms);
+    match locale().date {
+        DateOrder::Iso => format!("{year:04}-{month:02}-{day:02}"),
+        DateOrder::MonthFirst => format!("{month:02}/{day:02}/{year:04}"),
+        DateOrder::DayFirst(sep) => format!("{day:02}{sep}{month:02}{sep}{year:04}"),
+    }
+}
+
+/// A stopwatch reading, e.g. `1:23.4` or `1:23,4`
+pub fn time(ms: u64) -> String {
+    format!(
+        "{}:{:02}{}{}",
+        ms / 60_000,
+        ms / 1000 % 60,
+        locale().decimal,
+        ms / 100 % 10
+    )
+}
@@ -1,26 +1,809 @@
 //! Game logic
 
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
 use std::collections::VecDeque;
 
+use crate::clock::{self, PlayClock};
 use crate::messages as msg;
+use crate::naming;
+use crate::render::card_text;
+use crate::rules::{Degrade, DiscardView, EqualFight, Ruleset, SkipOrder, SkipPolicy};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Card {
     pub suit: char, // 'S', 'C', 'D', 'H'
-    pub value: u8,  // 2-14 (ace is 14)
+    pub value: u8,  // 2-14 (ace is 14), 16-18 for the `bosses` rule's bosses
+}
+
+impl Card {
+    /// Plain-ASCII code like `AS`, `10H` or `7D`, used in save files and exports
+    pub fn code(self) -> String {
+        let v = match self.value {
+            11 => "J".to_string(),
+            12 => "Q".to_string(),
+            13 => "K".to_string(),
+            14 => "A".to_string(),
+            _ => self.value.to_string(),
+        };
+        format!("{v}{}", self.suit)
+    }
+
+    /// Spades and clubs
+    pub fn is_monster(self) -> bool {
+        matches!(self.suit, 'S' | 'C')
+    }
+
+    pub fn is_boss(self) -> bool {
+        Boss::of(self).is_some()
+    }
+
+    /// Inverse of `code` (case-insensitive)
+    pub fn from_code(code: &str) -> Option<Card> {
+        let code = code.trim().to_ascii_uppercase();
+        let suit = code.chars().last()?;
+        if !matches!(suit, 'S' | 'C' | 'D' | 'H') {
+            return None;
+        }
+
+        let value = match &code[..code.len() - 1] {
+            "J" => 11,
+            "Q" => 12,
+            "K" => 13,
+            "A" => 14,
+            n => n
+                .parse()
+                .ok()
+                .filter(|v| (2..=10).contains(v) || *v >= 16)?,
+        };
+        let card = Card { suit, value };
+        (value <= 14 || card.is_boss()).then_some(card)
+    }
+}
+
+/// Weapons stronger than this can't hurt the Troll
+pub const TROLL_MAX_WEAPON: u8 = 8;
+
+/// A monster beyond the deck, dealt in every few rooms under the `bosses` rule. Each one
+/// bends the weapon rules its own way.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Boss {
+    /// Ignores weapons over `TROLL_MAX_WEAPON`
+    Troll,
+    /// Shatters any weapon that hits it
+    Golem,
+    /// Weapons only take off half their value
+    Dragon,
+}
+
+/// In the order they turn up
+pub const BOSSES: &[Boss] = &[Boss::Troll, Boss::Golem, Boss::Dragon];
+
+impl Boss {
+    pub fn card(self) -> Card {
+        match self {
+            Boss::Troll => Card {
+                suit: 'S',
+                value: 16,
+            },
+            Boss::Golem => Card {
+                suit: 'C',
+                value: 17,
+            },
+            Boss::Dragon => Card {
+                suit: 'S',
+                value: 18,
+            },
+        }
+    }
+
+    pub fn of(card: Card) -> Option<Boss> {
+        BOSSES.iter().copied().find(|b| b.card() == card)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Boss::Troll => "Troll",
+            Boss::Golem => "Golem",
+            Boss::Dragon => "Dragon",
+        }
+    }
+
+    /// Its twist, in a few words
+    pub fn twist(self) -> String {
+        match self {
+            Boss::Troll => format!("weapons over {TROLL_MAX_WEAPON} can't hurt it"),
+            Boss::Golem => "any weapon that hits it shatters".to_string(),
+            Boss::Dragon => "weapons only take off half their value".to_string(),
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum GameState {
     MainMenu,
+    /// Main menu variant shown at launch when an autosave exists: "Resume previous run?"
+    ResumePrompt,
     RoomChoice,
     CardSelection,
     /// Used for both "acknowledge" steps and weapon prompt
     CardInteraction,
+    /// Between rooms under the `shop` rule, or mid-room at a `merchant`: spend gold, then
+    /// leave
+    Shop,
+    /// `traps`: a trap sprang as the room was faced. Continuing goes on to pick cards.
+    Trap,
     GameOver,
 }
 
+/// A single in-dungeon player decision, independent of how it was entered (typed, clicked)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Face,
+    Skip,
+    Reshuffle,
+    /// Redeal the opening room (`mulligan` rule)
+    Mulligan,
+    /// Take the card in room slot `0..4` out of the game (`banish` rule)
+    Banish(usize),
+    /// Play the card in room slot `0..4`
+    Play(usize),
+    /// Put the potion or weapon in room slot `0..4` in the pack (`inventory` rule)
+    Stash(usize),
+    /// Drink or wield the `n`th card in the pack (0-based)
+    Use(usize),
+    /// Answer the weapon prompt: the hand whose weapon to fight with (0 is the main
+    /// hand), or `None` for bare-handed
+    Fight {
+        weapon: Option<usize>,
+    },
+    /// Acknowledge a resolved interaction
+    Continue,
+    /// Spend gold in the shop
+    Buy(Ware),
+    /// Leave the shop for the next room, or back to the room after a merchant
+    Leave,
+}
+
+impl Action {
+    /// Short ASCII code like `face`, `play3` or `fight-bare`, used in save files and logs
+    pub fn code(self) -> String {
+        match self {
+            Action::Face => "face".to_string(),
+            Action::Skip => "skip".to_string(),
+            Action::Reshuffle => "reshuffle".to_string(),
+            Action::Mulligan => "mulligan".to_string(),
+            Action::Banish(i) => format!("banish{}", i + 1),
+            Action::Play(i) => format!("play{}", i + 1),
+            Action::Stash(i) => format!("stash{}", i + 1),
+            Action::Use(i) => format!("use{}", i + 1),
+            Action::Fight { weapon: Some(0) } => "fight-weapon".to_string(),
+            Action::Fight { weapon: Some(hand) } => format!("fight-weapon{}", hand + 1),
+            Action::Fight { weapon: None } => "fight-bare".to_string(),
+            Action::Continue => "continue".to_string(),
+            Action::Buy(ware) => format!("buy-{}", ware.code()),
+            Action::Leave => "leave".to_string(),
+        }
+    }
+
+    /// Inverse of `code`
+    pub fn from_code(code: &str) -> Option<Action> {
+        let action = match code {
+            "face" => Action::Face,
+            "skip" => Action::Skip,
+            "reshuffle" => Action::Reshuffle,
+            "mulligan" => Action::Mulligan,
+            "fight-weapon" => Action::Fight { weapon: Some(0) },
+            "fight-weapon2" => Action::Fight { weapon: Some(1) },
+            "fight-bare" => Action::Fight { weapon: None },
+            "continue" => Action::Continue,
+            "leave" => Action::Leave,
+            _ if code.starts_with("buy-") => Action::Buy(Ware::from_code(&code[4..])?),
+            _ if code.starts_with("banish") => {
+                let n: usize = code["banish".len()..].parse().ok()?;
+                Action::Banish(n.checked_sub(1).filter(|i| *i < 4)?)
+            }
+            _ if code.starts_with("stash") => {
+                let n: usize = code["stash".len()..].parse().ok()?;
+                Action::Stash(n.checked_sub(1).filter(|i| *i < 4)?)
+            }
+            _ if code.starts_with("use") => {
+                let n: usize = code["use".len()..].parse().ok()?;
+                Action::Use(n.checked_sub(1).filter(|i| *i < INVENTORY_SIZE)?)
+            }
+            _ => {
+                let n: usize = code.strip_prefix("play")?.parse().ok()?;
+                Action::Play(n.checked_sub(1).filter(|i| *i < 4)?)
+            }
+        };
+        Some(action)
+    }
+}
+
+/// What an ironman run won't allow while it's in progress (`Game::ironman_forbids`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Guarded {
+    /// A manual `save` (the autosave on quit still happens, so the run can be resumed)
+    Save,
+    /// Taking moves back. Loading a save over the run is the only way there is.
+    Undo,
+    /// Dealing the same dungeon over from the start
+    Retry,
+    /// Giving the run up for another seed (`seed N`)
+    Reseed,
+    /// Swapping the run for a position set up by hand on the sandbox screen
+    Sandbox,
+}
+
+/// How soon after an accepted action a click or button press is taken for the same press
+/// arriving twice (a double-click, a bouncy gamepad button) and dropped
+pub const LATCH_MS: u64 = 300;
+
+/// Cards a poisoning lasts under `status-effects`
+pub const POISON_CARDS: u8 = 3;
+
+/// What a face-card monster leaves behind when it hits, under `status-effects`. Each one
+/// costs 1 HP for every card played after it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Affliction {
+    /// From clubs J and up; wears off after `POISON_CARDS` cards
+    Poison,
+    /// From spades J and up; lasts until a potion is drunk
+    Bleed,
+}
+
+impl Affliction {
+    /// What `monster` inflicts if it gets through
+    pub fn from_monster(monster: Card) -> Option<Affliction> {
+        match monster.suit {
+            _ if monster.value < 11 => None,
+            'C' => Some(Affliction::Poison),
+            'S' => Some(Affliction::Bleed),
+            _ => None,
+        }
+    }
+
+    /// Short name used in save files
+    pub fn code(self) -> &'static str {
+        match self {
+            Affliction::Poison => "poison",
+            Affliction::Bleed => "bleed",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Affliction> {
+        [Affliction::Poison, Affliction::Bleed]
+            .into_iter()
+            .find(|a| a.code() == code)
+    }
+}
+
+/// A monster's special rule: a boss's twist, or what a face card leaves behind under
+/// `status-effects`. Shown on the card before it's fought, and logged when it goes off.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ability {
+    Boss(Boss),
+    Affliction(Affliction),
+}
+
+impl Ability {
+    /// Short name used in save files
+    pub fn code(self) -> &'static str {
+        match self {
+            Ability::Boss(Boss::Troll) => "troll",
+            Ability::Boss(Boss::Golem) => "golem",
+            Ability::Boss(Boss::Dragon) => "dragon",
+            Ability::Affliction(a) => a.code(),
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Ability> {
+        BOSSES
+            .iter()
+            .map(|&b| Ability::Boss(b))
+            .find(|a| a.code() == code)
+            .or_else(|| Affliction::from_code(code).map(Ability::Affliction))
+    }
+
+    /// A few words for the card face
+    pub fn tag(self) -> &'static str {
+        match self {
+            Ability::Boss(Boss::Troll) => "weapons ≤ 8",
+            Ability::Boss(Boss::Golem) => "shatters weapons",
+            Ability::Boss(Boss::Dragon) => "halves weapons",
+            Ability::Affliction(Affliction::Poison) => "poisons",
+            Ability::Affliction(Affliction::Bleed) => "bleeds",
+        }
+    }
+
+    /// The whole rule, e.g. `Golem: any weapon that hits it shatters`
+    pub fn text(self) -> String {
+        match self {
+            Ability::Boss(boss) => format!("{}: {}", boss.name(), boss.twist()),
+            Ability::Affliction(Affliction::Poison) => {
+                format!("Poison: a hit costs 1 HP a card for the next {POISON_CARDS} cards")
+            }
+            Ability::Affliction(Affliction::Bleed) => {
+                "Bleed: a hit costs 1 HP a card until you drink a potion".to_string()
+            }
+        }
+    }
+}
+
+/// An affliction in effect
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StatusEffect {
+    pub affliction: Affliction,
+    /// Cards still to tick; `None` lasts until cured
+    pub cards_left: Option<u8>,
+    /// `Game::cards_played` when it took hold, so the card that caused it doesn't tick it
+    pub since: u32,
+}
+
+/// Rooms resolved between visits to the shop
+pub const SHOP_EVERY: u32 = 4;
+/// Gold for playing a treasure card under the `shop` rule
+pub const TREASURE_GOLD: u32 = 5;
+/// HP a heal from the shop gives back
+pub const SHOP_HEAL: i32 = 5;
+
+/// HP the rest stop between `floors` gives back
+pub const REST_HEAL: i32 = 5;
+
+/// Something the between-room shop sells
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ware {
+    /// A few HP back
+    Heal,
+    /// The weapon in hand can hit anything again, as if it had never been used
+    Repair,
+    /// One skip whatever the skip rules say
+    SkipToken,
+    /// Hammer both weapons in hand into one stronger one (`dual-wield`)
+    Forge,
+}
+
+/// Everything on the shelf, in the order the shop screen lists it
+pub const WARES: &[Ware] = &[Ware::Heal, Ware::Repair, Ware::SkipToken, Ware::Forge];
+
+/// The shop without `dual-wield`: one weapon leaves nothing to forge
+pub const ONE_HAND_WARES: &[Ware] = &[Ware::Heal, Ware::Repair, Ware::SkipToken];
+
+/// What a `merchant` card has on offer: no repairs
+pub const MERCHANT_WARES: &[Ware] = &[Ware::Heal, Ware::SkipToken];
+
+/// `merchant`: how many cards are merchants when the deck is dealt
+pub const MERCHANT_COUNT: usize = 2;
+
+/// `inventory`: how many cards the pack holds
+pub const INVENTORY_SIZE: usize = 2;
+
+/// `Ware::Forge`: value lost when two weapons are hammered into one
+pub const FORGE_LOSS: u8 = 3;
+
+/// `Ware::Forge`: the best weapon a forge can turn out
+pub const FORGE_MAX: u8 = 10;
+
+impl Ware {
+    /// What the player types to buy it, also its code in save files
+    pub fn code(self) -> &'static str {
+        match self {
+            Ware::Heal => "heal",
+            Ware::Repair => "repair",
+            Ware::SkipToken => "token",
+            Ware::Forge => "forge",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Ware> {
+        WARES.iter().copied().find(|w| w.code() == code)
+    }
+
+    pub fn price(self) -> u32 {
+        match self {
+            Ware::Heal => 4,
+            Ware::Repair => 6,
+            Ware::SkipToken => 5,
+            Ware::Forge => 8,
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Ware::Heal => "Heal 5 HP",
+            Ware::Repair => "Repair your weapon so it can hit anything again",
+            Ware::SkipToken => "A skip token: skip one room whatever the skip rules say",
+            Ware::Forge => "Forge your two weapons into one: their values added, less 3, up to 10",
+        }
+    }
+}
+
+/// A weekly challenge twist layered over the rules. Unlike rule knobs these aren't picked
+/// by the player; the engine checks `Game::has` wherever one applies.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mutator {
+    /// Potions heal half their value, rounded up
+    HalfPotions,
+    NoSkips,
+    /// Start on 15 HP (the cap stays 20)
+    Wounded,
+    /// J, Q, K and A monsters count 1 stronger when dealing damage
+    Brutes,
+}
+
+/// Every mutator, in the order the weekly draw picks from
+pub const MUTATORS: &[Mutator] = &[
+    Mutator::HalfPotions,
+    Mutator::NoSkips,
+    Mutator::Wounded,
+    Mutator::Brutes,
+];
+
+impl Mutator {
+    /// Short name used in save files
+    pub fn code(self) -> &'static str {
+        match self {
+            Mutator::HalfPotions => "half-potions",
+            Mutator::NoSkips => "no-skips",
+            Mutator::Wounded => "wounded",
+            Mutator::Brutes => "brutes",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Mutator> {
+        MUTATORS.iter().copied().find(|m| m.code() == code)
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Mutator::HalfPotions => "Potions heal half (rounded up)",
+            Mutator::NoSkips => "Skips disabled: every room must be faced",
+            Mutator::Wounded => "You enter the dungeon wounded, on 15 HP",
+            Mutator::Brutes => "Face-card and ace monsters hit 1 harder",
+        }
+    }
+}
+
+/// What a New Game+ run brings along from the win before it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Relic {
+    /// The weapon in this hand (0 is the main hand)
+    Weapon(usize),
+    Armor,
+}
+
+/// Potions a dungeon always keeps, however deep into New Game+
+pub const PLUS_MIN_POTIONS: usize = 4;
+
+/// One rung of the ascension ladder
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Handicap {
+    MaxHealth(u8),
+    EqualFight(EqualFight),
+    Skips(SkipPolicy),
+    /// A boss every this many rooms
+    Bosses(u8),
+}
+
+/// The ascension ladder, bottom rung first. Ascension `n` is the standard game under the
+/// first `n` handicaps, later ones taking over from earlier ones where they overlap.
+pub const ASCENSIONS: &[Handicap] = &[
+    Handicap::MaxHealth(18),
+    Handicap::EqualFight(EqualFight::WeaponBreaks),
+    Handicap::Skips(SkipPolicy::Once),
+    Handicap::MaxHealth(16),
+    Handicap::Bosses(6),
+    Handicap::Skips(SkipPolicy::Never),
+    Handicap::MaxHealth(14),
+];
+
+impl Handicap {
+    pub fn description(self) -> String {
+        match self {
+            Handicap::MaxHealth(hp) => format!("you start on {hp} HP"),
+            Handicap::EqualFight(EqualFight::WeaponBreaks) => {
+                "a weapon shatters on a monster of its own value".to_string()
+            }
+            Handicap::EqualFight(EqualFight::Chip) => {
+                "a monster of the weapon's own value still chips 1 HP".to_string()
+            }
+            Handicap::EqualFight(EqualFight::FreeKill) => "equal fights are free".to_string(),
+            Handicap::Skips(SkipPolicy::Once) => "one skip for the whole run".to_string(),
+            Handicap::Skips(SkipPolicy::Never) => "no skips at all".to_string(),
+            Handicap::Skips(policy) => format!("skips: {}", policy.name()),
+            Handicap::Bosses(every) => format!("a boss every {every} rooms"),
+        }
+    }
+
+    fn apply(self, rules: &mut Ruleset) {
+        match self {
+            Handicap::MaxHealth(hp) => rules.max_health = hp,
+            Handicap::EqualFight(equal) => rules.equal_fight = equal,
+            Handicap::Skips(policy) => rules.skips = policy,
+            Handicap::Bosses(every) => rules.bosses = every,
+        }
+    }
+}
+
+/// The rules at ascension `level`: the standard game with that many rungs of handicaps
+pub fn ascension_rules(level: u32) -> Ruleset {
+    let mut rules = Ruleset::default();
+    for handicap in ASCENSIONS.iter().take(level as usize) {
+        handicap.apply(&mut rules);
+    }
+    rules
+}
+
+/// How far into the dungeon a run is, going by the cards left. Only the room panel uses it
+/// so far, but anything that should change as the run goes deeper can ask `Game::depth`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Depth {
+    UpperHalls,
+    Catacombs,
+    TheDepths,
+}
+
+impl Depth {
+    pub fn name(self) -> &'static str {
+        match self {
+            Depth::UpperHalls => "Upper Halls",
+            Depth::Catacombs => "Catacombs",
+            Depth::TheDepths => "The Depths",
+        }
+    }
+}
+
+/// Cards in a fresh dungeon: every spade and club, plus diamonds and hearts 2-10
+pub const DECK_SIZE: usize = 44;
+
+/// A rule that just came into play, explained the first time a player runs into it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Lesson {
+    /// A weapon killed its first monster and can now only fight weaker ones
+    WeaponDegraded,
+    /// A second potion in one room
+    PotionWasted,
+    /// The room after a skip has to be faced
+    ForcedFace,
+}
+
+impl Lesson {
+    /// Stored in the profile once seen. Never change one once released.
+    pub fn key(self) -> &'static str {
+        match self {
+            Lesson::WeaponDegraded => "weapon-degraded",
+            Lesson::PotionWasted => "potion-wasted",
+            Lesson::ForcedFace => "forced-face",
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Lesson::WeaponDegraded => msg::LESSON_DEGRADED_TITLE,
+            Lesson::PotionWasted => msg::LESSON_POTION_TITLE,
+            Lesson::ForcedFace => msg::LESSON_FORCED_TITLE,
+        }
+    }
+
+    pub fn text(self) -> &'static str {
+        match self {
+            Lesson::WeaponDegraded => msg::LESSON_DEGRADED,
+            Lesson::PotionWasted => msg::LESSON_POTION,
+            Lesson::ForcedFace => msg::LESSON_FORCED,
+        }
+    }
+}
+
+/// `armor`: how many times its own value a piece of armor soaks up before it falls apart
+pub const ARMOR_WEAR: u8 = 2;
+
+/// Armor being worn under the `armor` rule: it takes its value off every monster's hit
+/// until it has soaked up `ARMOR_WEAR` times that much
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Armor {
+    pub card: Card,
+    /// Damage soaked up so far
+    pub absorbed: u8,
+}
+
+impl Armor {
+    pub fn new(card: Card) -> Self {
+        Self { card, absorbed: 0 }
+    }
+
+    /// Damage it can still soak up
+    pub fn left(self) -> u8 {
+        (self.card.value * ARMOR_WEAR).saturating_sub(self.absorbed)
+    }
+
+    /// How much of a `dmg` hit it takes
+    pub fn soaks(self, dmg: i32) -> i32 {
+        dmg.clamp(0, i32::from(self.card.value.min(self.left())))
+    }
+}
+
+/// What a monster's hit did once armor had its say
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Hit {
+    taken: i32,
+    soaked: i32,
+    armor_broke: bool,
+}
+
+impl Hit {
+    /// Tacked onto the fight message when armor was involved
+    fn note(self) -> String {
+        match (self.soaked, self.armor_broke) {
+            (0, _) => String::new(),
+            (soaked, false) => format!(" Your armor soaked up {soaked}."),
+            (soaked, true) => format!(" Your armor soaked up {soaked} and fell apart."),
+        }
+    }
+}
+
+/// `traps`: how many cards are trapped when the deck is dealt
+pub const TRAP_COUNT: usize = 4;
+
+/// What a trapped card does as its room is faced: black cards hide spikes, red ones a snare
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Trap {
+    /// Half the card's value in damage, rounded up
+    Spikes,
+    /// No weapon for the next pick
+    Snare,
+}
+
+impl Trap {
+    pub fn of(card: Card) -> Self {
+        if card.is_monster() {
+            Trap::Spikes
+        } else {
+            Trap::Snare
+        }
+    }
+
+    pub fn damage(card: Card) -> i32 {
+        (i32::from(card.value) + 1) / 2
+    }
+}
+
+/// A weapon in hand and how far it has degraded
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Wielded {
+    pub card: Card,
+    /// Value of the last monster it slew
+    pub last_slain: Option<u8>,
+    /// Monsters it has slain (for its name)
+    pub kills: u32,
+}
+
+impl Wielded {
+    pub fn new(card: Card) -> Self {
+        Wielded {
+            card,
+            last_slain: None,
+            kills: 0,
+        }
+    }
+
+    /// The strongest monster it could still take on, near enough to compare two weapons
+    fn reach(self) -> u8 {
+        match self.last_slain {
+            Some(last) => self.card.value.min(last.saturating_sub(1)),
+            None => self.card.value,
+        }
+    }
+}
+
+/// The weapons in hand: one, or a second under `dual-wield`, each degrading on its own.
+/// Hand 0 is the main hand, which is what everything else means by "the weapon".
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Loadout {
+    pub hands: [Option<Wielded>; 2],
+}
+
+impl Loadout {
+    pub fn main(&self) -> Option<Wielded> {
+        self.hands[0]
+    }
+
+    pub fn off(&self) -> Option<Wielded> {
+        self.hands[1]
+    }
+
+    /// Take up `card`, handing back the weapon it replaces. With one hand that's always
+    /// the main one; with two, an empty hand is filled first, then the weaker is swapped.
+    fn equip(&mut self, card: Card, two_hands: bool) -> Option<Card> {
+        let hand = match self.hands {
+            [Some(main), Some(off)] if two_hands && off.reach() <= main.reach() => 1,
+            [Some(_), None] if two_hands => 1,
+            _ => 0,
+        };
+        self.hands[hand].replace(Wielded::new(card)).map(|w| w.card)
+    }
+
+    /// Both weapons good as new
+    fn repair(&mut self) {
+        for wielded in self.hands.iter_mut().flatten() {
+            wielded.last_slain = None;
+        }
+    }
+
+    fn degraded(&self) -> bool {
+        self.hands.iter().flatten().any(|w| w.last_slain.is_some())
+    }
+
+    /// What `Ware::Forge` would make of the two weapons in hand: `None` with fewer than
+    /// two, or when it wouldn't beat the better of them
+    pub fn forged(&self) -> Option<Card> {
+        let [Some(main), Some(off)] = self.hands else {
+            return None;
+        };
+        let value = (main.card.value + off.card.value)
+            .saturating_sub(FORGE_LOSS)
+            .min(FORGE_MAX);
+        (value > main.card.value.max(off.card.value)).then_some(Card { suit: 'D', value })
+    }
+
+    /// Swap both weapons for what `forged` says, fresh in the main hand. Hands back the
+    /// two that went into it and the new one.
+    fn forge(&mut self) -> Option<([Card; 2], Card)> {
+        let forged = self.forged()?;
+        let [Some(main), Some(off)] = self.hands else {
+            return None;
+        };
+        self.hands = [Some(Wielded::new(forged)), None];
+        Some(([main.card, off.card], forged))
+    }
+}
+
+/// One accepted action in a run's event log
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LoggedEvent {
+    /// 1-based count of decisions so far, this one included
+    pub turn: u32,
+    /// Wall clock time from `clock::now_ms`
+    pub at_ms: u64,
+    pub action: Action,
+    /// HP once the action had resolved
+    pub health: i32,
+    /// The room card a `Play` or `Stash` picked up, or the pack's card for a `Use`
+    pub card: Option<Card>,
+    /// 1-based room the action was taken in
+    pub room: u32,
+    /// Weapon in hand once the action had resolved, and what it last slew
+    pub weapon: Option<Card>,
+    pub last_slain: Option<u8>,
+    /// A monster ability that went off while the action resolved
+    pub ability: Option<Ability>,
+}
+
+/// When a resolved card waits for Enter before play goes on (`acknowledge` in the config)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Acknowledge {
+    /// Only after a fight through the weapon prompt
+    #[default]
+    Auto,
+    /// After every card (classic)
+    Always,
+    /// After any card that cost HP
+    AfterDamage,
+}
+
+impl Acknowledge {
+    pub const VALUES: &str = "auto | always | damage";
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "auto" => Some(Acknowledge::Auto),
+            "always" => Some(Acknowledge::Always),
+            "damage" => Some(Acknowledge::AfterDamage),
+            _ => None,
+        }
+    }
+}
+
 /// Result of an action that may require an explicit "continue" (Enter) acknowledgement
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ResolveOutcome {
@@ -30,21 +813,58 @@ pub enum ResolveOutcome {
     AwaitContinue,
 }
 
+/// How picking a room card now would change your health (`Game::pick_hp_delta`)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HpDelta {
+    /// It only goes one way
+    Fixed(i32),
+    /// A monster a weapon can take: the change fighting with each hand that can (`Some`),
+    /// then bare-handed (`None`)
+    Choice(Vec<(Option<usize>, i32)>),
+}
+
 /// The core game model
+#[derive(Clone)]
 pub struct Game {
+    /// Variant rules for this run (carried over by `reset_to_playing`)
+    pub rules: Ruleset,
+
     pub deck: VecDeque<Card>,
+    /// Cards that have left play, oldest first. How much of it the player may see is
+    /// `rules.discard_view`.
+    pub discard: Vec<Card>,
 
     /// Stable room slots (always 4). `None` indicates an empty slot
     pub room_slots: [Option<Card>; 4],
+    /// `fog`: which slots hold a card dealt face down (see `is_hidden`)
+    pub hidden: [bool; 4],
+    /// `dark`: which slots hold a card whose value doesn't show yet (see `is_dark`)
+    pub dark: [bool; 4],
 
     pub health: i32,
     pub max_health: i32,
 
-    pub weapon: Option<Card>,
-    pub last_monster_slain_with_weapon: Option<u8>,
-    pub potion_used_this_room: bool,
+    pub loadout: Loadout,
+    /// Worn under the `armor` rule
+    pub armor: Option<Armor>,
+    /// Potions drunk in this room so far, wasted ones included
+    pub potions_this_room: u8,
+    /// Value of the last card played, if it was a potion (for `potion-bonus`)
+    pub last_played_potion: Option<u8>,
 
+    /// False right after a skip (what `skips = no-repeat` checks)
     pub can_skip: bool,
+    /// Remaining uses of the room reshuffle consumable (from `rules.room_reshuffles`)
+    pub reshuffles_left: u8,
+    /// The `mulligan` rule's one redeal has been taken
+    pub mulligan_used: bool,
+    /// When a resolved card waits for Enter. A preference rather than a rule, so it's
+    /// kept in step with the config instead of saved.
+    pub acknowledge: Acknowledge,
+    /// The card taken out of the game under the `banish` rule, if it's been used
+    pub banished: Option<Card>,
+    /// Set by a big enough hit under the `comeback` rule; the next room dealt is gentler
+    pub comeback_pending: bool,
     pub state: GameState,
     pub survived: bool,
 
@@ -60,134 +880,1473 @@ pub struct Game {
 
     /// After deciding to face a room, you get exactly 3 interactions
     pub interactions_left_in_room: u8,
+
+    /// When the run entered the dungeon (0 while on the menu)
+    pub started_at_ms: u64,
+    /// Time spent playing, pauses left out
+    pub play_clock: PlayClock,
+    /// Every accepted action, in order. Rejected ones aren't recorded.
+    pub events: Vec<LoggedEvent>,
+    /// The player's own scratch notes for this run (the notes panel)
+    pub notes: String,
+    /// Endless mode: clearing the dungeon deals another lap instead of winning
+    pub endless: bool,
+    /// Laps cleared so far in endless mode
+    pub laps: u32,
+    /// Every monster fought this run, whatever the lap (endless mode's score)
+    pub monsters_slain: u32,
+    /// `floors`: the floor being played, from 1
+    pub floor: u8,
+    /// `floors`: the decks of the floors still below, next one first
+    pub floors_ahead: Vec<Vec<Card>>,
+    /// `treasure-hunt`: the marked cards, and those played so far
+    pub treasures: Vec<Card>,
+    pub treasures_found: Vec<Card>,
+    /// `traps`: the trapped cards that haven't sprung yet
+    pub traps: Vec<Card>,
+    /// `merchant`: the merchant cards still to be played, and whether one has the shop
+    /// open right now
+    pub merchants: Vec<Card>,
+    pub at_merchant: bool,
+    /// `inventory`: potions and weapons stashed for later, oldest first
+    pub inventory: Vec<Card>,
+    /// `traps`: a snare has the weapon tied up until the next pick resolves
+    pub weapon_snared: bool,
+    /// `shop`: gold to spend, rooms resolved so far (every `SHOP_EVERY`th opens the shop)
+    /// and skip tokens bought
+    pub gold: u32,
+    pub rooms_resolved: u32,
+    pub skip_tokens: u8,
+    /// `status-effects`: poison and bleeding in effect
+    pub status_effects: Vec<StatusEffect>,
+    /// Cards played from rooms this run
+    pub cards_played: u32,
+    /// `barehanded-bonus`: score for monsters fought without a weapon
+    pub bonus_score: u32,
+    /// `treasure-score`: the value of every treasure played
+    pub treasure_bonus: u32,
+    /// Rules that came into play since the UI last took these. Not saved.
+    pub lessons: Vec<Lesson>,
+    /// A weapon the last fight left unable to touch any monster still to come, until the
+    /// UI takes it. Not saved.
+    pub dead_weapon: Option<Card>,
+    /// Dealt by hand on the sandbox screen: practice only, never scored
+    pub sandbox: bool,
+    /// No saves, loads or retries until the run is over (`ironman` on the main menu).
+    /// Marked in the score table.
+    pub ironman: bool,
+    /// The rung of the ascension ladder this run is played on (`ascend`), 0 for none
+    pub ascension: u32,
+    /// The score the player is after (`target N` on the main menu), which turns on the
+    /// pace coach at every room choice
+    pub target: Option<i32>,
+    /// New Game+ level: how many wins in a row this run follows on from, 0 for a fresh start
+    pub plus: u32,
+    /// The card New Game+ brought along from the win before
+    pub relic: Option<Card>,
+    /// Which go at this seed this is, counting from 1. Only `retry` goes past it.
+    pub attempt: u32,
+
+    /// Weekly challenge twists (empty for a normal run). Set before `start`.
+    pub mutators: Vec<Mutator>,
+
+    /// The same seed and rules deal the same dungeon
+    pub seed: u64,
+    /// Every shuffle in the run draws from this. Not saved: a loaded run reseeds it, so
+    /// later room reshuffles can differ from the original.
+    rng: StdRng,
 }
 
 impl Game {
-    pub fn new() -> Self {
+    /// A run with a random seed
+    pub fn new(rules: Ruleset) -> Self {
+        Self::with_seed(rules, rand::random())
+    }
+
+    pub fn with_seed(rules: Ruleset, seed: u64) -> Self {
+        let max_health = i32::from(rules.max_health);
         let mut g = Self {
+            reshuffles_left: rules.room_reshuffles,
+            mulligan_used: false,
+            acknowledge: Acknowledge::default(),
+            banished: None,
+            rules,
+
             deck: VecDeque::new(),
+            discard: Vec::new(),
             room_slots: [None, None, None, None],
+            hidden: [false; 4],
+            dark: [false; 4],
+
+            health: max_health,
+            max_health,
+
+            loadout: Loadout::default(),
+            armor: None,
+            potions_this_room: 0,
+            last_played_potion: None,
+
+            can_skip: true,
+            comeback_pending: false,
+            state: GameState::MainMenu,
+            survived: false,
+
+            message: String::new(),
+            last_command_feedback: String::new(),
+
+            current_monster: None,
+            awaiting_weapon_choice: false,
+
+            interactions_left_in_room: 0,
+
+            started_at_ms: 0,
+            play_clock: PlayClock::default(),
+            events: Vec::new(),
+            notes: String::new(),
+            endless: false,
+            laps: 0,
+            monsters_slain: 0,
+            floor: 1,
+            floors_ahead: Vec::new(),
+            treasures: Vec::new(),
+            treasures_found: Vec::new(),
+            traps: Vec::new(),
+            weapon_snared: false,
+            merchants: Vec::new(),
+            inventory: Vec::new(),
+            at_merchant: false,
+            gold: 0,
+            rooms_resolved: 0,
+            skip_tokens: 0,
+            status_effects: Vec::new(),
+            cards_played: 0,
+            bonus_score: 0,
+            treasure_bonus: 0,
+            lessons: Vec::new(),
+            dead_weapon: None,
+            sandbox: false,
+            ironman: false,
+            ascension: 0,
+            target: None,
+            plus: 0,
+            relic: None,
+            attempt: 1,
+
+            mutators: Vec::new(),
+
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        };
+
+        g.create_deck();
+        g
+    }
 
-            health: 20,
-            max_health: 20,
+    /// Draw every shuffle from here on from `seed` instead. The solver deals each rollout
+    /// its own future this way.
+    pub fn reseed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    /// A run starting from a hand-built position. The room is dealt from `setup.room`
+    /// first, then the top of the deck.
+    pub fn from_setup(setup: &Setup) -> Result<Self, String> {
+        setup.check()?;
+
+        let mut g = Self::new(setup.rules.clone());
+        let mut cards: Vec<Card> = setup.room.iter().chain(&setup.deck).copied().collect();
+        if setup.fill {
+            let mut rest: Vec<Card> = full_deck()
+                .into_iter()
+                .filter(|c| !setup.places(*c))
+                .collect();
+            rest.shuffle(&mut g.rng);
+            cards.extend(rest);
+        }
+        g.deal_cards(cards);
+
+        g.start();
+        g.health = setup.health;
+        g.loadout.hands[0] = setup.weapon.map(|card| Wielded {
+            card,
+            last_slain: setup.last_slain,
+            kills: u32::from(setup.last_slain.is_some()),
+        });
+        g.sandbox = true;
+        Ok(g)
+    }
+
+    /// Play the sandbox position `setup` in place of this run
+    pub fn play_setup(&mut self, setup: &Setup) -> Result<(), String> {
+        if let Some(reason) = self.ironman_forbids(Guarded::Sandbox) {
+            return Err(reason.to_string());
+        }
+        *self = Self::from_setup(setup)?;
+        Ok(())
+    }
+
+    /// `seed N`: a new run on `rules`, dealt from `seed`, in place of this one
+    pub fn deal_seed(&mut self, rules: Ruleset, seed: u64) -> Result<(), &'static str> {
+        if let Some(reason) = self.ironman_forbids(Guarded::Reseed) {
+            return Err(reason);
+        }
+        *self = Self::with_seed(rules, seed);
+        self.start();
+        Ok(())
+    }
+
+    /// Reset the game into a playable "in dungeon" state (RoomChoice + initial room filled)
+    pub fn reset_to_playing(&mut self) {
+        let endless = self.endless;
+        *self = Self::new(self.rules.clone());
+        self.endless = endless;
+        self.start();
+    }
+
+    /// Deal this same dungeon again from the top: same seed, rules, weekly twists,
+    /// endless flag and target, as go number `attempt`. Not for a sandbox position, or an
+    /// ironman run still being played.
+    pub fn retry(&mut self, attempt: u32) -> Result<(), &'static str> {
+        if self.sandbox {
+            return Err(msg::RETRY_SANDBOX);
+        }
+        if let Some(reason) = self.ironman_forbids(Guarded::Retry) {
+            return Err(reason);
+        }
+        let mut again = Self::with_seed(self.rules.clone(), self.seed);
+        again.endless = self.endless;
+        again.ironman = self.ironman;
+        again.ascension = self.ascension;
+        again.target = self.target;
+        again.mutators = self.mutators.clone();
+        again.attempt = attempt;
+        again.plus = self.plus;
+        again.relic = self.relic;
+        *self = again;
+        self.begin();
+        self.message = format!("The same dungeon again: attempt {attempt}.");
+        Ok(())
+    }
+
+    /// New Game+ after a win: a fresh dungeon on the same terms, one potion leaner for
+    /// each level, with `relic` (or whatever's in hand, if `None`) brought along
+    pub fn new_game_plus(&mut self, relic: Option<Relic>) -> Result<(), &'static str> {
+        if self.state != GameState::GameOver || !self.survived {
+            return Err(msg::PLUS_NEEDS_WIN);
+        }
+        if self.sandbox {
+            return Err(msg::PLUS_SANDBOX);
+        }
+        let carried = match relic {
+            Some(Relic::Weapon(hand)) => self
+                .loadout
+                .hands
+                .get(hand)
+                .copied()
+                .flatten()
+                .map(|w| w.card)
+                .ok_or(msg::PLUS_NO_WEAPON)?,
+            Some(Relic::Armor) => self.armor.map(|a| a.card).ok_or(msg::PLUS_NO_ARMOR)?,
+            None => self
+                .weapon()
+                .or(self.armor.map(|a| a.card))
+                .ok_or(msg::PLUS_NOTHING)?,
+        };
+
+        let mut next = Self::new(self.rules.clone());
+        next.ironman = self.ironman;
+        next.ascension = self.ascension;
+        next.mutators = self.mutators.clone();
+        next.plus = self.plus + 1;
+        next.relic = Some(carried);
+        *self = next;
+        self.begin();
+        self.message = format!(
+            "New Game+ {}: {} comes along, and the dungeon is a potion leaner.",
+            self.plus,
+            card_text(carried)
+        );
+        Ok(())
+    }
+
+    /// Deal the first room once the run's terms (seed, rules, mutators, New Game+ level and
+    /// relic) are set, as `retry` and `new_game_plus` do. An export is dealt again this way.
+    pub fn begin(&mut self) {
+        self.begin_plus();
+        self.start();
+    }
+
+    /// New Game+: take the relic and the top `plus` potions out of the dungeon before the
+    /// first room is dealt, then put the relic in hand as good as new
+    fn begin_plus(&mut self) {
+        if self.plus == 0 {
+            return;
+        }
+        let mut potions: Vec<Card> = self
+            .deck
+            .iter()
+            .chain(self.floors_ahead.iter().flatten())
+            .copied()
+            .filter(|c| c.suit == 'H')
+            .collect();
+        potions.sort_by_key(|c| std::cmp::Reverse(c.value));
+        let lost = (self.plus as usize).min(potions.len().saturating_sub(PLUS_MIN_POTIONS));
+        let mut gone: Vec<Card> = potions[..lost].to_vec();
+        gone.extend(self.relic);
+
+        self.deck.retain(|c| !gone.contains(c));
+        for floor in &mut self.floors_ahead {
+            floor.retain(|c| !gone.contains(c));
+        }
+        self.treasures.retain(|c| !gone.contains(c));
+        self.traps.retain(|c| !gone.contains(c));
+        self.merchants.retain(|c| !gone.contains(c));
+
+        match self.relic {
+            Some(card) if self.is_armor(card) => self.armor = Some(Armor::new(card)),
+            Some(card) => self.loadout.hands[0] = Some(Wielded::new(card)),
+            None => {}
+        }
+    }
+
+    /// Leave the main menu and deal the first room
+    pub fn start(&mut self) {
+        self.health = self.starting_health();
+        self.started_at_ms = clock::now_ms();
+        self.play_clock = PlayClock::default();
+        self.state = GameState::RoomChoice;
+        self.fill_room();
+        self.message = msg::ENTERED_DUNGEON.to_string();
+    }
+
+    /// Turn this into an ironman run. A sandbox position can't be one, since it was set
+    /// up by hand.
+    pub fn make_ironman(&mut self) -> Result<(), &'static str> {
+        if self.sandbox {
+            return Err(msg::IRONMAN_SANDBOX);
+        }
+        self.ironman = true;
+        Ok(())
+    }
+
+    /// Why `what` isn't allowed right now, if this is an ironman run still being played
+    pub fn ironman_forbids(&self, what: Guarded) -> Option<&'static str> {
+        if !self.ironman || !self.in_progress() {
+            return None;
+        }
+        Some(match what {
+            Guarded::Save => msg::IRONMAN_SAVE,
+            Guarded::Undo => msg::IRONMAN_UNDO,
+            Guarded::Retry => msg::IRONMAN_RETRY,
+            Guarded::Reseed => msg::IRONMAN_RESEED,
+            Guarded::Sandbox => msg::IRONMAN_SANDBOX_SWAP,
+        })
+    }
+
+    /// Every action the engine would accept right now
+    pub fn legal_actions(&self) -> Vec<Action> {
+        match self.state {
+            GameState::RoomChoice => {
+                let mut actions = vec![Action::Face];
+                if self.skip_allowed() {
+                    actions.push(Action::Skip);
+                }
+                if self.reshuffles_left > 0 {
+                    actions.push(Action::Reshuffle);
+                }
+                if self.mulligan_available() {
+                    actions.push(Action::Mulligan);
+                }
+                actions
+            }
+            GameState::CardSelection => {
+                let occupied = || (0..4).filter(|&i| self.room_slots[i].is_some());
+                let mut actions: Vec<Action> = occupied().map(Action::Play).collect();
+                if self.rules.banish && self.banished.is_none() && actions.len() > 1 {
+                    actions.extend(occupied().map(Action::Banish));
+                }
+                if self.rules.inventory {
+                    if self.inventory.len() < INVENTORY_SIZE {
+                        actions
+                            .extend(occupied().filter(|&i| self.can_stash(i)).map(Action::Stash));
+                    }
+                    actions.extend((0..self.inventory.len()).map(Action::Use));
+                }
+                actions
+            }
+            GameState::CardInteraction if self.awaiting_weapon_choice => {
+                let monster = self.current_monster;
+                (0..2)
+                    .filter(|&hand| monster.is_some_and(|m| self.can_use_hand(hand, m)))
+                    .map(|hand| Action::Fight { weapon: Some(hand) })
+                    .chain([Action::Fight { weapon: None }])
+                    .collect()
+            }
+            GameState::CardInteraction | GameState::Trap => vec![Action::Continue],
+            GameState::Shop => self
+                .wares()
+                .iter()
+                .filter(|w| self.can_buy(**w))
+                .map(|w| Action::Buy(*w))
+                .chain([Action::Leave])
+                .collect(),
+            GameState::MainMenu | GameState::ResumePrompt | GameState::GameOver => Vec::new(),
+        }
+    }
+
+    /// Why `action` can't be taken right now, or `None` if it's legal
+    pub fn illegal_reason(&self, action: Action) -> Option<&'static str> {
+        if self.legal_actions().contains(&action) {
+            return None;
+        }
+
+        let reason = match (action, self.state) {
+            (Action::Skip, GameState::RoomChoice) if self.has(Mutator::NoSkips) => {
+                msg::SKIPS_DISABLED
+            }
+            (Action::Skip, GameState::RoomChoice) if self.rules.skips == SkipPolicy::Never => {
+                msg::SKIPS_OFF
+            }
+            (Action::Skip, GameState::RoomChoice) if self.rules.skips == SkipPolicy::Once => {
+                msg::SKIP_SPENT
+            }
+            (Action::Skip, GameState::RoomChoice) => msg::NEED_FACE_ONLY,
+            (Action::Reshuffle, GameState::RoomChoice) => msg::NO_RESHUFFLES_LEFT,
+            (Action::Reshuffle, _) => msg::RESHUFFLE_BEFORE_FACING,
+            (Action::Banish(_), _) if !self.rules.banish => msg::BANISH_OFF,
+            (Action::Banish(_), _) if self.banished.is_some() => msg::BANISH_SPENT,
+            (Action::Banish(_), GameState::CardSelection)
+                if self.room_slots.iter().flatten().count() == 1 =>
+            {
+                msg::BANISH_LAST_CARD
+            }
+            (Action::Banish(_), GameState::CardSelection) => msg::INVALID_CARD_SELECTION,
+            (Action::Banish(_), _) => msg::BANISH_NOT_NOW,
+            (Action::Mulligan, _) if !self.rules.mulligan => msg::MULLIGAN_OFF,
+            (Action::Mulligan, _) if self.mulligan_used => msg::MULLIGAN_SPENT,
+            (Action::Mulligan, _) => msg::MULLIGAN_TOO_LATE,
+            (Action::Stash(_) | Action::Use(_), _) if !self.rules.inventory => msg::INVENTORY_OFF,
+            (Action::Stash(_), GameState::CardSelection)
+                if self.inventory.len() >= INVENTORY_SIZE =>
+            {
+                msg::INVENTORY_FULL
+            }
+            (Action::Stash(_), GameState::CardSelection) => msg::INVENTORY_CANT_STASH,
+            (Action::Use(_), GameState::CardSelection) => msg::INVENTORY_NO_SUCH,
+            (Action::Stash(_) | Action::Use(_), _) => msg::INVENTORY_NOT_NOW,
+            (Action::Play(_), GameState::CardSelection) => msg::INVALID_CARD_SELECTION,
+            (Action::Play(_), _) => msg::MUST_FACE_FIRST,
+            (Action::Buy(Ware::Repair), GameState::Shop) if self.at_merchant => {
+                msg::MERCHANT_NO_REPAIR
+            }
+            (Action::Buy(ware), GameState::Shop) if self.gold < ware.price() => msg::SHOP_NO_GOLD,
+            (Action::Buy(Ware::Repair), GameState::Shop) => msg::SHOP_NOTHING_TO_REPAIR,
+            (Action::Buy(Ware::Forge), GameState::Shop) => msg::SHOP_NOTHING_TO_FORGE,
+            (Action::Buy(_), GameState::Shop) => msg::SHOP_FULL_HEALTH,
+            _ => msg::NOT_NOW,
+        };
+        Some(reason)
+    }
+
+    /// `apply` for an action from a single click or button press. One that comes within
+    /// `LATCH_MS` of the last accepted action is the same press bouncing (a double-click,
+    /// two actions off one gamepad press) and is dropped, whatever it is. Bots, replays and
+    /// typed commands go straight to `apply`.
+    pub fn press(&mut self, action: Action) -> ResolveOutcome {
+        // Before the legality check, so the echo doesn't overwrite the message with an error
+        if self.latched() {
+            return ResolveOutcome::None;
+        }
+        self.apply(action)
+    }
+
+    /// Single entry point for player decisions. Illegal actions leave the state untouched
+    /// and explain themselves through `message`.
+    pub fn apply(&mut self, action: Action) -> ResolveOutcome {
+        if let Some(reason) = self.illegal_reason(action) {
+            self.message = reason.to_string();
+            return ResolveOutcome::None;
+        }
+        // Settled before this skip is logged, or `skips = once` would count it against itself
+        let free_skip = self.skips_free();
+
+        self.events.push(LoggedEvent {
+            turn: self.events.len() as u32 + 1,
+            at_ms: clock::now_ms(),
+            action,
+            health: self.health,
+            card: match action {
+                Action::Play(i) | Action::Stash(i) => self.room_slots.get(i).copied().flatten(),
+                Action::Use(i) => self.inventory.get(i).copied(),
+                _ => None,
+            },
+            room: self.rooms_resolved + 1,
+            weapon: None,
+            last_slain: None,
+            ability: None,
+        });
+
+        let outcome = match action {
+            Action::Face => {
+                self.face_room();
+                ResolveOutcome::None
+            }
+            Action::Skip => {
+                self.skip_room(free_skip);
+                ResolveOutcome::None
+            }
+            Action::Reshuffle => {
+                self.reshuffle_room();
+                ResolveOutcome::None
+            }
+            Action::Mulligan => {
+                self.mulligan();
+                ResolveOutcome::None
+            }
+            Action::Banish(idx) => {
+                self.banish(idx);
+                ResolveOutcome::None
+            }
+            Action::Play(idx) if self.is_hidden(idx) => {
+                let card = self.room_slots[idx];
+                let outcome = self.play_card_from_slot(idx);
+                if let Some(card) = card {
+                    self.message = format!("Turned over {}. {}", card_text(card), self.message);
+                }
+                outcome
+            }
+            Action::Play(idx) if self.is_dark(idx) => {
+                let card = self.room_slots[idx];
+                let outcome = self.play_card_from_slot(idx);
+                if let Some(card) = card {
+                    self.message = format!("Up close it's {}. {}", card_text(card), self.message);
+                }
+                outcome
+            }
+            Action::Play(idx) => self.play_card_from_slot(idx),
+            Action::Stash(idx) => self.stash(idx),
+            Action::Use(i) => {
+                self.use_stashed(i);
+                ResolveOutcome::None
+            }
+            Action::Fight { weapon } => self.answer_weapon_prompt(weapon),
+            Action::Continue if self.state == GameState::Trap => {
+                self.state = GameState::CardSelection;
+                self.message = msg::TRAP_PASSED.to_string();
+                ResolveOutcome::None
+            }
+            Action::Continue => {
+                self.continue_after_interaction();
+                ResolveOutcome::None
+            }
+            Action::Buy(ware) => {
+                self.buy(ware);
+                ResolveOutcome::None
+            }
+            Action::Leave if self.at_merchant => {
+                self.at_merchant = false;
+                self.message = msg::MERCHANT_LEFT.to_string();
+                self.continue_after_interaction();
+                ResolveOutcome::None
+            }
+            Action::Leave => {
+                self.state = GameState::RoomChoice;
+                self.message = msg::SHOP_LEFT.to_string();
+                ResolveOutcome::None
+            }
+        };
+
+        let (weapon, last_slain) = (self.weapon(), self.last_slain());
+        if let Some(event) = self.events.last_mut() {
+            event.health = self.health;
+            event.weapon = weapon;
+            event.last_slain = last_slain;
+        }
+        // The last treasure wins once its card has resolved, unless it killed you
+        if self.treasures_complete()
+            && self.health > 0
+            && !self.awaiting_weapon_choice
+            && self.state != GameState::GameOver
+        {
+            self.survived = true;
+            self.state = GameState::GameOver;
+            self.message = msg::TREASURES_FOUND.to_string();
+        }
+        // Out of moves once the next thing asked for is a move, so the last card still
+        // resolves (and can still kill you) first
+        if self.moves_left() == Some(0)
+            && matches!(self.state, GameState::RoomChoice | GameState::CardSelection)
+        {
+            self.survived = false;
+            self.state = GameState::GameOver;
+            self.message = msg::OUT_OF_MOVES.to_string();
+        }
+        outcome
+    }
+
+    /// Faces, skips, reshuffles and cards played, stashed or used so far. Answering a
+    /// weapon prompt or continuing is part of resolving a card, so those don't count.
+    pub fn moves_made(&self) -> usize {
+        self.events
+            .iter()
+            .filter(|e| {
+                matches!(
+                    e.action,
+                    Action::Face
+                        | Action::Skip
+                        | Action::Reshuffle
+                        | Action::Play(_)
+                        | Action::Stash(_)
+                        | Action::Use(_)
+                )
+            })
+            .count()
+    }
+
+    /// `None` unless the `move-limit` rule is on
+    pub fn moves_left(&self) -> Option<usize> {
+        match self.rules.move_limit {
+            0 => None,
+            limit => Some(usize::from(limit).saturating_sub(self.moves_made())),
+        }
+    }
+
+    /// Death check safeguard (some sequences may reduce HP outside continue)
+    pub fn settle_death(&mut self) {
+        if self.health <= 0 && self.state != GameState::GameOver {
+            self.survived = false;
+            self.state = GameState::GameOver;
+            self.message = msg::YOU_DIED.to_string();
+        }
+    }
+
+    /// True while a run is underway (anything between entering the dungeon and GameOver)
+    pub fn in_progress(&self) -> bool {
+        matches!(
+            self.state,
+            GameState::RoomChoice
+                | GameState::CardSelection
+                | GameState::CardInteraction
+                | GameState::Shop
+                | GameState::Trap
+        )
+    }
+
+    pub fn create_deck(&mut self) {
+        let mut cards = full_deck();
+        cards.shuffle(&mut self.rng);
+        self.deal_cards(cards);
+    }
+
+    /// Deal from `cards` in this order instead, marking treasures and the like among them,
+    /// slotting the bosses in (unless they're already there) and cutting it into floors
+    pub fn deal_cards(&mut self, cards: Vec<Card>) {
+        self.deck = VecDeque::from(cards);
+        self.mark_treasures();
+        self.mark_traps();
+        self.mark_merchants();
+        if !self.deck.iter().any(|&c| Boss::of(c).is_some()) {
+            self.place_bosses();
+        }
+        self.split_floors();
+    }
+
+    /// `floors`: cut the dealt deck into one smaller deck per floor, bosses and treasures
+    /// staying wherever they were dealt
+    fn split_floors(&mut self) {
+        self.floor = 1;
+        self.floors_ahead.clear();
+        let floors = usize::from(self.rules.floors);
+        if floors <= 1 {
+            return;
+        }
+        let per_floor = self.deck.len().div_ceil(floors);
+        let mut decks: Vec<Vec<Card>> = Vec::from(std::mem::take(&mut self.deck))
+            .chunks(per_floor)
+            .map(<[Card]>::to_vec)
+            .collect();
+        self.deck = VecDeque::from(decks.remove(0));
+        self.floors_ahead = decks;
+    }
+
+    pub fn floor_count(&self) -> u8 {
+        self.floor + self.floors_ahead.len() as u8
+    }
+
+    /// `bosses`: slot each boss in where the room it's due in draws from, going by the
+    /// deck as dealt (skips and reshuffles can still move it)
+    fn place_bosses(&mut self) {
+        let every = usize::from(self.rules.bosses);
+        if every == 0 {
+            return;
+        }
+        // The first room takes four cards, each one after that as many as were played
+        let per_room = usize::from(self.rules.room_interactions);
+        for (i, boss) in BOSSES.iter().enumerate() {
+            let room = every * (i + 1);
+            let at = 4 + (room - 1) * per_room - 1;
+            if at > self.deck.len() {
+                break;
+            }
+            self.deck.insert(at, boss.card());
+        }
+    }
+
+    /// The special rule `monster` fights by, if any
+    pub fn ability_of(&self, monster: Card) -> Option<Ability> {
+        if !monster.is_monster() {
+            return None;
+        }
+        Boss::of(monster).map(Ability::Boss).or_else(|| {
+            self.rules
+                .status_effects
+                .then(|| Affliction::from_monster(monster))
+                .flatten()
+                .map(Ability::Affliction)
+        })
+    }
+
+    /// Note `ability` against the action being resolved, unless one already went off
+    fn ability_fired(&mut self, ability: Ability) {
+        if let Some(event) = self.events.last_mut() {
+            event.ability.get_or_insert(ability);
+        }
+    }
+
+    /// The boss the next room will deal, if there is one: a room's warning
+    pub fn boss_ahead(&self) -> Option<Boss> {
+        self.deck
+            .iter()
+            .take(usize::from(self.rules.room_interactions))
+            .find_map(|c| Boss::of(*c))
+    }
+
+    /// `treasure-hunt` and `treasure-score`: pick three cards in the deck to be the
+    /// treasures
+    fn mark_treasures(&mut self) {
+        self.treasures = if self.rules.treasure_hunt || self.rules.treasure_score {
+            let cards: Vec<Card> = self.deck.iter().copied().collect();
+            cards.choose_multiple(&mut self.rng, 3).copied().collect()
+        } else {
+            Vec::new()
+        };
+        self.treasures_found.clear();
+    }
+
+    /// `traps`: pick `TRAP_COUNT` cards in the deck to be trapped, never a treasure
+    fn mark_traps(&mut self) {
+        self.traps = if self.rules.traps {
+            let cards: Vec<Card> = self
+                .deck
+                .iter()
+                .copied()
+                .filter(|c| !self.treasures.contains(c))
+                .collect();
+            cards
+                .choose_multiple(&mut self.rng, TRAP_COUNT)
+                .copied()
+                .collect()
+        } else {
+            Vec::new()
+        };
+        self.weapon_snared = false;
+    }
+
+    /// `merchant`: pick `MERCHANT_COUNT` cards in the deck to be merchants, never one
+    /// that's already a treasure or a trap
+    fn mark_merchants(&mut self) {
+        self.merchants = if self.rules.merchant {
+            let cards: Vec<Card> = self
+                .deck
+                .iter()
+                .copied()
+                .filter(|c| !self.treasures.contains(c) && !self.traps.contains(c))
+                .collect();
+            cards
+                .choose_multiple(&mut self.rng, MERCHANT_COUNT)
+                .copied()
+                .collect()
+        } else {
+            Vec::new()
+        };
+        self.at_merchant = false;
+    }
+
+    /// Whether anything pays out gold: the `shop` and `merchant` rules
+    pub fn earns_gold(&self) -> bool {
+        self.rules.shop || self.rules.merchant
+    }
+
+    /// What's for sale in the shop that's open
+    pub fn wares(&self) -> &'static [Ware] {
+        if self.at_merchant {
+            MERCHANT_WARES
+        } else if self.rules.dual_wield {
+            WARES
+        } else {
+            ONE_HAND_WARES
+        }
+    }
+
+    /// Trapped cards in the room on the table
+    pub fn traps_in_room(&self) -> Vec<Card> {
+        self.room_slots
+            .iter()
+            .flatten()
+            .copied()
+            .filter(|c| self.traps.contains(c))
+            .collect()
+    }
+
+    /// Every treasure played, and still standing: the `treasure-hunt` win
+    pub fn treasures_complete(&self) -> bool {
+        self.rules.treasure_hunt
+            && !self.treasures.is_empty()
+            && self.treasures_found.len() == self.treasures.len()
+    }
+
+    /// Whether `card` is a treasure not found yet. Endless laps deal played treasures
+    /// back in, but each one only pays out once.
+    pub fn is_treasure(&self, card: Card) -> bool {
+        self.treasures.contains(&card) && !self.treasures_found.contains(&card)
+    }
+
+    /// Treasures in the room on the table, as far as can be seen (`fog`, `dark`)
+    pub fn treasures_in_room(&self) -> Vec<Card> {
+        (0..4)
+            .filter_map(|i| self.visible_card(i))
+            .filter(|&c| self.is_treasure(c))
+            .collect()
+    }
+
+    /// The last card is gone: a win, or in endless mode the start of another lap
+    fn dungeon_cleared(&mut self) {
+        if !self.floors_ahead.is_empty() {
+            self.next_floor();
+            return;
+        }
+        if self.endless {
+            self.next_lap();
+            return;
+        }
+        self.survived = true;
+        self.state = GameState::GameOver;
+        self.message = msg::YOU_SURVIVED.to_string();
+    }
+
+    /// Endless mode: the potions and weapons used up so far go back in with a fresh set of
+    /// monsters. The weapon in hand stays in hand.
+    fn next_lap(&mut self) {
+        self.laps += 1;
+
+        let mut cards: Vec<Card> = self.discard.drain(..).filter(|c| !c.is_monster()).collect();
+        cards.extend(full_deck().into_iter().filter(|c| c.is_monster()));
+        cards.shuffle(&mut self.rng);
+        self.deck = VecDeque::from(cards);
+        self.split_floors();
+
+        self.can_skip = true;
+        self.interactions_left_in_room = 0;
+        self.fill_room();
+        self.state = GameState::RoomChoice;
+        self.message = format!("Lap {} cleared. The dungeon deals itself again.", self.laps);
+    }
+
+    /// `floors`: a rest on the stairs (some health back, the weapon repaired), then the
+    /// next floor's first room
+    fn next_floor(&mut self) {
+        let heal = REST_HEAL.min(self.max_health - self.health);
+        self.health += heal;
+        self.loadout.repair();
+
+        self.floor += 1;
+        self.deck = VecDeque::from(self.floors_ahead.remove(0));
+        self.can_skip = true;
+        self.interactions_left_in_room = 0;
+        self.fill_room();
+        self.state = GameState::RoomChoice;
+        self.message = format!(
+            "Floor {} cleared. You rest on the stairs (+{heal} HP, weapon repaired) and go down to floor {}.",
+            self.floor - 1,
+            self.floor
+        );
+    }
+
+    pub fn room_is_empty(&self) -> bool {
+        self.room_slots.iter().all(|c| c.is_none())
+    }
+
+    /// Fill empty room slots from the top of the deck, without shifting existing cards
+    pub fn fill_room(&mut self) {
+        let empty: Vec<usize> = (0..4).filter(|&i| self.room_slots[i].is_none()).collect();
+        for &i in &empty {
+            self.hidden[i] = false;
+            self.dark[i] = false;
+        }
+
+        if self.comeback_pending && !self.deck.is_empty() {
+            self.comeback_pending = false;
+            self.fill_room_gently();
+        } else {
+            for slot in self.room_slots.iter_mut() {
+                if slot.is_none()
+                    && let Some(card) = self.deck.pop_front()
+                {
+                    *slot = Some(card);
+                }
+            }
+        }
+        self.fog_room(&empty);
+        self.darken_room(&empty);
+    }
+
+    /// `dark`: now and then the cards just dealt into `dealt` come without their values.
+    /// A card left over from the last room keeps whatever it had.
+    fn darken_room(&mut self, dealt: &[usize]) {
+        if self.rules.dark == 0 || !self.rng.gen_ratio(1, u32::from(self.rules.dark)) {
+            return;
+        }
+        for &i in dealt {
+            self.dark[i] = self.room_slots[i].is_some();
+        }
+    }
+
+    /// Whether the card in room slot `i` shows its suit but not its value
+    pub fn is_dark(&self, i: usize) -> bool {
+        self.dark.get(i).copied().unwrap_or(false)
+            && self.room_slots.get(i).copied().flatten().is_some()
+    }
+
+    /// `fog`: turn one of the cards just dealt into `dealt` face down, unless a card left
+    /// over from the last room still is
+    fn fog_room(&mut self, dealt: &[usize]) {
+        if !self.rules.fog || (0..4).any(|i| self.is_hidden(i)) {
+            return;
+        }
+        let dealt: Vec<usize> = dealt
+            .iter()
+            .copied()
+            .filter(|&i| self.room_slots[i].is_some())
+            .collect();
+        if let Some(&i) = dealt.choose(&mut self.rng) {
+            self.hidden[i] = true;
+        }
+    }
+
+    /// Whether the card in room slot `i` is face down
+    pub fn is_hidden(&self, i: usize) -> bool {
+        self.hidden.get(i).copied().unwrap_or(false)
+            && self.room_slots.get(i).copied().flatten().is_some()
+    }
+
+    /// The card in room slot `i`, unless it's empty, face down or dark
+    pub fn visible_card(&self, i: usize) -> Option<Card> {
+        if self.is_hidden(i) || self.is_dark(i) {
+            return None;
+        }
+        self.room_slots.get(i).copied().flatten()
+    }
+
+    /// `comeback` deal: at most two monsters in the room. Monsters passed over keep their
+    /// place at the top of the deck, so the shuffle is bent rather than rerolled.
+    fn fill_room_gently(&mut self) {
+        const MAX_MONSTERS: usize = 2;
+        let mut monsters = self
+            .room_slots
+            .iter()
+            .flatten()
+            .filter(|c| c.is_monster())
+            .count();
+
+        for i in 0..4 {
+            if self.room_slots[i].is_some() {
+                continue;
+            }
+            // Nothing but monsters left: deal them anyway
+            let pick = self
+                .deck
+                .iter()
+                .position(|c| monsters < MAX_MONSTERS || !c.is_monster())
+                .unwrap_or(0);
+            let Some(card) = self.deck.remove(pick) else {
+                break;
+            };
+            if card.is_monster() {
+                monsters += 1;
+            }
+            self.room_slots[i] = Some(card);
+        }
+    }
+
+    /// Lose `dmg` HP, arming the `comeback` rule if the hit was big enough
+    fn take_damage(&mut self, dmg: i32) -> Hit {
+        let soaked = self.armor_soaks(dmg);
+        let mut armor_broke = false;
+        if let Some(armor) = &mut self.armor {
+            armor.absorbed += soaked as u8;
+            if armor.left() == 0 {
+                self.discard.push(armor.card);
+                self.armor = None;
+                armor_broke = true;
+            }
+        }
+        let dmg = dmg - soaked;
+        self.health -= dmg;
+        let threshold = i32::from(self.rules.comeback);
+        if threshold > 0 && dmg >= threshold && self.health > 0 {
+            self.comeback_pending = true;
+        }
+        Hit {
+            taken: dmg,
+            soaked,
+            armor_broke,
+        }
+    }
+
+    /// How much of a `dmg` hit the armor being worn would take
+    pub fn armor_soaks(&self, dmg: i32) -> i32 {
+        self.armor.map_or(0, |a| a.soaks(dmg))
+    }
+
+    /// Whether `card` is worn rather than wielded: a diamond no higher than `armor`
+    pub fn is_armor(&self, card: Card) -> bool {
+        card.suit == 'D' && card.value <= self.rules.armor
+    }
+
+    pub fn face_room(&mut self) {
+        self.potions_this_room = 0;
+        self.interactions_left_in_room = self.rules.room_interactions;
+        self.state = GameState::CardSelection;
+        self.message = msg::FACE_ROOM.to_string();
+
+        let heal = (i32::from(self.rules.room_heal)).min(self.max_health - self.health);
+        if heal > 0 {
+            self.health += heal;
+            self.message = format!("{} (+{heal} HP)", msg::FACE_ROOM);
+        }
+        self.spring_traps();
+    }
+
+    /// `traps`: every trap in the room goes off before a card can be picked. The cards
+    /// themselves stay put and play as usual.
+    fn spring_traps(&mut self) {
+        let sprung = self.traps_in_room();
+        if sprung.is_empty() {
+            return;
+        }
+        self.traps.retain(|c| !sprung.contains(c));
+
+        // A trap gives its card away
+        for i in 0..4 {
+            if self.room_slots[i].is_some_and(|c| sprung.contains(&c)) {
+                self.hidden[i] = false;
+                self.dark[i] = false;
+            }
+        }
+
+        let mut notes = Vec::new();
+        for card in sprung {
+            match Trap::of(card) {
+                Trap::Spikes => {
+                    let hit = self.take_damage(Trap::damage(card));
+                    notes.push(format!(
+                        "Spikes under {}! Took {} damage.{}",
+                        card_text(card),
+                        hit.taken,
+                        hit.note()
+                    ));
+                }
+                Trap::Snare if self.weapon().is_some() => {
+                    self.weapon_snared = true;
+                    notes.push(format!(
+                        "A snare under {} ties up your weapon for your next pick.",
+                        card_text(card)
+                    ));
+                }
+                Trap::Snare => {
+                    notes.push(format!(
+                        "A snare under {} snaps shut on an empty hand.",
+                        card_text(card)
+                    ));
+                }
+            }
+        }
+        self.message = notes.join(" ");
+
+        if self.health <= 0 {
+            self.survived = false;
+            self.state = GameState::GameOver;
+            self.message.push(' ');
+            self.message.push_str(msg::TRAP_KILLED);
+        } else {
+            self.state = GameState::Trap;
+        }
+    }
+
+    /// Whether the room on the table may be skipped, going by the skip policy
+    pub fn skip_allowed(&self) -> bool {
+        if self.has(Mutator::NoSkips) {
+            return false;
+        }
+        self.skip_tokens > 0 || self.skips_free()
+    }
+
+    /// Whether the skip policy alone allows a skip, without spending a token
+    fn skips_free(&self) -> bool {
+        match self.rules.skips {
+            SkipPolicy::NoRepeat => self.can_skip,
+            SkipPolicy::Once => !self.has_skipped(),
+            SkipPolicy::Always => true,
+            SkipPolicy::Never => false,
+        }
+    }
+
+    /// Whether an input arriving now is within `LATCH_MS` of the last accepted action
+    fn latched(&self) -> bool {
+        self.events
+            .last()
+            .is_some_and(|e| clock::now_ms().saturating_sub(e.at_ms) < LATCH_MS)
+    }
+
+    /// The move log: every card played, stashed or used and room skipped, in order
+    pub fn moves(&self) -> impl Iterator<Item = &LoggedEvent> {
+        self.events.iter().filter(|e| {
+            matches!(
+                e.action,
+                Action::Play(_) | Action::Stash(_) | Action::Use(_) | Action::Skip
+            )
+        })
+    }
+
+    /// Turns taken so far, one per card played, stashed or used, or room skipped
+    pub fn turns(&self) -> usize {
+        self.moves().count()
+    }
+
+    /// Any skip so far this run
+    pub fn has_skipped(&self) -> bool {
+        self.events.iter().any(|e| e.action == Action::Skip)
+    }
+
+    /// Only reached through `apply`, which has already checked `skip_allowed`, and worked
+    /// out whether the policy lets this skip go `free` before logging it. (Checking again
+    /// here would see this skip in the event log and count it under `skips = once`.)
+    pub fn skip_room(&mut self, free: bool) {
+        let left_behind = self.treasures_in_room();
+        // Skipped cards go to the bottom of the deck, shuffled unless the rules say otherwise
+        let mut skipped: Vec<Card> = self
+            .room_slots
+            .iter_mut()
+            .filter_map(|s| s.take())
+            .collect();
+        if self.rules.skip_order == SkipOrder::Shuffled {
+            skipped.shuffle(&mut self.rng);
+        }
+        self.deck.extend(skipped);
+
+        if !free {
+            self.skip_tokens = self.skip_tokens.saturating_sub(1);
+        }
+        self.can_skip = false;
+        self.fill_room();
+
+        if self.room_is_empty() && self.deck.is_empty() {
+            self.dungeon_cleared();
+        } else {
+            self.message = msg::SKIPPED_ROOM.to_string();
+            if self.rules.treasure_score && !left_behind.is_empty() {
+                let cards: Vec<String> = left_behind.iter().map(|&c| card_text(c)).collect();
+                let worth: u32 = left_behind.iter().map(|c| u32::from(c.value)).sum();
+                self.message = format!(
+                    "Skipped the room, and walked away from {} (+{worth} score) with it.",
+                    cards.join(" ")
+                );
+            }
+            if self.rules.skips == SkipPolicy::NoRepeat {
+                self.lessons.push(Lesson::ForcedFace);
+            }
+        }
+    }
+
+    /// Shuffle the whole room back into the dungeon and deal a fresh one.
+    ///
+    /// Unlike a skip this doesn't send the cards to the bottom, and it doesn't touch `can_skip`.
+    pub fn reshuffle_room(&mut self) {
+        if self.state != GameState::RoomChoice {
+            self.message = msg::RESHUFFLE_BEFORE_FACING.to_string();
+            return;
+        }
+        if self.reshuffles_left == 0 {
+            self.message = msg::NO_RESHUFFLES_LEFT.to_string();
+            return;
+        }
+
+        let mut cards: Vec<Card> = self.deck.drain(..).collect();
+        cards.extend(self.room_slots.iter_mut().filter_map(|s| s.take()));
+        cards.shuffle(&mut self.rng);
+        self.deck = VecDeque::from(cards);
+
+        self.reshuffles_left -= 1;
+        self.fill_room();
+        self.message = msg::RESHUFFLED_ROOM.to_string();
+    }
+
+    /// Whether the opening room can still be redealt: the rule is on, it hasn't been used,
+    /// and the room hasn't been faced or skipped
+    pub fn mulligan_available(&self) -> bool {
+        self.rules.mulligan
+            && !self.mulligan_used
+            && self.state == GameState::RoomChoice
+            && !self
+                .events
+                .iter()
+                .any(|e| matches!(e.action, Action::Face | Action::Skip))
+    }
+
+    /// The opening room goes back into the dungeon, which is shuffled, and four cards are
+    /// dealt again. Only reached through `apply`, which has checked `mulligan_available`.
+    pub fn mulligan(&mut self) {
+        let mut cards: Vec<Card> = self.deck.drain(..).collect();
+        cards.extend(self.room_slots.iter_mut().filter_map(|s| s.take()));
+        cards.shuffle(&mut self.rng);
+        self.deck = VecDeque::from(cards);
+
+        self.mulligan_used = true;
+        self.fill_room();
+        self.message = msg::MULLIGANED.to_string();
+    }
+
+    /// Take a room card out of the game: it's gone for the rest of the run, not back into
+    /// the dungeon, and playing it doesn't use up one of the room's interactions. Only
+    /// reached through `apply`, which has checked it's allowed.
+    pub fn banish(&mut self, idx: usize) {
+        let Some(card) = self.room_slots[idx].take() else {
+            return;
+        };
+        self.banished = Some(card);
+        // A room that would now run out of cards before plays ends when it runs out
+        let left = self.room_slots.iter().flatten().count();
+        self.interactions_left_in_room = self.interactions_left_in_room.min(left as u8);
+        self.message = format!("{} is banished from the dungeon.", card_text(card));
+    }
+
+    /// `inventory`: whether the card in `idx` could go in the pack. Only potions and
+    /// weapons you can see do, and not merchants or treasures, which want playing.
+    pub fn can_stash(&self, idx: usize) -> bool {
+        self.visible_card(idx).is_some_and(|card| {
+            matches!(card.suit, 'H' | 'D')
+                && !self.merchants.contains(&card)
+                && !self.treasures.contains(&card)
+        })
+    }
+
+    /// `inventory`: the card in `idx` goes in the pack, which counts as one of the
+    /// room's picks
+    fn stash(&mut self, idx: usize) -> ResolveOutcome {
+        let Some(card) = self.room_slots[idx].take() else {
+            return ResolveOutcome::None;
+        };
+        self.inventory.push(card);
+        self.state = GameState::CardInteraction;
+        self.message = format!(
+            "Stashed {} ({}/{INVENTORY_SIZE} in your pack).",
+            card_text(card),
+            self.inventory.len()
+        );
+        self.resolved(false, 0)
+    }
 
-            weapon: None,
-            last_monster_slain_with_weapon: None,
-            potion_used_this_room: false,
+    /// `inventory`: drink or wield the `i`th card in the pack. It isn't in the room, so
+    /// it's not one of the room's picks, and the room carries on.
+    fn use_stashed(&mut self, i: usize) {
+        let card = self.inventory.remove(i);
+        self.last_played_potion = (card.suit == 'H').then_some(card.value);
+        if card.suit == 'H' {
+            self.drink(card);
+        } else {
+            self.wield(card);
+        }
+        self.message = format!("From your pack: {}", self.message);
+    }
 
-            can_skip: true,
-            state: GameState::MainMenu,
-            survived: false,
+    /// The main-hand weapon
+    pub fn weapon(&self) -> Option<Card> {
+        self.loadout.main().map(|w| w.card)
+    }
 
-            message: String::new(),
-            last_command_feedback: String::new(),
+    /// What the main-hand weapon last slew
+    pub fn last_slain(&self) -> Option<u8> {
+        self.loadout.main().and_then(|w| w.last_slain)
+    }
 
-            current_monster: None,
-            awaiting_weapon_choice: false,
+    /// Whether any weapon in hand can fight `monster`
+    pub fn can_use_weapon_on(&self, monster: Card) -> bool {
+        (0..2).any(|hand| self.can_use_hand(hand, monster))
+    }
 
-            interactions_left_in_room: 0,
+    pub fn can_use_hand(&self, hand: usize, monster: Card) -> bool {
+        let Some(w) = self.loadout.hands[hand] else {
+            return false;
         };
-
-        g.create_deck();
-        g
+        if self.weapon_snared {
+            return false;
+        }
+        if Boss::of(monster) == Some(Boss::Troll) && w.card.value > TROLL_MAX_WEAPON {
+            return false;
+        }
+        match (w.last_slain, self.rules.degrade) {
+            (None, _) | (_, Degrade::Off) => true,
+            (Some(last), Degrade::Strict) => monster.value < last,
+            (Some(last), Degrade::Equal) => monster.value <= last,
+        }
     }
 
-    /// Reset the game into a playable "in dungeon" state (RoomChoice + initial room filled)
-    pub fn reset_to_playing(&mut self) {
-        *self = Self::new();
-        self.state = GameState::RoomChoice;
-        self.fill_room();
-        self.message = msg::ENTERED_DUNGEON.to_string();
+    /// What a plain "yes" at the weapon prompt means
+    pub fn weapon_answer(&self) -> Action {
+        let hand = self.current_monster.map_or(0, |m| self.weapon_hand(m));
+        Action::Fight { weapon: Some(hand) }
     }
 
-    pub fn create_deck(&mut self) {
-        let mut cards = Vec::new();
+    /// True when the weapon prompt is offering both hands (answered 1/2/n)
+    pub fn choosing_hand(&self) -> bool {
+        self.awaiting_weapon_choice
+            && self
+                .current_monster
+                .is_some_and(|m| self.can_use_hand(0, m) && self.can_use_hand(1, m))
+    }
 
-        for suit in ['S', 'C', 'D', 'H'] {
-            for value in 2..=14u8 {
-                // Red aces and face cards removed, them's da rulez
-                if (suit == 'D' || suit == 'H') && value >= 11 {
-                    continue;
-                }
-                cards.push(Card { suit, value });
-            }
-        }
+    /// The hand a plain "yes" at the weapon prompt fights with: the first that can
+    fn weapon_hand(&self, monster: Card) -> usize {
+        (0..2)
+            .find(|&hand| self.can_use_hand(hand, monster))
+            .unwrap_or(0)
+    }
 
-        let mut rng = rand::thread_rng();
-        cards.shuffle(&mut rng);
-        self.deck = VecDeque::from(cards);
+    /// Damage taken fighting `monster` with the weapon a "yes" would use (pure, no
+    /// degradation applied)
+    pub fn weapon_damage(&self, monster: Card) -> i32 {
+        self.hand_damage(self.weapon_hand(monster), monster)
     }
 
-    pub fn room_is_empty(&self) -> bool {
-        self.room_slots.iter().all(|c| c.is_none())
+    pub fn hand_damage(&self, hand: usize, monster: Card) -> i32 {
+        let Some(w) = self.loadout.hands[hand].map(|w| w.card) else {
+            return self.monster_strength(monster);
+        };
+
+        let cut = if Boss::of(monster) == Some(Boss::Dragon) {
+            w.value / 2
+        } else {
+            w.value
+        };
+        let dmg = (self.monster_strength(monster) - cut as i32).max(0);
+        if monster.value == w.value && self.rules.equal_fight == EqualFight::Chip {
+            1
+        } else {
+            dmg
+        }
     }
 
-    /// Fill empty room slots from the top of the deck, without shifting existing cards
-    pub fn fill_room(&mut self) {
-        for slot in self.room_slots.iter_mut() {
-            if slot.is_none() {
-                if let Some(card) = self.deck.pop_front() {
-                    *slot = Some(card);
-                }
-            }
+    /// What picking room card `idx` now would do to your health. It's played out on a copy
+    /// through `apply`, so it always matches the real thing; at the room choice that means
+    /// facing the room first. `None` for a card you can't see or can't pick.
+    pub fn pick_hp_delta(&self, idx: usize) -> Option<HpDelta> {
+        self.visible_card(idx)?;
+        let mut trial = self.clone();
+        if trial.state == GameState::RoomChoice {
+            trial.apply(Action::Face);
         }
+        if trial.illegal_reason(Action::Play(idx)).is_some() {
+            return None;
+        }
+        trial.apply(Action::Play(idx));
+        if !trial.awaiting_weapon_choice {
+            return Some(HpDelta::Fixed(trial.health - self.health));
+        }
+        let outcomes: Vec<(Option<usize>, i32)> = trial
+            .legal_actions()
+            .into_iter()
+            .filter_map(|action| match action {
+                Action::Fight { weapon } => {
+                    let mut fight = trial.clone();
+                    fight.apply(action);
+                    Some((weapon, fight.health - self.health))
+                }
+                _ => None,
+            })
+            .collect();
+        Some(match outcomes.as_slice() {
+            [(_, hp)] => HpDelta::Fixed(*hp),
+            _ => HpDelta::Choice(outcomes),
+        })
     }
 
-    pub fn face_room(&mut self) {
-        self.potion_used_this_room = false;
-        self.interactions_left_in_room = 3;
-        self.state = GameState::CardSelection;
-        self.message = msg::FACE_ROOM.to_string();
+    /// True if fighting `monster` with the weapon a "yes" would use would shatter it
+    pub fn weapon_breaks_on(&self, monster: Card) -> bool {
+        self.hand_breaks_on(self.weapon_hand(monster), monster)
     }
 
-    pub fn skip_room(&mut self) {
-        if !self.can_skip {
-            self.message = msg::NEED_FACE_ONLY.to_string();
-            return;
+    pub fn hand_breaks_on(&self, hand: usize, monster: Card) -> bool {
+        let weapon = self.loadout.hands[hand].map(|w| w.card);
+        if Boss::of(monster) == Some(Boss::Golem) {
+            return weapon.is_some();
         }
+        self.rules.equal_fight == EqualFight::WeaponBreaks
+            && weapon.is_some_and(|w| w.value == monster.value)
+    }
+
+    pub fn handle_monster_with_weapon(&mut self, hand: usize, monster: Card) -> i32 {
+        let Some(mut wielded) = self.loadout.hands[hand] else {
+            return monster.value as i32;
+        };
 
-        // Put skipped room cards at bottom of deck, currently preserving slot order
-        // TODO: This order should technically be randomized
-        for slot in self.room_slots.iter_mut() {
-            if let Some(card) = slot.take() {
-                self.deck.push_back(card);
+        let dmg = self.hand_damage(hand, monster);
+        if self.hand_breaks_on(hand, monster) {
+            self.discard.push(wielded.card);
+            self.loadout.hands[hand] = None;
+        } else {
+            if wielded.last_slain.is_none() && self.rules.degrade != Degrade::Off {
+                self.lessons.push(Lesson::WeaponDegraded);
+            }
+            wielded.last_slain = Some(monster.value);
+            wielded.kills += 1;
+            self.loadout.hands[hand] = Some(wielded);
+            if self.earns_gold() {
+                self.gold += u32::from(wielded.card.value.saturating_sub(monster.value));
             }
         }
+        dmg
+    }
 
-        self.can_skip = false;
-        self.fill_room();
+    /// The main-hand weapon's name, e.g. `Oathbreaker +3 kills`
+    pub fn weapon_name(&self) -> Option<String> {
+        self.hand_name(0)
+    }
 
-        if self.room_is_empty() && self.deck.is_empty() {
-            self.survived = true;
-            self.state = GameState::GameOver;
-            self.message = msg::YOU_SURVIVED.to_string();
-        } else {
-            self.message = msg::SKIPPED_ROOM.to_string();
-        }
+    pub fn hand_name(&self, hand: usize) -> Option<String> {
+        self.loadout.hands[hand].map(|w| naming::weapon_name(self.seed, w.card, w.kills))
     }
 
-    pub fn can_use_weapon_on(&self, monster: Card) -> bool {
-        if self.weapon.is_none() {
-            return false;
-        }
-        match self.last_monster_slain_with_weapon {
-            None => true,
-            Some(last) => monster.value < last,
-        }
+    pub fn handle_monster_without_weapon(&self, monster: Card) -> i32 {
+        self.monster_strength(monster)
     }
 
-    pub fn handle_monster_with_weapon(&mut self, monster: Card) -> i32 {
-        if let Some(w) = self.weapon {
-            let dmg = (monster.value as i32 - w.value as i32).max(0);
-            self.last_monster_slain_with_weapon = Some(monster.value);
-            dmg
+    /// Damage a monster deals before any weapon, which is its value unless a mutator says
+    /// otherwise. Weapon degradation still goes by the card's value.
+    pub fn monster_strength(&self, monster: Card) -> i32 {
+        let brute = self.has(Mutator::Brutes) && monster.value >= 11;
+        monster.value as i32 + i32::from(brute)
+    }
+
+    pub fn starting_health(&self) -> i32 {
+        if self.has(Mutator::Wounded) {
+            (self.max_health - 5).max(1)
         } else {
-            monster.value as i32
+            self.max_health
         }
     }
 
-    pub fn handle_monster_without_weapon(&self, monster: Card) -> i32 {
-        monster.value as i32
+    pub fn has(&self, mutator: Mutator) -> bool {
+        self.mutators.contains(&mutator)
     }
 
     /// Play a card, perform the card effect and transition the state accordingly
@@ -201,6 +2360,8 @@ impl Game {
             return ResolveOutcome::None;
         }
 
+        self.hidden[idx] = false;
+        self.dark[idx] = false;
         let card = match self.room_slots[idx].take() {
             Some(c) => c,
             None => {
@@ -208,6 +2369,30 @@ impl Game {
                 return ResolveOutcome::None;
             }
         };
+        self.cards_played += 1;
+        self.last_played_potion = (card.suit == 'H').then_some(card.value);
+        if self.is_treasure(card) {
+            self.treasures_found.push(card);
+            if self.earns_gold() {
+                self.gold += TREASURE_GOLD;
+            }
+            if self.rules.treasure_score {
+                self.treasure_bonus += u32::from(card.value);
+            }
+        }
+        // A merchant takes the card's place: it's spent on the shop, not played
+        if self.merchants.contains(&card) {
+            self.merchants.retain(|c| *c != card);
+            self.discard.push(card);
+            self.at_merchant = true;
+            self.state = GameState::Shop;
+            self.message = format!(
+                "A merchant steps out from behind {}. You have {} gold.",
+                card_text(card),
+                self.gold
+            );
+            return ResolveOutcome::None;
+        }
 
         match card.suit {
             // Monster
@@ -218,59 +2403,72 @@ impl Game {
                     self.awaiting_weapon_choice = true;
                     self.state = GameState::CardInteraction;
 
-                    let monster_txt = card_text(card);
-                    let weapon_txt = self
-                        .weapon
-                        .map(card_text)
-                        .unwrap_or_else(|| "?".to_string());
-                    self.message =
-                        format!("Monster {monster_txt} — use weapon {weapon_txt}? (y/n)");
+                    // Fair warning before committing a weapon to it
+                    let monster_txt = match self.ability_of(card) {
+                        Some(ability) => format!("{} ({})", card_text(card), ability.text()),
+                        None => card_text(card),
+                    };
+                    let usable: Vec<String> = self
+                        .loadout
+                        .hands
+                        .iter()
+                        .enumerate()
+                        .filter(|(hand, _)| self.can_use_hand(*hand, card))
+                        .filter_map(|(_, w)| w.map(|w| card_text(w.card)))
+                        .collect();
+                    self.message = match usable.as_slice() {
+                        [main, off] => format!(
+                            "Monster {monster_txt} — fight with 1) {main} or 2) {off}, or bare-handed? (1/2/n)"
+                        ),
+                        _ => format!(
+                            "Monster {monster_txt} — use weapon {}? (y/n)",
+                            usable.concat()
+                        ),
+                    };
 
                     ResolveOutcome::None
                 } else {
-                    let dmg = self.handle_monster_without_weapon(card);
-                    self.health -= dmg;
+                    let hit = self.take_damage(self.handle_monster_without_weapon(card));
+                    let dmg = hit.taken;
+                    self.bare_handed_kill();
+                    self.discard.push(card);
+                    self.monsters_slain += 1;
                     self.state = GameState::CardInteraction;
 
-                    self.message = if self.weapon.is_some() {
+                    let shrugged = Boss::of(card) == Some(Boss::Troll)
+                        && self.weapon().is_some_and(|w| w.value > TROLL_MAX_WEAPON);
+                    if shrugged {
+                        self.ability_fired(Ability::Boss(Boss::Troll));
+                    }
+                    self.message = if shrugged {
+                        format!("The Troll shrugs off your weapon. You take {dmg} damage.")
+                    } else if self.weapon_snared && self.weapon().is_some() {
+                        format!("{} Took {dmg} damage.", msg::SNARED_FIGHT)
+                    } else if self.weapon().is_some() {
                         "Your weapon is too degraded to hurt this monster. You fight bare-handed."
                             .to_string()
                     } else {
                         format!("Fought monster! Took {dmg} damage.")
                     };
+                    self.message.push_str(&hit.note());
+                    self.afflict(card, dmg);
 
-                    //ResolveOutcome::AwaitContinue
-                    self.continue_after_interaction();
-                    ResolveOutcome::None
+                    self.resolved(false, dmg)
                 }
             }
 
-            // Weapon
+            // Weapon, or armor under the `armor` rule
             'D' => {
-                self.weapon = Some(card);
-                self.last_monster_slain_with_weapon = None;
+                self.wield(card);
                 self.state = GameState::CardInteraction;
-                self.message = format!("Equipped {}!", card_text(card));
-                //ResolveOutcome::AwaitContinue
-                self.continue_after_interaction();
-                ResolveOutcome::None
+                self.resolved(false, 0)
             }
 
             // Potion
             'H' => {
                 self.state = GameState::CardInteraction;
-                if !self.potion_used_this_room {
-                    let heal = card.value as i32;
-                    self.health = (self.health + heal).min(self.max_health);
-                    self.potion_used_this_room = true;
-                    self.message = format!("Healed for {heal} HP.");
-                } else {
-                    // This string isn't centralized in messages.rs, I don't think it really needs to be
-                    self.message = "Potion wasted (only 1 per room).".to_string();
-                }
-                //ResolveOutcome::AwaitContinue
-                self.continue_after_interaction();
-                ResolveOutcome::None
+                self.drink(card);
+                self.resolved(false, 0)
             }
 
             _ => {
@@ -281,8 +2479,50 @@ impl Game {
         }
     }
 
-    /// Answer the current weapon prompt (y/n)
-    pub fn answer_weapon_prompt(&mut self, use_weapon: bool) -> ResolveOutcome {
+    /// Put on diamond `card` as armor, or take it up as a weapon. What it replaces goes to
+    /// the discard.
+    fn wield(&mut self, card: Card) {
+        if self.is_armor(card) {
+            let replaced = self.armor.replace(Armor::new(card));
+            self.discard.extend(replaced.map(|a| a.card));
+            self.message = format!("Put on {} armor!", card_text(card));
+        } else {
+            let replaced = self.loadout.equip(card, self.rules.dual_wield);
+            self.discard.extend(replaced);
+            self.message = format!("Equipped {}!", card_text(card));
+        }
+    }
+
+    /// Drink potion `card`, which only heals up to the room's potion limit
+    fn drink(&mut self, card: Card) {
+        self.discard.push(card);
+        self.potions_this_room += 1;
+        if self.potions_this_room <= self.rules.potion_limit {
+            let heal = if self.has(Mutator::HalfPotions) {
+                (card.value as i32 + 1) / 2
+            } else {
+                card.value as i32
+            };
+            self.health = (self.health + heal).min(self.max_health);
+            self.message = format!("Healed for {heal} HP.");
+            if self
+                .status_effects
+                .iter()
+                .any(|e| e.affliction == Affliction::Bleed)
+            {
+                self.status_effects
+                    .retain(|e| e.affliction != Affliction::Bleed);
+                self.message.push_str(" The bleeding stops.");
+            }
+        } else {
+            // This string isn't centralized in messages.rs, I don't think it really needs to be
+            self.message = format!("Potion wasted (only {} per room).", self.rules.potion_limit);
+            self.lessons.push(Lesson::PotionWasted);
+        }
+    }
+
+    /// Answer the current weapon prompt: fight with the weapon in `hand`, or bare-handed
+    pub fn answer_weapon_prompt(&mut self, hand: Option<usize>) -> ResolveOutcome {
         if !self.awaiting_weapon_choice {
             return ResolveOutcome::None;
         }
@@ -295,26 +2535,118 @@ impl Game {
             }
         };
 
-        let dmg = if use_weapon {
-            self.handle_monster_with_weapon(monster)
-        } else {
-            self.handle_monster_without_weapon(monster)
+        let use_weapon = hand.is_some();
+        let breaks = hand.is_some_and(|hand| self.hand_breaks_on(hand, monster));
+        // Golem and Dragon only bite a weapon
+        let twist = Boss::of(monster).filter(|&b| use_weapon && b != Boss::Troll);
+        if let Some(boss) = twist {
+            self.ability_fired(Ability::Boss(boss));
+        }
+        let dmg = match hand {
+            Some(hand) => self.handle_monster_with_weapon(hand, monster),
+            None => self.handle_monster_without_weapon(monster),
         };
-
-        self.health -= dmg;
+        let hit = self.take_damage(dmg);
+        let dmg = hit.taken;
+        if !use_weapon {
+            self.bare_handed_kill();
+        }
+        let dead = hand.filter(|&hand| !breaks && self.dead_weight(hand));
         self.awaiting_weapon_choice = false;
-
-        self.message = if use_weapon {
+        self.discard.push(monster);
+        self.monsters_slain += 1;
+
+        self.message = if twist == Some(Boss::Golem) {
+            format!("The Golem shatters your weapon! Took {dmg} damage.")
+        } else if twist == Some(Boss::Dragon) {
+            format!("The Dragon's hide turns half the blow. Took {dmg} damage.")
+        } else if breaks {
+            format!("Fought with weapon! Took {dmg} damage. Your weapon shattered.")
+        } else if use_weapon {
             format!("Fought with weapon! Took {dmg} damage.")
         } else {
             format!("Fought monster! Took {dmg} damage.")
         };
+        self.message.push_str(&hit.note());
+        if let Some(hand) = dead {
+            self.dead_weapon = self.loadout.hands[hand].map(|w| w.card);
+            self.message.push_str(msg::DEAD_WEIGHT);
+        }
+        self.afflict(monster, dmg);
+
+        self.resolved(true, dmg)
+    }
+
+    /// A card has been dealt with: wait for Enter, or go straight on, as `acknowledge`
+    /// says. `prompted` is for a fight that came through the weapon prompt.
+    fn resolved(&mut self, prompted: bool, damage: i32) -> ResolveOutcome {
+        let waits = match self.acknowledge {
+            Acknowledge::Auto => prompted,
+            Acknowledge::Always => true,
+            Acknowledge::AfterDamage => damage > 0,
+        };
+        if waits {
+            ResolveOutcome::AwaitContinue
+        } else {
+            self.continue_after_interaction();
+            ResolveOutcome::None
+        }
+    }
+
+    fn bare_handed_kill(&mut self) {
+        if self.rules.barehanded_bonus {
+            self.bonus_score += 1;
+        }
+    }
+
+    /// `status-effects`: a monster that drew blood leaves its affliction. A second dose
+    /// starts the count again rather than stacking.
+    fn afflict(&mut self, monster: Card, dmg: i32) {
+        if !self.rules.status_effects || dmg <= 0 {
+            return;
+        }
+        let Some(affliction) = Affliction::from_monster(monster) else {
+            return;
+        };
+        self.ability_fired(Ability::Affliction(affliction));
+        self.status_effects.retain(|e| e.affliction != affliction);
+        self.status_effects.push(StatusEffect {
+            affliction,
+            cards_left: (affliction == Affliction::Poison).then_some(POISON_CARDS),
+            since: self.cards_played,
+        });
+        self.message.push_str(match affliction {
+            Affliction::Poison => " You're poisoned.",
+            Affliction::Bleed => " You're bleeding.",
+        });
+    }
 
-        ResolveOutcome::AwaitContinue
+    /// 1 HP per affliction for the card just played, leaving out any it brought on
+    fn tick_status_effects(&mut self) {
+        let mut hurt = 0;
+        for effect in &mut self.status_effects {
+            if effect.since >= self.cards_played {
+                continue;
+            }
+            hurt += 1;
+            if let Some(left) = &mut effect.cards_left {
+                *left -= 1;
+            }
+        }
+        if hurt == 0 {
+            return;
+        }
+        self.status_effects.retain(|e| e.cards_left != Some(0));
+        self.health -= hurt;
+        self.message
+            .push_str(&format!(" Your wounds cost {hurt} HP."));
     }
 
     /// Continue after an acknowledged interaction (Enter)
     pub fn continue_after_interaction(&mut self) {
+        self.tick_status_effects();
+        self.weapon_snared = false;
+
         // Death check
         if self.health <= 0 {
             self.survived = false;
@@ -336,27 +2668,112 @@ impl Game {
             self.fill_room();
 
             if self.room_is_empty() && self.deck.is_empty() {
-                self.survived = true;
-                self.state = GameState::GameOver;
-                self.message = msg::YOU_SURVIVED.to_string();
+                self.dungeon_cleared();
             } else {
-                self.state = GameState::RoomChoice;
-                self.message = msg::ROOM_RESOLVED.to_string();
+                self.rooms_resolved += 1;
+                if self.rules.shop && self.rooms_resolved.is_multiple_of(SHOP_EVERY) {
+                    self.state = GameState::Shop;
+                    self.message = format!("{} You have {} gold.", msg::SHOP_OPEN, self.gold);
+                } else {
+                    self.state = GameState::RoomChoice;
+                    self.message = msg::ROOM_RESOLVED.to_string();
+                }
             }
             return;
         }
 
         // Still in the room interaction window
         if self.room_is_empty() && self.deck.is_empty() {
-            self.survived = true;
-            self.state = GameState::GameOver;
-            self.message = msg::YOU_SURVIVED.to_string();
+            self.dungeon_cleared();
             return;
         }
 
         self.state = GameState::CardSelection;
     }
 
+    /// Whether `ware` is affordable and would do anything
+    pub fn can_buy(&self, ware: Ware) -> bool {
+        self.gold >= ware.price()
+            && self.wares().contains(&ware)
+            && match ware {
+                Ware::Heal => self.health < self.max_health,
+                Ware::Repair => self.loadout.degraded(),
+                Ware::SkipToken => true,
+                Ware::Forge => self.loadout.forged().is_some(),
+            }
+    }
+
+    /// Only reached through `apply`, which has already checked `can_buy`
+    fn buy(&mut self, ware: Ware) {
+        self.gold -= ware.price();
+        self.message = match ware {
+            Ware::Heal => {
+                let heal = SHOP_HEAL.min(self.max_health - self.health);
+                self.health += heal;
+                format!("Healed for {heal} HP.")
+            }
+            Ware::Repair => {
+                self.loadout.repair();
+                msg::SHOP_REPAIRED.to_string()
+            }
+            Ware::SkipToken => {
+                self.skip_tokens += 1;
+                msg::SHOP_TOKEN.to_string()
+            }
+            Ware::Forge => match self.loadout.forge() {
+                Some(([main, off], forged)) => {
+                    self.discard.extend([main, off]);
+                    format!(
+                        "Forged {} and {} into {}.",
+                        card_text(main),
+                        card_text(off),
+                        card_text(forged)
+                    )
+                }
+                None => msg::SHOP_NOTHING_TO_FORGE.to_string(),
+            },
+        };
+        self.message.push_str(&format!(" {} gold left.", self.gold));
+    }
+
+    /// `inventory`: what's in the pack, numbered for `use N`
+    pub fn inventory_summary(&self) -> String {
+        if !self.rules.inventory {
+            return msg::INVENTORY_OFF.to_string();
+        }
+        if self.inventory.is_empty() {
+            return msg::INVENTORY_EMPTY.to_string();
+        }
+        let cards: Vec<String> = self
+            .inventory
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| format!("{}) {}", i + 1, card_text(c)))
+            .collect();
+        format!(
+            "In your pack ({}/{INVENTORY_SIZE}): {}. 'use N' while picking from a room.",
+            self.inventory.len(),
+            cards.join("  ")
+        )
+    }
+
+    /// What the player is allowed to know about the discard pile right now
+    pub fn discard_summary(&self) -> String {
+        let Some(&top) = self.discard.last() else {
+            return msg::DISCARD_EMPTY.to_string();
+        };
+        let count = self.discard.len();
+
+        match self.rules.discard_view {
+            DiscardView::Hidden => format!("{count} cards discarded. {}", msg::DISCARD_HIDDEN),
+            DiscardView::Top => format!("{count} cards discarded, {} on top.", card_text(top)),
+            DiscardView::Full => {
+                let cards: Vec<String> = self.discard.iter().map(|&c| card_text(c)).collect();
+                format!("Discarded, oldest first: {}", cards.join(" "))
+            }
+        }
+    }
+
     pub fn remaining_summary_line(&self) -> String {
         let mut remaining: Vec<Card> = Vec::new();
         remaining.extend(self.room_slots.iter().copied().flatten());
@@ -376,45 +2793,401 @@ impl Game {
         format!("Remaining monsters total threat: -{total_threat}")
     }
 
+    /// Cards never played: the room plus the dungeon
+    pub fn cards_remaining(&self) -> usize {
+        self.room_slots.iter().flatten().count()
+            + self.deck.len()
+            + self.floors_ahead.iter().map(Vec::len).sum::<usize>()
+    }
+
+    /// The top third of the deck is the Upper Halls, the middle the Catacombs, and the
+    /// last third The Depths
+    pub fn depth(&self) -> Depth {
+        let left = self.cards_remaining();
+        if left * 3 > DECK_SIZE * 2 {
+            Depth::UpperHalls
+        } else if left * 3 > DECK_SIZE {
+            Depth::Catacombs
+        } else {
+            Depth::TheDepths
+        }
+    }
+
+    /// Monsters not met yet: the room, the dungeon and any floors still to come
+    pub fn monsters_left(&self) -> impl Iterator<Item = Card> + '_ {
+        self.room_slots
+            .iter()
+            .flatten()
+            .chain(self.deck.iter())
+            .chain(self.floors_ahead.iter().flatten())
+            .copied()
+            .filter(|c| c.is_monster())
+    }
+
+    /// Total value of the monsters still in the room and the dungeon
+    pub fn remaining_threat(&self) -> i32 {
+        self.monsters_left().map(|c| c.value as i32).sum()
+    }
+
+    /// A dulled weapon that can't take any monster left. Never in endless mode, where
+    /// the monsters come round again.
+    pub fn dead_weight(&self, hand: usize) -> bool {
+        !self.endless
+            && self.loadout.hands[hand].is_some_and(|w| w.last_slain.is_some())
+            && self.monsters_left().next().is_some()
+            && !self.monsters_left().any(|m| self.can_use_hand(hand, m))
+    }
+
     pub fn final_score(&self) -> i32 {
-        if self.survived {
-            self.health
+        let base = if self.endless {
+            self.monsters_slain as i32
+        } else if self.survived {
+            self.health + self.potion_bonus()
         } else {
-            let mut remaining: Vec<Card> = Vec::new();
-            remaining.extend(self.room_slots.iter().copied().flatten());
-            remaining.extend(self.deck.iter().copied());
+            -self.remaining_threat()
+        };
+        (base + (self.bonus_score + self.treasure_bonus) as i32) * self.no_skip_multiplier()
+    }
 
-            let sum: i32 = remaining
-                .iter()
-                .filter(|c| c.suit == 'S' || c.suit == 'C')
-                .map(|c| c.value as i32)
-                .sum();
+    /// What `no-skip-multiplier` multiplies a finished run's score by: the rule's value for
+    /// a win with no skips, otherwise 1
+    pub fn no_skip_multiplier(&self) -> i32 {
+        let clear = self.survived && !self.endless && !self.has_skipped();
+        match self.rules.no_skip_multiplier {
+            n if n > 1 && clear => i32::from(n),
+            _ => 1,
+        }
+    }
+
+    /// What `potion-bonus` adds to a finished run's score (0 when it doesn't apply)
+    pub fn potion_bonus(&self) -> i32 {
+        match self.last_played_potion {
+            Some(value)
+                if self.rules.potion_bonus && self.survived && self.health >= self.max_health =>
+            {
+                i32::from(value)
+            }
+            _ => 0,
+        }
+    }
+
+    /// How `final_score` adds up, when there's more to it than one number,
+    /// e.g. `20 HP + 6 potion bonus`
+    pub fn score_breakdown(&self) -> Option<String> {
+        match self.no_skip_multiplier() {
+            1 => self.base_breakdown(),
+            n => Some(format!(
+                "{}, ×{n} for a no-skip clear",
+                self.base_breakdown()
+                    .unwrap_or_else(|| format!("{} HP", self.health))
+            )),
+        }
+    }
+
+    fn base_breakdown(&self) -> Option<String> {
+        // Bonuses on top of the base score
+        let mut bare = match self.bonus_score {
+            0 => String::new(),
+            n => format!(" + {n} bare-handed bonus"),
+        };
+        if self.treasure_bonus > 0 {
+            bare += &format!(" + {} treasure", self.treasure_bonus);
+        }
+        if self.endless {
+            return Some(format!(
+                "{} monsters slain{bare}, {} laps cleared",
+                self.monsters_slain, self.laps
+            ));
+        }
+        match (self.survived, self.potion_bonus()) {
+            (true, 0) if bare.is_empty() => None,
+            (true, 0) => Some(format!("{} HP{bare}", self.health)),
+            (true, bonus) => Some(format!("{} HP + {bonus} potion bonus{bare}", self.health)),
+            (false, _) if bare.is_empty() => None,
+            (false, _) => Some(format!(
+                "{} left in the dungeon{bare}",
+                -self.remaining_threat()
+            )),
+        }
+    }
+}
 
-            -sum
+/// Every card in a fresh dungeon, unshuffled
+pub fn full_deck() -> Vec<Card> {
+    let mut cards = Vec::with_capacity(DECK_SIZE);
+    for suit in ['S', 'C', 'D', 'H'] {
+        for value in 2..=14u8 {
+            // Red aces and face cards removed, them's da rulez
+            if (suit == 'D' || suit == 'H') && value >= 11 {
+                continue;
+            }
+            cards.push(Card { suit, value });
         }
     }
+    cards
 }
 
 // ==============================
-// Rendering helpers (pure)
+// Sandbox
 // ==============================
 
-pub fn card_text(card: Card) -> String {
-    let v = match card.value {
-        11 => "J".to_string(),
-        12 => "Q".to_string(),
-        13 => "K".to_string(),
-        14 => "A".to_string(),
-        _ => card.value.to_string(),
-    };
-
-    let s = match card.suit {
-        'S' => "󱢱",
-        'C' => "󱢥",
-        'D' => "󱢩",
-        'H' => "󱢭",
-        _ => "?",
-    };
-
-    format!("{v}{s}")
+/// A starting position put together on the sandbox screen
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Setup {
+    pub rules: Ruleset,
+    pub health: i32,
+    pub weapon: Option<Card>,
+    /// What the weapon last slew, if it has already been used
+    pub last_slain: Option<u8>,
+    /// The first room, in slot order. Short rooms are topped up from the deck.
+    pub room: Vec<Card>,
+    /// Top of the dungeon, first drawn first
+    pub deck: Vec<Card>,
+    /// Shuffle every card not placed above in under `deck`
+    pub fill: bool,
+}
+
+impl Setup {
+    pub fn new(rules: Ruleset) -> Self {
+        Self {
+            health: i32::from(rules.max_health),
+            rules,
+            weapon: None,
+            last_slain: None,
+            room: Vec::new(),
+            deck: Vec::new(),
+            fill: true,
+        }
+    }
+
+    /// Whether `card` is already in the room, the deck or hand
+    pub fn places(&self, card: Card) -> bool {
+        self.weapon == Some(card) || self.room.contains(&card) || self.deck.contains(&card)
+    }
+
+    /// Take `card` back out of wherever it was placed
+    pub fn remove(&mut self, card: Card) {
+        if self.weapon == Some(card) {
+            self.weapon = None;
+            self.last_slain = None;
+        }
+        self.room.retain(|c| *c != card);
+        self.deck.retain(|c| *c != card);
+    }
+
+    pub fn check(&self) -> Result<(), String> {
+        let max = i32::from(self.rules.max_health);
+        if !(1..=max).contains(&self.health) {
+            return Err(format!("HP has to be between 1 and {max}."));
+        }
+        if let Some(w) = self.weapon
+            && w.suit != 'D'
+        {
+            return Err("Only diamonds can be wielded.".to_string());
+        }
+        if self.last_slain.is_some() && self.weapon.is_none() {
+            return Err("There's no weapon to have slain anything.".to_string());
+        }
+        if self.room.len() > 4 {
+            return Err("A room holds at most 4 cards.".to_string());
+        }
+
+        let deck = full_deck();
+        let placed: Vec<Card> = self
+            .weapon
+            .iter()
+            .chain(&self.room)
+            .chain(&self.deck)
+            .copied()
+            .collect();
+        for (i, card) in placed.iter().enumerate() {
+            if !deck.contains(card) {
+                return Err(format!("{} isn't in a Scoundrel deck.", card.code()));
+            }
+            if placed[..i].contains(card) {
+                return Err(format!("{} is placed twice.", card.code()));
+            }
+        }
+        if self.room.is_empty() && self.deck.is_empty() && !self.fill {
+            return Err("There's nothing to play: add some cards, or turn 'fill' on.".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn once() -> Game {
+        let rules = Ruleset {
+            skips: SkipPolicy::Once,
+            ..Ruleset::default()
+        };
+        let mut game = Game::with_seed(rules, 1);
+        game.start();
+        game
+    }
+
+    #[test]
+    fn first_skip_under_once_is_free() {
+        let mut game = once();
+        assert!(game.skip_allowed());
+        game.apply(Action::Skip);
+        assert_eq!(game.skip_tokens, 0);
+        assert!(game.has_skipped());
+        assert!(!game.skip_allowed());
+    }
+
+    #[test]
+    fn later_skip_under_once_spends_a_token() {
+        let mut game = once();
+        game.apply(Action::Skip);
+        game.skip_tokens = 1;
+        assert!(game.skip_allowed());
+        game.apply(Action::Skip);
+        assert_eq!(game.skip_tokens, 0);
+        assert_eq!(game.state, GameState::RoomChoice);
+    }
+
+    fn ironman() -> Game {
+        let mut game = Game::with_seed(Ruleset::default(), 7);
+        game.make_ironman().unwrap();
+        game.start();
+        game.apply(Action::Face);
+        game
+    }
+
+    #[test]
+    fn ironman_refuses_retry_reseed_and_sandbox() {
+        let mut game = ironman();
+        let before = game.room_slots;
+        assert_eq!(game.retry(2), Err(msg::IRONMAN_RETRY));
+        assert_eq!(
+            game.deal_seed(Ruleset::default(), 8),
+            Err(msg::IRONMAN_RESEED)
+        );
+        assert!(game.play_setup(&Setup::new(Ruleset::default())).is_err());
+        assert_eq!(game.seed, 7);
+        assert_eq!(game.room_slots, before);
+        assert!(!game.sandbox);
+    }
+
+    #[test]
+    fn finished_ironman_run_can_be_retried() {
+        let mut game = ironman();
+        game.health = 0;
+        game.settle_death();
+        assert_eq!(game.state, GameState::GameOver);
+        assert_eq!(game.retry(2), Ok(()));
+        assert!(game.ironman);
+    }
+
+    #[test]
+    fn endless_breakdown_counts_the_monsters() {
+        let mut game = Game::with_seed(Ruleset::default(), 5);
+        game.endless = true;
+        game.monsters_slain = 17;
+        game.laps = 2;
+        assert_eq!(
+            game.score_breakdown().as_deref(),
+            Some("17 monsters slain, 2 laps cleared")
+        );
+        game.bonus_score = 4;
+        assert_eq!(
+            game.score_breakdown().as_deref(),
+            Some("17 monsters slain + 4 bare-handed bonus, 2 laps cleared")
+        );
+    }
+
+    /// Every card dealt into `game`: the room, the deck and the floors below
+    fn dealt(game: &Game) -> Vec<Card> {
+        let mut cards: Vec<Card> = game
+            .room_slots
+            .iter()
+            .flatten()
+            .chain(&game.deck)
+            .chain(game.floors_ahead.iter().flatten())
+            .copied()
+            .collect();
+        cards.sort_by_key(|c| (c.suit, c.value));
+        cards
+    }
+
+    #[test]
+    fn sandbox_deals_each_card_once_across_floors() {
+        let mut setup = Setup::new(Ruleset {
+            floors: 2,
+            bosses: 3,
+            ..Ruleset::default()
+        });
+        setup.room = vec![
+            Card {
+                suit: 'S',
+                value: 14,
+            },
+            Card {
+                suit: 'H',
+                value: 2,
+            },
+        ];
+        let game = Game::from_setup(&setup).unwrap();
+
+        let mut expected: Vec<Card> = full_deck()
+            .into_iter()
+            .chain(BOSSES.iter().map(|b| b.card()))
+            .collect();
+        expected.sort_by_key(|c| (c.suit, c.value));
+        assert_eq!(dealt(&game), expected);
+        assert_eq!(game.floor_count(), 2);
+        assert_eq!(game.room_slots[0], Some(setup.room[0]));
+    }
+
+    #[test]
+    fn a_bouncy_press_applies_one_action() {
+        let rules = Ruleset {
+            skips: SkipPolicy::Always,
+            ..Ruleset::default()
+        };
+        let mut game = Game::with_seed(rules, 9);
+        game.start();
+
+        game.press(Action::Skip);
+        assert_eq!(game.illegal_reason(Action::Skip), None);
+        game.press(Action::Skip);
+        assert_eq!(game.events.len(), 1);
+
+        // A different action off the same press is dropped too
+        game.apply(Action::Face);
+        assert_eq!(game.illegal_reason(Action::Play(0)), None);
+        game.press(Action::Play(0));
+        assert_eq!(game.events.len(), 2);
+
+        // Bots and replays are never latched
+        game.apply(Action::Play(0));
+        assert_eq!(game.events.len(), 3);
+    }
+
+    #[test]
+    fn endless_treasures_pay_out_once() {
+        let rules = Ruleset {
+            treasure_score: true,
+            ..Ruleset::default()
+        };
+        let mut game = Game::with_seed(rules, 21);
+        game.endless = true;
+        game.start();
+        let treasure = game.treasures[0];
+
+        for _ in 0..2 {
+            game.room_slots[0] = Some(treasure);
+            game.state = GameState::CardSelection;
+            game.interactions_left_in_room = 3;
+            game.apply(Action::Play(0));
+        }
+        assert_eq!(game.cards_played, 2);
+        assert_eq!(game.treasures_found, vec![treasure]);
+        assert_eq!(game.treasure_bonus, u32::from(treasure.value));
+        assert!(!game.is_treasure(treasure));
+    }
 }
@@ -1,17 +1,174 @@
+mod achievements;
+mod advisor;
+mod annotate;
+mod cli;
+mod clipboard;
+mod clock;
+mod coach;
+mod command;
+mod config;
+#[cfg(feature = "dev")]
+mod dev;
+mod export;
+#[cfg(feature = "gamepad")]
+mod gamepad;
+mod hint;
+mod locale;
 mod logic;
+mod marathon;
 mod messages;
+mod mirror;
+mod naming;
+mod observe;
+mod pacing;
+mod paths;
+mod presets;
+mod profile;
 mod render;
+mod replay;
+mod rules;
+mod save;
+mod scores;
+mod script;
+mod sim;
+mod solver;
+mod speedrun;
+mod storage;
+mod text;
+mod theme;
 mod ui;
+mod weekly;
 
-use minui::prelude::*;
-use std::time::Duration;
+use std::process::ExitCode;
 
-fn main() -> minui::Result<()> {
-    let initial = ui::AppState::new();
+fn main() -> ExitCode {
+    let args = match cli::Args::from_env() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{e}\n\n{}", cli::usage());
+            return ExitCode::FAILURE;
+        }
+    };
 
-    let mut app = App::new(initial)?.with_frame_rate(Duration::from_millis(16));
+    if args.help {
+        println!("{}", cli::usage());
+        return ExitCode::SUCCESS;
+    }
 
-    app.run(ui::update, ui::draw)?;
+    let dirs = paths::Dirs::resolve(args.portable);
 
-    Ok(())
+    if args.print_paths {
+        println!("config: {}", dirs.config.display());
+        println!("data:   {}", dirs.data.display());
+        println!("logs:   {}", dirs.logs.display());
+        println!("cache:  {}", dirs.cache.display());
+        return ExitCode::SUCCESS;
+    }
+
+    let mut config = match config::Config::load(&dirs.config) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Err(e) = args.apply(&mut config) {
+        eprintln!("{e}\n\n{}", cli::usage());
+        return ExitCode::FAILURE;
+    }
+    render::set_glyphs(config.glyphs);
+    locale::set_locale(config.locale);
+
+    #[cfg(feature = "dev")]
+    if let Some((bot, when)) = &args.bisect {
+        let seed = config.seed.unwrap_or(0);
+        return match dev::bisect(&config.rules, seed, bot, when) {
+            Ok(report) => {
+                print!("{report}");
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    if let Some(path) = &args.script {
+        let played = std::fs::read_to_string(path)
+            .map_err(|e| format!("couldn't read {}: {e}", path.display()))
+            .and_then(|text| script::Script::parse(&text))
+            .and_then(|script| script.run(config));
+        return match played {
+            Ok(frames) => {
+                print!("{frames}");
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    if let Some(dir) = &args.annotate {
+        let rollouts = args.rollouts.unwrap_or(annotate::ROLLOUTS);
+        return match annotate::annotate_dir(dir, rollouts) {
+            Ok(report) => {
+                print!("{report}");
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    if let Some(addr) = &args.mirror {
+        let watched = match mirror::connect(addr) {
+            Ok(stream) => mirror::run(stream, addr, &config).map_err(|e| e.to_string()),
+            Err(e) => Err(format!(
+                "couldn't reach a game at {addr}: {e} (start it with --broadcast {addr})"
+            )),
+        };
+        return match watched {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("{e}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    match run(dirs, config, args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(dirs: paths::Dirs, config: config::Config, args: cli::Args) -> minui::Result<()> {
+    let pacing = pacing::Pacing::new(&config, pacing::on_battery());
+    let storage = storage::open(&dirs.data);
+    let mut initial = ui::AppState::new(storage, config);
+    if let Some(path) = &args.observe {
+        observe::mirror_to_file(initial.publisher.observe(), path.clone());
+    }
+    if let Some(addr) = &args.broadcast {
+        observe::broadcast(initial.publisher.observe(), addr)?;
+    }
+
+    #[cfg(feature = "dev")]
+    {
+        initial.hot_reload = Some(dev::HotReload::new(&dirs.config, args));
+    }
+    #[cfg(not(feature = "dev"))]
+    let _ = args;
+
+    initial.pacing = pacing;
+
+    pacing::run(initial)
 }
@@ -0,0 +1,103 @@
+//! Marathon mode
+//!
+//! Runs are dealt back to back and their final scores add up to a session total until the
+//! player types `stop`. Finished sessions go on their own leaderboard, kept separately from
+//! the per-run high scores in the same one-line-per-entry format.
+
+use std::io;
+
+use crate::storage::Storage;
+
+pub const BOARD: &str = "marathon.txt";
+
+const HEADER: &str = "scoundrel-marathon 1";
+
+/// A session in progress (or just stopped, for the summary screen)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Marathon {
+    /// `(final_score, survived)` for each finished run, in order
+    pub runs: Vec<(i32, bool)>,
+}
+
+impl Marathon {
+    pub fn total(&self) -> i32 {
+        self.runs.iter().map(|(score, _)| score).sum()
+    }
+
+    pub fn wins(&self) -> u32 {
+        self.runs.iter().filter(|(_, survived)| *survived).count() as u32
+    }
+
+    pub fn entry(&self, at_ms: u64) -> SessionEntry {
+        SessionEntry {
+            total: self.total(),
+            runs: self.runs.len() as u32,
+            wins: self.wins(),
+            at_ms,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SessionEntry {
+    pub total: i32,
+    pub runs: u32,
+    pub wins: u32,
+    /// When the session was stopped (`clock::now_ms`)
+    pub at_ms: u64,
+}
+
+/// Every finished session, oldest first. A missing file is an empty board.
+pub fn load(storage: &dyn Storage) -> Result<Vec<SessionEntry>, String> {
+    let text = match storage.read(BOARD) {
+        Ok(Some(text)) => text,
+        Ok(None) => return Ok(Vec::new()),
+        Err(e) => return Err(e.to_string()),
+    };
+
+    let mut lines = text.lines();
+    if lines.next().map(str::trim) != Some(HEADER) {
+        return Err("not a Scoundrel marathon board (or from a newer version)".to_string());
+    }
+    lines
+        .filter(|l| !l.trim().is_empty())
+        .map(parse_line)
+        .collect()
+}
+
+/// Write the whole board back with `entry` appended
+pub fn record(
+    storage: &dyn Storage,
+    entries: &[SessionEntry],
+    entry: SessionEntry,
+) -> io::Result<()> {
+    let mut text = format!("{HEADER}\n");
+    for e in entries.iter().chain([&entry]) {
+        text.push_str(&format!("{} {} {} {}\n", e.total, e.runs, e.wins, e.at_ms));
+    }
+    storage.write(BOARD, &text)
+}
+
+/// Best `n` sessions, highest total first (earlier sessions win ties)
+pub fn top(entries: &[SessionEntry], n: usize) -> Vec<SessionEntry> {
+    let mut sorted = entries.to_vec();
+    sorted.sort_by(|a, b| b.total.cmp(&a.total).then(a.at_ms.cmp(&b.at_ms)));
+    sorted.truncate(n);
+    sorted
+}
+
+/// `total runs wins at_ms`
+fn parse_line(line: &str) -> Result<SessionEntry, String> {
+    let bad = || format!("bad marathon line '{line}'");
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let [total, runs, wins, at_ms] = fields.as_slice() else {
+        return Err(bad());
+    };
+
+    Ok(SessionEntry {
+        total: total.parse().map_err(|_| bad())?,
+        runs: runs.parse().map_err(|_| bad())?,
+        wins: wins.parse().map_err(|_| bad())?,
+        at_ms: at_ms.parse().map_err(|_| bad())?,
+    })
+}
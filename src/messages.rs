@@ -1,30 +1,241 @@
 //! Shared user-facing strings
 
 /// Hint/help lines shown in the Message panel (top line)
-pub const HINT_MAIN: &str = "Main menu: type 'start' to begin.";
+pub const HINT_MAIN: &str = "Main menu: type 'start' to begin, or 'watch' to see an example game.";
+pub const HINT_MAIN_CAN_LOAD: &str =
+    "Main menu: type 'start' to begin, 'load' to resume, or 'watch' for an example game.";
+pub const HINT_RESUME: &str = "Resume previous run? Type 'y' or 'n'.";
 pub const HINT_ROOM_CHOICE_CAN_SKIP: &str = "Room: face or skip.";
 pub const HINT_ROOM_CHOICE_NO_SKIP: &str = "Room: must face (skip already used).";
 pub const HINT_CARD_SELECTION: &str = "Select: click a card, or type 1-4.";
 pub const HINT_PROMPT_WEAPON: &str = "Prompt: type 'y' or 'n'.";
+pub const HINT_PROMPT_HANDS: &str = "Prompt: type '1', '2' or 'n'.";
 pub const HINT_INTERACTION_ACK: &str = "Battle won. Press 'enter' to continue.";
-pub const HINT_GAME_OVER: &str = "Game over: type 'restart' to play again, or Ctrl+Q to quit.";
+pub const HINT_SHOP: &str = "Shop: type what to buy, or 'leave'.";
+pub const HINT_TRAP: &str = "Trap: press 'enter' to pick a card.";
+pub const HINT_GAME_OVER: &str = "Game over: 'restart' or 'retry' this dungeon, 'tag' the run, 'share' or 'export' it, or Ctrl+Q.";
 
 /// Common state/status messages
 pub const ENTERED_DUNGEON: &str = "Entered the dungeon.";
 pub const FACE_ROOM: &str = "Facing the room. Choose a card.";
 pub const SKIPPED_ROOM: &str = "Skipped the room.";
+pub const RESHUFFLED_ROOM: &str = "Reshuffled the room into the dungeon.";
+pub const MULLIGANED: &str =
+    "Mulligan: the opening room went back into the dungeon and a new one was dealt.";
 pub const ROOM_RESOLVED: &str = "Room resolved. Face or skip the next room.";
 pub const YOU_SURVIVED: &str = "You survived the dungeon!";
+pub const TREASURES_FOUND: &str = "You found all three treasures and slipped out of the dungeon!";
+pub const SHOP_TITLE: &str = "Shop";
+pub const SHOP_OPEN: &str = "A merchant sets up shop between the rooms.";
+pub const MERCHANT_TITLE: &str = "Merchant";
+pub const MERCHANT_LEFT: &str = "The merchant packs up. Choose a card.";
+pub const MERCHANT_NO_REPAIR: &str = "This merchant doesn't do repairs.";
+pub const SHOP_LEFT: &str = "You leave the shop. Face or skip the next room.";
+pub const SHOP_REPAIRED: &str = "Your weapon is as good as new.";
+pub const SHOP_TOKEN: &str = "Bought a skip token.";
+pub const OUT_OF_MOVES: &str = "Out of moves. The dungeon keeps you.";
 pub const YOU_DIED: &str = "You succumbed to the dungeon's monsters.";
+pub const TRAP_KILLED: &str = "The trap was the end of you.";
+pub const TRAP_PASSED: &str = "The trap is spent. Choose a card.";
+pub const SNARED_FIGHT: &str = "Your weapon is caught in the snare. You fight bare-handed.";
+pub const RUN_LOADED: &str = "Loaded your saved run.";
+pub const RUN_RESUMED: &str = "Welcome back. Picking up where you left off.";
+pub const AUTOSAVE_FOUND: &str = "You quit in the middle of a run last time.";
+pub const AUTOSAVE_DISCARDED: &str = "Previous run discarded.";
+pub const QUIT_CONFIRM_TITLE: &str = "Quit?";
+pub const QUIT_CONFIRM_BODY: &str = "Your run will be autosaved and offered back next time.";
+pub const QUIT_CONFIRM_KEYS: &str = "y / Enter: quit   n / Esc: keep playing";
 
 /// Validation / guidance messages
 pub const NEED_START: &str = "Type 'start' then 'enter'.";
 pub const NEED_FACE_OR_SKIP: &str = "Type 'face' or 'skip'.";
 pub const NEED_FACE_ONLY: &str = "Must face — skip already used.";
+pub const SHARE_COPIED: &str = "Result copied to the clipboard (if your terminal allows it).";
+pub const DISCARD_EMPTY: &str = "Nothing has been discarded yet.";
+pub const DISCARD_HIDDEN: &str = "No peeking: try remembering them.";
+pub const SKIP_SPENT: &str = "You've used your one skip for this run.";
+pub const SKIPS_OFF: &str = "Skipping is off under these rules: every room must be faced.";
+pub const SKIPS_DISABLED: &str = "No skipping this week: every room must be faced.";
+pub const SEED_HIDDEN: &str = "The seed is hidden in streamer mode.";
+pub const NO_RESHUFFLES_LEFT: &str = "No reshuffles left.";
+pub const RESHUFFLE_BEFORE_FACING: &str = "You can only reshuffle a room before facing it.";
+pub const BANISH_OFF: &str = "Banishing is off (rule: banish).";
+pub const BANISH_SPENT: &str = "You've already banished a card this run.";
+pub const BANISH_LAST_CARD: &str = "You can't banish the last card in the room.";
+pub const BANISH_NOT_NOW: &str = "You can only banish a card while choosing one from the room.";
+pub const BANISH_KEPT: &str = "Nothing banished.";
+pub const INVENTORY_OFF: &str = "You have no pack to stash cards in (rule: inventory).";
+pub const INVENTORY_FULL: &str = "Your pack is full. 'use N' something in it first.";
+pub const INVENTORY_CANT_STASH: &str =
+    "Only potions and weapons you can see fit in your pack, and not merchants or treasures.";
+pub const INVENTORY_NO_SUCH: &str = "There's nothing there in your pack ('inv' to look).";
+pub const INVENTORY_NOT_NOW: &str =
+    "You can only stash or use a card while choosing one from the room.";
+pub const INVENTORY_EMPTY: &str =
+    "Your pack is empty. 'inv N' stashes a potion or weapon from the room.";
+pub const FORGE_KEPT: &str = "You keep both weapons.";
+pub const MULLIGAN_OFF: &str = "Mulligans are off (rule: mulligan).";
+pub const MULLIGAN_SPENT: &str = "You've already taken your mulligan.";
+pub const MULLIGAN_TOO_LATE: &str =
+    "A mulligan is only for the opening room, before you face or skip it.";
 pub const NEED_SELECT_CARD: &str = "Type 1-4 to select a card, or click a card.";
+pub const DROP_ON_WEAPON: &str = "Only a diamond can be dropped on the weapon line.";
+pub const DROP_ON_HEALTH: &str = "Only a heart can be dropped on the HP bar.";
+pub const DROP_HIDDEN: &str = "A face-down card can only be played where it lies.";
 pub const INVALID_CARD_SELECTION: &str = "Invalid card selection.";
 pub const MUST_FACE_FIRST: &str = "You must face the room before selecting.";
 pub const NEED_Y_OR_N: &str = "Type 'y' or 'n'.";
-pub const RESTART_HELP: &str = "Type 'restart' to play again, 'exit' to quit, or Ctrl+Q.";
+pub const NOT_NOW: &str = "You can't do that right now.";
+pub const NEED_SHOP: &str = "Type what to buy ('heal', 'repair', 'token') or 'leave'.";
+pub const SHOP_NO_GOLD: &str = "You can't afford that.";
+pub const SHOP_NOTHING_TO_REPAIR: &str = "Your weapon doesn't need repairing.";
+pub const SHOP_NOTHING_TO_FORGE: &str =
+    "Forging takes two weapons in hand that would make a stronger one.";
+pub const SHOP_FULL_HEALTH: &str = "You're already at full health.";
+pub const NOTHING_TO_TAG: &str = "Only a run that went into the score table can be tagged.";
+pub const NOTHING_TO_SAVE: &str = "Nothing to save — no run in progress.";
+pub const NO_SAVE_FOUND: &str = "No saved run found.";
+pub const STORAGE_PRUNE_HINT: &str = "'storage prune' deletes old exports.";
+pub const STORAGE_NOTHING_TO_PRUNE: &str = "Nothing to prune: your exports are within the limits.";
+pub const RESTART_HELP: &str =
+    "Type 'restart' for a new dungeon, 'retry' for this one again, 'exit' to quit, or Ctrl+Q.";
+pub const RETRY_SANDBOX: &str =
+    "A sandbox position can't be retried. Type 'sandbox' to set it up again.";
+
+/// First-time rule explanations
+pub const DEAD_WEIGHT: &str = " Nothing left in the dungeon is weak enough for that weapon now.";
+pub const DEAD_WEIGHT_TITLE: &str = "Dead weight";
+pub const DEAD_WEIGHT_TAG: &str = " · dead weight";
+pub const LESSON_HELP: &str = "Enter or Esc to carry on. You won't see this one again.";
+pub const LESSON_DEGRADED_TITLE: &str = "Your weapon is dulled";
+pub const LESSON_DEGRADED: &str = "A weapon that has slain a monster can only fight monsters weaker than the last one it killed. Save it for the big ones, or fight small fry bare-handed.";
+pub const LESSON_POTION_TITLE: &str = "Potion limit";
+pub const LESSON_POTION: &str = "Only so many potions heal per room (one, in the standard game). Any more in the same room are thrown away.";
+pub const LESSON_FORCED_TITLE: &str = "No skipping twice";
+pub const LESSON_FORCED: &str = "You can't skip two rooms in a row, so this one has to be faced.";
+
+/// Marathon mode
+pub const MARATHON_STARTED: &str =
+    "Marathon started. Runs follow one another until you type 'stop'.";
+pub const MARATHON_LOCKED: &str = "Not during a marathon. Finish the run, or type 'stop'.";
+pub const NO_MARATHON: &str = "No marathon running. Type 'marathon' on the main menu.";
+pub const MARATHON_EMPTY: &str = "Marathon stopped before any run finished.";
+pub const MARATHON_SUMMARY_TITLE: &str = "Marathon over";
+pub const MARATHON_BOARD_TITLE: &str = "Best marathons";
+pub const MARATHON_SUMMARY_HELP: &str = "Enter or Esc to return to the menu.";
+
+/// Casual mode
+pub const CASUAL_STARTED: &str =
+    "Casual game: two potions heal per room, and facing a room heals 2 HP. Take your time.";
+
+/// Endless mode
+pub const ENDLESS_STARTED: &str =
+    "Endless dungeon. Clear it and it deals again; your score is every monster you slay.";
+
+pub const TARGET_STARTED: &str = "Coach on. Every room choice, you'll hear how you're pacing for";
+
+/// Ironman
+pub const IRONMAN_STARTED: &str =
+    "Ironman run: no saving, loading or retrying until it's over. It'll be marked in the scores.";
+pub const IRONMAN_SAVE: &str = "Ironman run: no saving. Quitting still keeps your place.";
+pub const IRONMAN_UNDO: &str = "Ironman run: no loading over it. Every move stands.";
+pub const IRONMAN_RETRY: &str = "Ironman run: no retrying until it's over.";
+pub const IRONMAN_RESEED: &str = "Ironman run: no dealing another seed until it's over.";
+pub const IRONMAN_SANDBOX_SWAP: &str = "Ironman run: no swapping it for a sandbox position.";
+pub const IRONMAN_SANDBOX: &str = "A sandbox position can't be played as ironman.";
+
+/// Weekly challenge
+pub const WEEKLY_TITLE: &str = "Weekly challenge";
+pub const WEEKLY_MUTATORS: &str = "This week's twists:";
+pub const WEEKLY_HELP: &str = "Same dungeon for everyone this week, standard rules otherwise. Enter to begin, Esc to go back.";
+pub const WEEKLY_STARTED: &str = "Weekly challenge underway. Good luck.";
+
+/// Custom game screen
+pub const CUSTOM_TITLE: &str = "Custom game";
+pub const CUSTOM_HELP: &str = "Type a rule and a value (e.g. 'equal-fight chip'), 'test' to try it on a bot, then 'play'. Esc or 'back' to leave.";
+pub const CUSTOM_PLACEHOLDER: &str =
+    "rule value | paste a code | code | test | save/load/delete NAME | reset | play | back";
+pub const PRESETS_TITLE: &str = "Presets";
+pub const NO_PRESETS_YET: &str = "None yet. 'save NAME' keeps the rules above for next time.";
+pub const PRESETS_UNAVAILABLE: &str = "Couldn't read the presets file, so presets can't be saved.";
+pub const BAD_PRESET_NAME: &str = "Preset names are up to 24 letters, digits, '-' or '_'.";
+
+/// Sandbox screen
+pub const SANDBOX_TITLE: &str = "Sandbox";
+pub const SANDBOX_HELP: &str = "Set up a position (e.g. 'hp 9', 'weapon 7d', 'room 10s qc 5h'), then 'play'. Esc or 'back' to leave.";
+pub const SANDBOX_PLACEHOLDER: &str = "hp N | weapon CARD [LAST] | room/deck CARDS | remove CARDS | fill on/off | clear | play | back";
+pub const SANDBOX_LEGEND: &str =
+    "r room · d deck · w weapon  ('room', 'deck' or 'weapon' picks where clicks go)";
+pub const SANDBOX_STARTED: &str = "Sandbox run: practice only, it won't count toward your scores.";
+
+/// Example game viewer
+pub const DEMO_PLAYING: &str = "type to pause and add a note, Enter or Esc to stop watching";
+pub const DEMO_NOTE_PAUSED: &str = "paused, Enter attaches this note to step";
+pub const DEMO_OVER: &str =
+    "That's the whole run. Type a note, or Enter or Esc to return to the menu.";
+pub const DEMO_NOTE_TOO_EARLY: &str =
+    "Nothing has happened yet. Wait for the first move, then add a note.";
+pub const DEMO_STOPPED: &str = "Back at the main menu. Type 'start' when you're ready.";
+
+/// Dev builds: config hot-reload
+#[cfg(feature = "dev")]
+pub const CONFIG_RELOADED: &str = "Reloaded config.toml. New rules apply from the next run.";
+
+/// Notes panel
+pub const NOTES_TITLE: &str = "Notes (Tab)";
+pub const NOTES_PLACEHOLDER: &str = "Counts, plans, anything. Saved with the run.";
+
+/// Streamer mode panel
+pub const HINT_NOTHING: &str = "There's no move to suggest right now.";
+pub const ANALYSIS_TITLE: &str = "Analysis done";
+pub const ANALYZE_NOTHING: &str = "There's only one way forward here, so nothing to weigh up.";
+pub const ANALYSIS_STALE: &str = "The game moved on before it finished: 'analyze' again";
+pub const INVENTORY_TITLE: &str = "Inventory";
+pub const INVENTORY_HELP: &str = "'use N' while picking a card · 'inv N' stashes one from the room";
+pub const STREAM_TITLE: &str = "On stream";
+
+/// High score table
+pub const SCORES_TITLE: &str = "High Scores";
+pub const SIMULATION_TITLE: &str = "Simulation done";
+pub const NO_SCORES_YET: &str = "No finished runs yet.";
+pub const SCORES_UNAVAILABLE: &str = "Couldn't read the score file, so scores aren't being kept.";
+
+/// Mutators from the main menu
+pub const MUTATORS_HELP: &str = "Type 'mutators' and some of them, or a friend's code.";
+
+/// New Game+
+pub const HINT_GAME_WON: &str = "You won: 'plus' carries your weapon into New Game+, or 'restart', 'retry', 'tag', 'share' or 'export'.";
+pub const PLUS_NEEDS_WIN: &str = "New Game+ follows a win.";
+pub const PLUS_SANDBOX: &str = "A sandbox position can't lead into New Game+.";
+pub const PLUS_NO_WEAPON: &str = "There's no weapon in that hand to bring along.";
+pub const PLUS_NO_ARMOR: &str = "There's no armor to bring along.";
+pub const PLUS_NOTHING: &str = "Nothing in hand to bring along. 'plus' needs a weapon or armor.";
+
+/// Ascension ladder
+pub const ASCENSION_LOCKED: &str = "Win a standard game to unlock ascension 1.";
+
+/// Difficulty advice on the main menu
+pub const ADVICE_DISMISS_HINT: &str = "('dismiss' to stop these tips)";
+pub const ADVICE_DISMISSED: &str = "Got it. No more difficulty tips.";
+
+/// Achievements screen
+pub const ACHIEVEMENTS_TITLE: &str = "Achievements";
+pub const ACHIEVEMENTS_HELP: &str =
+    "Progress adds up across runs. Type 'achievements' or press Esc to close.";
+pub const ACHIEVEMENTS_UNAVAILABLE: &str =
+    "Couldn't read your profile, so achievements aren't being tracked.";
+
+/// Move log screen
+pub const LOG_TITLE: &str = "Move log";
+pub const LOG_HELP: &str =
+    "Every card played and room skipped this run. Type 'log' or press Esc to close.";
+pub const LOG_EMPTY: &str = "Nothing played yet.";
+
+/// Contrast audit screen
+pub const CONTRAST_AUDIT_TITLE: &str = "Contrast audit: text needs 4.5:1, borders 3:1 (WCAG AA).";
+pub const CONTRAST_AUDIT_HELP: &str =
+    "Transparent backgrounds are measured against black. Type 'contrast' or press Esc to close.";
+pub const CONTRAST_AUDIT_HELP_SOLID: &str =
+    "Measured against each theme's panel background. Type 'contrast' or press Esc to close.";
 
 pub const CMD_PREFIX: &str = "> ";
@@ -0,0 +1,182 @@
+//! `--mirror ADDR`: a read-only copy of a game being played in another terminal
+//!
+//! The game has to be started with `--broadcast ADDR`. What comes over the wire is the
+//! observer's state (see `observe.rs`), so the mirror shows what a viewer may see: HP,
+//! weapon, the room (face-down cards stay face down), the message and the score at the
+//! end. Nothing typed here reaches the game; `q` or Esc closes the mirror.
+
+use std::io::{self, BufRead, BufReader};
+use std::net::TcpStream;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use minui::Window;
+use minui::prelude::*;
+use minui::widgets::ContainerPadding;
+
+use crate::config::{Config, KeyAction};
+use crate::observe::ObservableState;
+use crate::render::{self, Glyphs, card_color, card_text, health_color, health_line};
+use crate::theme::Theme;
+
+/// How often the screen is redrawn while waiting for news
+const REFRESH: Duration = Duration::from_millis(100);
+
+/// What the reader thread has heard so far
+#[derive(Default)]
+struct Feed {
+    latest: Option<ObservableState>,
+    /// The game closed the connection
+    gone: bool,
+}
+
+pub fn connect(addr: &str) -> io::Result<TcpStream> {
+    TcpStream::connect(addr)
+}
+
+pub fn run(stream: TcpStream, addr: &str, config: &Config) -> minui::Result<()> {
+    let feed: Arc<Mutex<Feed>> = Arc::default();
+    let writer = Arc::clone(&feed);
+    thread::spawn(move || {
+        let mut frame = String::new();
+        for line in BufReader::new(stream).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            // An empty line ends a state
+            if !line.is_empty() {
+                frame += &line;
+                frame.push('\n');
+                continue;
+            }
+            if let Ok(state) = ObservableState::decode(&frame) {
+                writer.lock().unwrap_or_else(|e| e.into_inner()).latest = Some(state);
+            }
+            frame.clear();
+        }
+        writer.lock().unwrap_or_else(|e| e.into_inner()).gone = true;
+    });
+
+    let mut window = TerminalWindow::new()?;
+    window.set_auto_flush(false);
+    loop {
+        window.clear_screen()?;
+        {
+            let feed = feed.lock().unwrap_or_else(|e| e.into_inner());
+            draw(&mut window, &feed, addr, config)?;
+        }
+        let event = window.get_input_timeout(REFRESH)?;
+        let quit = matches!(
+            event.as_legacy_key_event(),
+            Some(Event::Character('q') | Event::Escape)
+        ) || config.keys.action_for(&event) == Some(KeyAction::Quit);
+        if quit {
+            return Ok(());
+        }
+    }
+}
+
+fn draw(window: &mut dyn Window, feed: &Feed, addr: &str, config: &Config) -> minui::Result<()> {
+    let theme = &config.theme;
+    let glyphs = render::glyphs();
+    let (w, h) = window.get_size();
+
+    let title = match (&feed.latest, feed.gone) {
+        (_, true) => format!("Scoundrel · mirror of {addr} · the game has closed"),
+        (None, false) => format!("Scoundrel · mirror of {addr} · waiting for the game"),
+        (Some(_), false) => format!("Scoundrel · mirror of {addr}"),
+    };
+    panel(window, theme, glyphs, (0, 0, w, h), &title)?;
+    window.write_str_colored(
+        h.saturating_sub(2),
+        2,
+        "Read-only. q or Esc closes the mirror.",
+        theme.muted,
+    )?;
+
+    let Some(state) = &feed.latest else {
+        return window.end_frame();
+    };
+    let inner_w = w.saturating_sub(2);
+
+    // Status
+    panel(window, theme, glyphs, (1, 1, inner_w, 5), "Status")?;
+    window.write_str_colored(
+        2,
+        3,
+        &health_line(state.health, state.max_health),
+        health_color(state.health, theme),
+    )?;
+    window.write_str_colored(
+        3,
+        3,
+        &render::weapon_line(state.weapon, state.last_slain, None),
+        theme.text,
+    )?;
+    let mut progress = format!(
+        "Cards left in Dungeon: {}  Rooms: {}  Turn {}",
+        state.cards_left, state.rooms_resolved, state.turns
+    );
+    if let Some(score) = state.score {
+        let outcome = if state.survived { "survived" } else { "died" };
+        progress += &format!("  Score {score} ({outcome})");
+    }
+    window.write_str_colored(4, 3, &progress, theme.text)?;
+
+    // Room
+    panel(window, theme, glyphs, (1, 6, inner_w, 5), "Room")?;
+    let card_w = inner_w.saturating_sub(4) / 4;
+    for i in 0..4 {
+        let x = 3 + i as u16 * card_w;
+        let (label, color) = match state.room[i] {
+            _ if state.hidden[i] => (
+                format!("[{}] {} face down", i + 1, glyphs.card_back),
+                theme.muted,
+            ),
+            Some(card) => (
+                format!("[{}] {}", i + 1, card_text(card)),
+                card_color(card, theme),
+            ),
+            None => ("[ ] empty".to_string(), theme.empty_slot),
+        };
+        panel(
+            window,
+            theme,
+            glyphs,
+            (x, 7, card_w.saturating_sub(1), 3),
+            "",
+        )?;
+        window.write_str_colored(8, x + 1, &label, color)?;
+    }
+
+    // Message
+    panel(window, theme, glyphs, (1, 11, inner_w, 3), "Message")?;
+    let message: String = state
+        .message
+        .chars()
+        .take(usize::from(inner_w.saturating_sub(4)))
+        .collect();
+    window.write_str_colored(12, 3, &message, theme.text)?;
+
+    window.end_frame()
+}
+
+/// A bordered box at `(x, y, w, h)`
+fn panel(
+    window: &mut dyn Window,
+    theme: &Theme,
+    glyphs: &Glyphs,
+    (x, y, w, h): (u16, u16, u16, u16),
+    title: &str,
+) -> minui::Result<()> {
+    Container::new()
+        .with_position_and_size(x, y, w, h)
+        .with_border()
+        .with_border_chars(theme.frames.root.border.chars(glyphs))
+        .with_border_color(theme.panel_border)
+        .with_title(title)
+        .with_title_alignment(theme.frames.root.title)
+        .with_padding(ContainerPadding::uniform(0))
+        .draw(window)
+}
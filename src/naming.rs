@@ -0,0 +1,67 @@
+//! Weapon names
+//!
+//! Every weapon picked up gets a name from its value and how much it has killed: a
+//! "Rusty Dagger" fresh off the floor, "Oathbreaker +3 kills" once it has earned one. The
+//! words come from the run seed and the card, so a weapon keeps its name for the whole run
+//! and the same dungeon names its weapons the same way every time.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::logic::Card;
+
+/// Kills it takes for a weapon to earn a proper name
+const NAMED_AT: u32 = 3;
+
+/// By value: 2-4, 5-7, 8-10
+const ADJECTIVES: [[&str; 4]; 3] = [
+    ["Rusty", "Chipped", "Bent", "Crude"],
+    ["Plain", "Sturdy", "Worn", "Balanced"],
+    ["Fine", "Gleaming", "Heavy", "Masterwork"],
+];
+const NOUNS: [[&str; 4]; 3] = [
+    ["Dagger", "Knife", "Shiv", "Dirk"],
+    ["Shortsword", "Hatchet", "Mace", "Cudgel"],
+    ["Longsword", "Warhammer", "Greataxe", "Halberd"],
+];
+const TITLES: [&str; 12] = [
+    "Oathbreaker",
+    "Widowmaker",
+    "Gravesong",
+    "Nightfall",
+    "Kingslayer",
+    "Thornheart",
+    "Duskfang",
+    "Last Word",
+    "Grudge",
+    "Mercy",
+    "Ashbringer",
+    "Hollow Crown",
+];
+
+/// e.g. `Rusty Dagger`, `Sturdy Mace +1 kill`, `Oathbreaker +3 kills`
+pub fn weapon_name(seed: u64, weapon: Card, kills: u32) -> String {
+    // Mix the card in so two weapons in the same run don't share their words
+    let mut rng =
+        StdRng::seed_from_u64(seed ^ u64::from(weapon.value).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    let tier = match weapon.value {
+        ..=4 => 0,
+        5..=7 => 1,
+        _ => 2,
+    };
+    // Always draw all three, so the name only changes when the kills do
+    let adjective = ADJECTIVES[tier][rng.gen_range(0..4)];
+    let noun = NOUNS[tier][rng.gen_range(0..4)];
+    let title = TITLES[rng.gen_range(0..TITLES.len())];
+
+    let name = if kills >= NAMED_AT {
+        title.to_string()
+    } else {
+        format!("{adjective} {noun}")
+    };
+    match kills {
+        0 => name,
+        1 => format!("{name} +1 kill"),
+        n => format!("{name} +{n} kills"),
+    }
+}
@@ -0,0 +1,277 @@
+//! Read-only view of the game for other threads
+//!
+//! `Publisher` sits on the UI side and is handed every new state after an update;
+//! `Observer`s are cheap handles anyone can hold (an overlay writer, a presence bridge, a
+//! stats widget) to see what's on the table without borrowing the `Game`. `--observe FILE`
+//! is the built-in one: it keeps FILE up to date with the latest state. `--broadcast ADDR`
+//! sends the same text to anyone who connects, which is what `--mirror ADDR` draws from.
+
+use std::io::{self, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::logic::{Card, Game, GameState};
+use crate::save;
+
+/// What an observer gets: a copy of the parts of a `Game` worth showing
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ObservableState {
+    /// `GameState` as named in save files, e.g. `card-selection`
+    pub state: &'static str,
+    /// Left out in streamer mode, the same as on screen
+    pub seed: Option<u64>,
+    pub health: i32,
+    pub max_health: i32,
+    pub weapon: Option<Card>,
+    pub last_slain: Option<u8>,
+    /// Face-down and dark cards (`fog`, `dark`) are `None` here and flagged in `hidden`
+    pub room: [Option<Card>; 4],
+    pub hidden: [bool; 4],
+    pub cards_left: usize,
+    pub rooms_resolved: u32,
+    /// One per card played or room skipped
+    pub turns: usize,
+    /// Only once the run is over
+    pub score: Option<i32>,
+    pub survived: bool,
+    /// What the Message panel says, on one line
+    pub message: String,
+}
+
+impl ObservableState {
+    pub fn of(game: &Game, streamer_mode: bool) -> Self {
+        let over = game.state == GameState::GameOver;
+        ObservableState {
+            state: save::state_name(game.state),
+            seed: (!streamer_mode).then_some(game.seed),
+            health: game.health,
+            max_health: game.max_health,
+            weapon: game.weapon(),
+            last_slain: game.last_slain(),
+            // Face-down and dark cards stay a secret from the stream too
+            room: std::array::from_fn(|i| game.visible_card(i)),
+            hidden: std::array::from_fn(|i| game.is_hidden(i) || game.is_dark(i)),
+            cards_left: game.cards_remaining(),
+            rooms_resolved: game.rooms_resolved,
+            turns: game.turns(),
+            score: over.then(|| game.final_score()),
+            survived: game.survived,
+            message: game.message.replace('\n', " "),
+        }
+    }
+
+    /// `key=value` lines, the same shape as a save file
+    pub fn encode(&self) -> String {
+        let card = |c: Option<Card>| c.map(Card::code).unwrap_or_default();
+        let mut lines = vec![format!("state={}", self.state)];
+        if let Some(seed) = self.seed {
+            lines.push(format!("seed={seed}"));
+        }
+        lines.extend([
+            format!("health={}", self.health),
+            format!("max_health={}", self.max_health),
+            format!("weapon={}", card(self.weapon)),
+            format!(
+                "last_slain={}",
+                self.last_slain.map(|v| v.to_string()).unwrap_or_default()
+            ),
+            format!(
+                "room={}",
+                (0..4)
+                    .map(|i| if self.hidden[i] {
+                        "?".to_string()
+                    } else {
+                        card(self.room[i])
+                    })
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+            format!("cards_left={}", self.cards_left),
+            format!("rooms_resolved={}", self.rooms_resolved),
+            format!("turns={}", self.turns),
+            format!(
+                "score={}",
+                self.score.map(|s| s.to_string()).unwrap_or_default()
+            ),
+            format!("survived={}", self.survived),
+            format!("message={}", self.message),
+        ]);
+        lines.join("\n") + "\n"
+    }
+
+    /// Read back what `encode` wrote. Unknown keys are skipped, so a newer game can still
+    /// be mirrored.
+    pub fn decode(text: &str) -> Result<Self, String> {
+        let mut state = ObservableState::default();
+        let card = |v: &str| -> Result<Option<Card>, String> {
+            if v.is_empty() {
+                Ok(None)
+            } else {
+                save::parse_card(v).map(Some)
+            }
+        };
+        for line in text.lines() {
+            let Some((k, v)) = line.split_once('=') else {
+                continue;
+            };
+            match k {
+                "state" => state.state = save::state_name(save::parse_state(v)?),
+                "seed" => state.seed = Some(number(k, v)?),
+                "health" => state.health = number(k, v)?,
+                "max_health" => state.max_health = number(k, v)?,
+                "weapon" => state.weapon = card(v)?,
+                "last_slain" if v.is_empty() => state.last_slain = None,
+                "last_slain" => state.last_slain = Some(number(k, v)?),
+                "room" => {
+                    for (i, c) in v.split(',').take(4).enumerate() {
+                        state.hidden[i] = c == "?";
+                        state.room[i] = if c == "?" { None } else { card(c)? };
+                    }
+                }
+                "cards_left" => state.cards_left = number(k, v)?,
+                "rooms_resolved" => state.rooms_resolved = number(k, v)?,
+                "turns" => state.turns = number(k, v)?,
+                "score" if v.is_empty() => state.score = None,
+                "score" => state.score = Some(number(k, v)?),
+                "survived" => state.survived = v == "true",
+                "message" => state.message = v.to_string(),
+                _ => {}
+            }
+        }
+        Ok(state)
+    }
+}
+
+fn number<T: FromStr>(key: &str, value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("bad '{key}'"))
+}
+
+/// The latest state and how many times it has changed
+#[derive(Default)]
+struct Shared {
+    latest: Mutex<(u64, ObservableState)>,
+    changed: Condvar,
+}
+
+/// The writing end, owned by `AppState`
+#[derive(Default)]
+pub struct Publisher {
+    shared: Arc<Shared>,
+}
+
+impl Publisher {
+    /// A new handle, which starts out having seen nothing
+    pub fn observe(&self) -> Observer {
+        Observer {
+            shared: Arc::clone(&self.shared),
+            seen: 0,
+        }
+    }
+
+    /// Share `game`'s state, waking observers only if something they can see changed.
+    /// In streamer mode the seed stays off the feed.
+    pub fn publish(&self, game: &Game, streamer_mode: bool) {
+        let now = ObservableState::of(game, streamer_mode);
+        let mut latest = self.shared.latest.lock().unwrap_or_else(|e| e.into_inner());
+        if latest.0 > 0 && latest.1 == now {
+            return;
+        }
+        *latest = (latest.0 + 1, now);
+        self.shared.changed.notify_all();
+    }
+}
+
+/// A read-only handle on the game, for any thread
+#[derive(Clone)]
+pub struct Observer {
+    shared: Arc<Shared>,
+    /// Version this handle last returned
+    seen: u64,
+}
+
+impl Observer {
+    /// The next state this handle hasn't seen yet, waiting up to `timeout` for one
+    pub fn changed(&mut self, timeout: Duration) -> Option<ObservableState> {
+        let latest = self.shared.latest.lock().unwrap_or_else(|e| e.into_inner());
+        let (latest, _) = self
+            .shared
+            .changed
+            .wait_timeout_while(latest, timeout, |(version, _)| *version == self.seen)
+            .unwrap_or_else(|e| e.into_inner());
+        if latest.0 == self.seen {
+            return None;
+        }
+        self.seen = latest.0;
+        Some(latest.1.clone())
+    }
+}
+
+/// `--observe FILE`: rewrite FILE with every new state, from a thread of its own. Errors
+/// are dropped; a missing overlay file shouldn't stop the game.
+pub fn mirror_to_file(mut observer: Observer, path: PathBuf) {
+    thread::spawn(move || {
+        loop {
+            if let Some(state) = observer.changed(Duration::from_secs(1)) {
+                let _ = std::fs::write(&path, state.encode());
+            }
+        }
+    });
+}
+
+/// `--broadcast ADDR`: listen on ADDR and send every new state to each connected viewer,
+/// an empty line after each. Someone joining late gets the latest state straight away.
+/// Binding happens here, so a taken port is reported before the game starts; everything
+/// after that runs on threads of its own and a viewer going away is simply forgotten.
+pub fn broadcast(mut observer: Observer, addr: &str) -> io::Result<SocketAddr> {
+    let listener = TcpListener::bind(addr)?;
+    let local = listener.local_addr()?;
+    let viewers: Arc<Mutex<(Vec<TcpStream>, String)>> = Arc::default();
+
+    let joining = Arc::clone(&viewers);
+    thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let mut viewers = joining.lock().unwrap_or_else(|e| e.into_inner());
+            if viewers.1.is_empty() || stream.write_all(viewers.1.as_bytes()).is_ok() {
+                viewers.0.push(stream);
+            }
+        }
+    });
+
+    thread::spawn(move || {
+        loop {
+            if let Some(state) = observer.changed(Duration::from_secs(1)) {
+                let frame = state.encode() + "\n";
+                let mut viewers = viewers.lock().unwrap_or_else(|e| e.into_inner());
+                viewers
+                    .0
+                    .retain_mut(|s| s.write_all(frame.as_bytes()).is_ok());
+                viewers.1 = frame;
+            }
+        }
+    });
+    Ok(local)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Ruleset;
+
+    #[test]
+    fn streamer_mode_keeps_the_seed_off_the_feed() {
+        let mut game = Game::with_seed(Ruleset::default(), 4242);
+        game.start();
+
+        let open = ObservableState::of(&game, false).encode();
+        assert!(open.lines().any(|l| l == "seed=4242"));
+        assert_eq!(ObservableState::decode(&open).unwrap().seed, Some(4242));
+
+        let streamed = ObservableState::of(&game, true).encode();
+        assert!(!streamed.contains("seed="));
+        assert_eq!(ObservableState::decode(&streamed).unwrap().seed, None);
+    }
+}
@@ -0,0 +1,152 @@
+//! Frame pacing
+//!
+//! One policy for how often the game wakes up: the frame rate, whether the card tooltip
+//! waits for the pointer to settle, and whether an idle screen is redrawn at all. Low power
+//! (`--low-power`, or running on battery) turns all three down.
+//!
+//! The loop here stands in for `minui::App::run`, which redraws flat out between frames.
+
+use std::time::{Duration, Instant};
+
+use minui::Window;
+use minui::prelude::*;
+
+use crate::config::Config;
+use crate::ui::{self, AppState};
+
+/// Frame rate cap while in low power
+pub const LOW_POWER_FPS: u64 = 10;
+/// How long an idle screen sleeps in low power before looking up again (the streamer
+/// panel's run timer still needs a tick now and then)
+const IDLE: Duration = Duration::from_secs(1);
+/// Hover time before a card tooltip shows, when not in low power
+const TOOLTIP_DELAY: Duration = Duration::from_millis(300);
+
+/// `low_power` in the config file
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LowPower {
+    /// On while running on battery, where that can be told
+    #[default]
+    Auto,
+    On,
+    Off,
+}
+
+impl LowPower {
+    pub const VALUES: &str = "true, false or \"auto\"";
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pacing {
+    pub low_power: bool,
+    frame: Duration,
+}
+
+impl Pacing {
+    /// `on_battery` only counts if `low_power` is left on `auto`
+    pub fn new(config: &Config, on_battery: bool) -> Pacing {
+        let low_power = match config.low_power {
+            LowPower::Auto => on_battery,
+            LowPower::On => true,
+            LowPower::Off => false,
+        };
+        let frame = if low_power {
+            config
+                .frame_interval()
+                .max(Duration::from_millis(1000 / LOW_POWER_FPS))
+        } else {
+            config.frame_interval()
+        };
+        Pacing { low_power, frame }
+    }
+
+    /// Time between ticks. In low power an idle screen (`busy` false) barely ticks at all.
+    pub fn interval(&self, busy: bool) -> Duration {
+        if self.low_power && !busy {
+            IDLE
+        } else {
+            self.frame
+        }
+    }
+
+    /// Tooltips pop up straight away in low power, so nothing has to tick while they wait
+    pub fn tooltip_delay(&self) -> Duration {
+        if self.low_power {
+            Duration::ZERO
+        } else {
+            TOOLTIP_DELAY
+        }
+    }
+}
+
+/// Whether the machine is running off its battery. Only Linux can tell; elsewhere it's
+/// always `false`, and `--low-power` is the way in.
+#[cfg(target_os = "linux")]
+pub fn on_battery() -> bool {
+    let Ok(supplies) = std::fs::read_dir("/sys/class/power_supply") else {
+        return false;
+    };
+    let read = |dir: &std::path::Path, file: &str| {
+        std::fs::read_to_string(dir.join(file))
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default()
+    };
+
+    let mut discharging = false;
+    for supply in supplies.flatten() {
+        let dir = supply.path();
+        match read(&dir, "type").as_str() {
+            // Plugged in beats any battery
+            "Mains" if read(&dir, "online") == "1" => return false,
+            "Battery" => discharging |= read(&dir, "status") == "Discharging",
+            _ => {}
+        }
+    }
+    discharging
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn on_battery() -> bool {
+    false
+}
+
+/// Play until `ui::update` says stop. Draws after anything happens, ticks at
+/// `state.pacing`'s interval, and otherwise sleeps waiting for input.
+pub fn run(mut state: AppState) -> minui::Result<()> {
+    let mut window = TerminalWindow::new()?;
+    window.set_auto_flush(false);
+
+    let mut last_tick = Instant::now();
+    let mut dirty = true;
+    loop {
+        if dirty {
+            window.clear_screen()?;
+            ui::draw(&mut state, &mut window)?;
+            dirty = false;
+        }
+
+        let interval = state.pacing.interval(state.busy());
+        let wait = interval.saturating_sub(last_tick.elapsed());
+        let event = window.get_input_timeout(wait)?;
+        if !matches!(event, Event::Unknown) {
+            if !ui::update(&mut state, event) {
+                return Ok(());
+            }
+            // Take the rest of a burst (mouse moves, a paste) before drawing again
+            while let Some(event) = window.poll_input()? {
+                if !ui::update(&mut state, event) {
+                    return Ok(());
+                }
+            }
+            dirty = true;
+        }
+
+        if last_tick.elapsed() >= interval {
+            if !ui::update(&mut state, Event::Frame) {
+                return Ok(());
+            }
+            last_tick = Instant::now();
+            dirty = true;
+        }
+    }
+}
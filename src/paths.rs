@@ -0,0 +1,109 @@
+//! Platform-appropriate locations for everything the game writes
+//!
+//! - Linux/BSD: XDG base directories (`$XDG_CONFIG_HOME`, `$XDG_DATA_HOME`, `$XDG_STATE_HOME`,
+//!   `$XDG_CACHE_HOME`)
+//! - macOS: `~/Library/Application Support`, `~/Library/Logs` and `~/Library/Caches`
+//! - Windows: `%APPDATA%` for config, `%LOCALAPPDATA%` for data, logs and cache
+//!
+//! `--portable` keeps everything in a `scoundrel-data` folder beside the binary instead.
+
+use std::env;
+use std::path::PathBuf;
+
+const APP_DIR: &str = "scoundrel";
+const PORTABLE_DIR: &str = "scoundrel-data";
+
+#[derive(Clone, Debug)]
+pub struct Dirs {
+    /// User-edited settings (config.toml)
+    pub config: PathBuf,
+    /// Saves, scores and profile data
+    pub data: PathBuf,
+    /// Diagnostic output, safe to delete
+    pub logs: PathBuf,
+    /// Anything that can be rebuilt, also safe to delete
+    pub cache: PathBuf,
+}
+
+impl Dirs {
+    pub fn resolve(portable: bool) -> Self {
+        if portable {
+            Self::portable()
+        } else {
+            Self::platform()
+        }
+    }
+
+    fn portable() -> Self {
+        let base = env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(|p| p.to_path_buf()))
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join(PORTABLE_DIR);
+
+        Self {
+            config: base.join("config"),
+            data: base.join("data"),
+            logs: base.join("logs"),
+            cache: base.join("cache"),
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    fn platform() -> Self {
+        let roaming = env_dir("APPDATA").unwrap_or_else(fallback_base);
+        let local = env_dir("LOCALAPPDATA").unwrap_or_else(|| roaming.clone());
+
+        Self {
+            config: roaming.join(APP_DIR),
+            data: local.join(APP_DIR),
+            logs: local.join(APP_DIR).join("logs"),
+            cache: local.join(APP_DIR).join("cache"),
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    fn platform() -> Self {
+        let home = env_dir("HOME").unwrap_or_else(fallback_base);
+        let support = home
+            .join("Library")
+            .join("Application Support")
+            .join(APP_DIR);
+
+        Self {
+            config: support.clone(),
+            data: support,
+            logs: home.join("Library").join("Logs").join(APP_DIR),
+            cache: home.join("Library").join("Caches").join(APP_DIR),
+        }
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    fn platform() -> Self {
+        let home = env_dir("HOME").unwrap_or_else(fallback_base);
+        let xdg = |var: &str, default: &[&str]| {
+            env_dir(var).unwrap_or_else(|| default.iter().fold(home.clone(), |p, s| p.join(s)))
+        };
+
+        Self {
+            config: xdg("XDG_CONFIG_HOME", &[".config"]).join(APP_DIR),
+            data: xdg("XDG_DATA_HOME", &[".local", "share"]).join(APP_DIR),
+            logs: xdg("XDG_STATE_HOME", &[".local", "state"])
+                .join(APP_DIR)
+                .join("logs"),
+            cache: xdg("XDG_CACHE_HOME", &[".cache"]).join(APP_DIR),
+        }
+    }
+}
+
+/// Only absolute paths count, per the XDG spec (relative values must be ignored)
+fn env_dir(var: &str) -> Option<PathBuf> {
+    env::var_os(var)
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+}
+
+/// No usable home directory at all: behave like `--portable` relative to the working dir
+fn fallback_base() -> PathBuf {
+    PathBuf::from(".").join(PORTABLE_DIR)
+}
@@ -0,0 +1,227 @@
+//! Named rule presets
+//!
+//! Built on the custom game screen and kept in `presets.txt`, one per line, e.g.
+//! `brutal: equal-fight=chip comeback=off`. Only knobs that differ from the standard game
+//! are written, so a preset keeps meaning the same thing as new knobs are added.
+//!
+//! Rules also travel as short codes like `SC1.EC.R1.C10`: a version, then one segment per
+//! changed knob, made of the knob's `code` letter and its value (numbers as they are,
+//! named values by the initials of their words, so `free-kill` is `FK`). Codes are
+//! case-insensitive. Knobs added later just don't appear in older codes, so those keep
+//! working; the version only needs bumping if the scheme itself changes.
+//!
+//! Mutators travel as four-character codes like `XK7Q` (`mutators XK7Q` on the main menu):
+//! 20 bits in Crockford base32, ten for which `MUTATORS` are on and ten of checksum, so a
+//! typo is caught instead of dealing the wrong game.
+
+use std::io;
+
+use crate::logic::{MUTATORS, Mutator};
+use crate::rules::{KNOBS, Knob, Ruleset};
+use crate::storage::Storage;
+
+pub const PRESETS: &str = "presets.txt";
+
+const HEADER: &str = "scoundrel-presets 1";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Preset {
+    pub name: String,
+    pub rules: Ruleset,
+}
+
+/// Letters, digits, `-` and `_`, so names survive the file format and typed commands
+pub fn valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 24
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// A missing file is no presets yet
+pub fn load(storage: &dyn Storage) -> Result<Vec<Preset>, String> {
+    let text = match storage.read(PRESETS) {
+        Ok(Some(text)) => text,
+        Ok(None) => return Ok(Vec::new()),
+        Err(e) => return Err(e.to_string()),
+    };
+
+    let mut lines = text.lines();
+    if lines.next().map(str::trim) != Some(HEADER) {
+        return Err("not a Scoundrel presets file (or from a newer version)".to_string());
+    }
+    lines
+        .filter(|l| !l.trim().is_empty())
+        .map(parse_line)
+        .collect()
+}
+
+pub fn save(storage: &dyn Storage, presets: &[Preset]) -> io::Result<()> {
+    let mut text = format!("{HEADER}\n");
+    for preset in presets {
+        let knobs: Vec<String> = preset
+            .rules
+            .differences()
+            .iter()
+            .map(|(k, v)| format!("{k}={v}"))
+            .collect();
+        text.push_str(&format!("{}: {}\n", preset.name, knobs.join(" ")));
+    }
+    storage.write(PRESETS, &text)
+}
+
+fn parse_line(line: &str) -> Result<Preset, String> {
+    let (name, knobs) = line
+        .split_once(':')
+        .ok_or_else(|| format!("bad preset line '{line}'"))?;
+
+    let mut rules = Ruleset::default();
+    for pair in knobs.split_whitespace() {
+        let (key, value) = pair
+            .split_once('=')
+            .ok_or_else(|| format!("bad rule '{pair}' in preset '{name}'"))?;
+        rules.set(key, value)?;
+    }
+    Ok(Preset {
+        name: name.trim().to_string(),
+        rules,
+    })
+}
+
+// ==============================
+// Share codes
+// ==============================
+
+const CODE_VERSION: &str = "SC1";
+
+pub fn to_code(rules: &Ruleset) -> String {
+    let mut segments = vec![CODE_VERSION.to_string()];
+    for (key, value) in rules.differences() {
+        if let Some(knob) = KNOBS.iter().find(|k| k.key == key) {
+            segments.push(format!("{}{}", knob.code, abbreviate(&value)).to_uppercase());
+        }
+    }
+    segments.join(".")
+}
+
+pub fn from_code(code: &str) -> Result<Ruleset, String> {
+    let code = code.trim().to_lowercase();
+    let mut segments = code.split('.');
+
+    let version = segments.next().unwrap_or_default();
+    if version != CODE_VERSION.to_lowercase() {
+        return Err(match version.strip_prefix("sc").map(str::parse::<u32>) {
+            Some(Ok(_)) => "That code is from a newer version of Scoundrel.".to_string(),
+            _ => format!("'{code}' isn't a rules code."),
+        });
+    }
+
+    let mut rules = Ruleset::default();
+    for segment in segments.filter(|s| !s.is_empty()) {
+        let mut chars = segment.chars();
+        let letter = chars.next().unwrap_or_default();
+        let knob = KNOBS.iter().find(|k| k.code == letter).ok_or_else(|| {
+            format!("Unknown rule '{letter}' in the code (from a newer version?)")
+        })?;
+        let value = expand(knob, chars.as_str()).ok_or_else(|| {
+            format!(
+                "Bad value '{}' for {} in the code",
+                chars.as_str(),
+                knob.key
+            )
+        })?;
+        rules.set(knob.key, &value)?;
+    }
+    Ok(rules)
+}
+
+/// `free-kill` -> `fk`, `10` -> `10`
+fn abbreviate(value: &str) -> String {
+    if value.chars().all(|c| c.is_ascii_digit()) {
+        return value.to_string();
+    }
+    value.split('-').filter_map(|w| w.chars().next()).collect()
+}
+
+/// Inverse of `abbreviate`, using the knob's list of named values
+fn expand(knob: &Knob, short: &str) -> Option<String> {
+    if !short.is_empty() && short.chars().all(|c| c.is_ascii_digit()) {
+        return Some(short.to_string());
+    }
+    knob.values
+        .split('|')
+        .map(str::trim)
+        .find(|v| abbreviate(v) == short)
+        .map(str::to_string)
+}
+
+// ==============================
+// Mutator codes
+// ==============================
+
+/// Crockford's base32: no I, L, O or U, so codes read back unambiguously
+const BASE32: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const MUTATOR_CODE_LEN: usize = 4;
+/// Bits for the mutators themselves; room for more than there are yet
+const MUTATOR_BITS: u32 = 10;
+const MUTATOR_MASK: u32 = (1 << MUTATOR_BITS) - 1;
+
+pub fn mutator_code(mutators: &[Mutator]) -> String {
+    let bits = MUTATORS
+        .iter()
+        .enumerate()
+        .filter(|(_, m)| mutators.contains(m))
+        .fold(0u32, |bits, (i, _)| bits | 1 << i);
+    let mut packed = checksum(bits) << MUTATOR_BITS | bits;
+    let mut code = [0u8; MUTATOR_CODE_LEN];
+    for c in code.iter_mut().rev() {
+        *c = BASE32[(packed & 31) as usize];
+        packed >>= 5;
+    }
+    String::from_utf8_lossy(&code).into_owned()
+}
+
+/// The mutators in a code from `mutator_code`, in registry order
+pub fn mutators_from_code(code: &str) -> Result<Vec<Mutator>, String> {
+    let bad = || format!("'{code}' isn't a mutators code.");
+    if code.chars().count() != MUTATOR_CODE_LEN {
+        return Err(bad());
+    }
+    let mut packed = 0u32;
+    for c in code.chars() {
+        // Read the way people tend to copy it down
+        let c = match c.to_ascii_uppercase() {
+            'O' => '0',
+            'I' | 'L' => '1',
+            c => c,
+        };
+        let digit = BASE32
+            .iter()
+            .position(|&b| char::from(b) == c)
+            .ok_or_else(bad)?;
+        packed = packed << 5 | digit as u32;
+    }
+    let bits = packed & MUTATOR_MASK;
+    if packed >> MUTATOR_BITS != checksum(bits) {
+        return Err(bad());
+    }
+    if bits >> MUTATORS.len() != 0 {
+        return Err("That code has mutators from a newer version of Scoundrel.".to_string());
+    }
+    if bits == 0 {
+        return Err(bad());
+    }
+    Ok(MUTATORS
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| bits & 1 << i != 0)
+        .map(|(_, &m)| m)
+        .collect())
+}
+
+/// Ten bits, different for every set of mutators, so changing any one character of a
+/// code leaves it not adding up
+fn checksum(bits: u32) -> u32 {
+    (bits.wrapping_mul(0x2C9) ^ 0x1B5) & MUTATOR_MASK
+}
@@ -0,0 +1,263 @@
+//! Player profile
+//!
+//! Lifetime stats that outlive any single run. Stored as `key=value` lines; unknown keys
+//! are ignored so older builds can still read a profile written by newer ones.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io;
+
+use crate::locale;
+use crate::logic::ASCENSIONS;
+use crate::messages as msg;
+use crate::scores::ScoreEntry;
+use crate::storage::Storage;
+
+pub const PROFILE: &str = "profile.txt";
+
+const HEADER: &str = "scoundrel-profile 1";
+
+/// Finished runs before the main menu starts suggesting a difficulty
+pub const ADVICE_AFTER: u32 = 5;
+/// Losses in a row that make it suggest an easier game
+const LOSING_RUN: usize = 5;
+/// Wins in a row that make it suggest a harder one
+const WINNING_RUN: usize = 3;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Profile {
+    pub wins: u32,
+    pub losses: u32,
+    /// Consecutive wins up to and including the latest run
+    pub current_streak: u32,
+    pub best_streak: u32,
+    /// Achievement counters, tallied from each finished run's events
+    pub weapon_kills: u32,
+    pub rooms_faced: u32,
+    pub hp_healed: u32,
+    /// `Lesson` keys already explained. Kept as text so keys from newer builds survive.
+    pub seen_lessons: BTreeSet<String>,
+    /// The latest attempt at each seed that's been retried
+    pub seed_attempts: BTreeMap<u64, u32>,
+    /// Fastest win on each seed with the speedrun timer on, in ms of play time
+    pub best_times: BTreeMap<u64, u64>,
+    /// Turned the main menu's difficulty advice off for good (`dismiss`)
+    pub advice_dismissed: bool,
+    /// Highest rung of the ascension ladder unlocked so far
+    pub ascension: u32,
+}
+
+impl Profile {
+    pub fn record(&mut self, survived: bool) {
+        if survived {
+            self.wins += 1;
+            self.current_streak += 1;
+            self.best_streak = self.best_streak.max(self.current_streak);
+        } else {
+            self.losses += 1;
+            self.current_streak = 0;
+        }
+    }
+
+    /// Note a retry of `seed`. First attempts aren't kept, so the list only holds the
+    /// dungeons someone went back to.
+    pub fn record_attempt(&mut self, seed: u64, attempt: u32) {
+        if attempt > 1 {
+            let tried = self.seed_attempts.entry(seed).or_default();
+            *tried = (*tried).max(attempt);
+        }
+    }
+
+    /// Keep `ms` as the best time on `seed` if it beats the old one. True if it did.
+    pub fn record_time(&mut self, seed: u64, ms: u64) -> bool {
+        let best = self.best_times.entry(seed).or_insert(u64::MAX);
+        let beaten = ms < *best;
+        *best = (*best).min(ms);
+        beaten
+    }
+
+    /// A win on the highest rung reached so far unlocks the next one. `counts` says whether
+    /// the run was on the ladder at all, which a standard game is as rung 0. The rung
+    /// unlocked, if any.
+    pub fn climb(&mut self, level: u32, counts: bool) -> Option<u32> {
+        if !counts || level != self.ascension || level as usize >= ASCENSIONS.len() {
+            return None;
+        }
+        self.ascension += 1;
+        Some(self.ascension)
+    }
+
+    /// Goes at `seed` so far; a seed never retried has had one
+    pub fn attempts_at(&self, seed: u64) -> u32 {
+        self.seed_attempts.get(&seed).copied().unwrap_or(1)
+    }
+
+    /// A nudge toward an easier or harder game, read off how the latest of `runs` (the
+    /// score table, oldest first) went. Nothing until `ADVICE_AFTER` runs are done, or
+    /// once it's been dismissed.
+    pub fn advice(&self, runs: &[ScoreEntry], ranks_on: bool) -> Option<String> {
+        if self.advice_dismissed || self.wins + self.losses < ADVICE_AFTER {
+            return None;
+        }
+        let last = runs.last()?;
+        let streak = runs
+            .iter()
+            .rev()
+            .take_while(|r| r.survived == last.survived && r.variant == last.variant)
+            .count();
+
+        let tip = match (last.survived, last.variant.as_str()) {
+            (false, _) => {
+                let lost = runs.iter().rev().take_while(|r| !r.survived).count();
+                if lost < LOSING_RUN {
+                    return None;
+                }
+                if last.variant != "casual" {
+                    let ranks = if ranks_on {
+                        ""
+                    } else {
+                        ", or turn on 'ranks' to see which cards hurt most"
+                    };
+                    format!(
+                        "You've lost {lost} in a row. Try 'casual' for a gentler dungeon{ranks}."
+                    )
+                } else if !ranks_on {
+                    format!(
+                        "You've lost {lost} in a row. Try 'ranks' to see which cards hurt most."
+                    )
+                } else {
+                    format!("You've lost {lost} in a row. 'watch' an example game for ideas.")
+                }
+            }
+            (true, "casual") if streak >= WINNING_RUN => format!(
+                "You've won {streak} casual runs in a row. Ready for the real thing? Type 'start'."
+            ),
+            (true, "standard") if streak >= WINNING_RUN => {
+                format!("You've won {streak} in a row. Try 'ascend' for a harder climb.")
+            }
+            _ => return None,
+        };
+        Some(format!("{tip} {}", msg::ADVICE_DISMISS_HINT))
+    }
+
+    /// One-line summary for the menu and GameOver screens, e.g.
+    /// `Lifetime: 4 wins, 9 losses (31%) · streak 1 · best 3`
+    pub fn summary(&self) -> String {
+        let played = self.wins + self.losses;
+        if played == 0 {
+            return "Lifetime: no finished runs yet".to_string();
+        }
+        let rate = self.wins * 100 / played;
+        let mut line = format!(
+            "Lifetime: {} wins, {} losses ({}) · streak {} · best {}",
+            self.wins,
+            self.losses,
+            locale::percent(rate.into()),
+            self.current_streak,
+            self.best_streak
+        );
+        if self.ascension > 0 {
+            line += &format!(" · ascension {}", self.ascension);
+        }
+        line
+    }
+}
+
+/// A missing file is a fresh profile
+pub fn load(storage: &dyn Storage) -> Result<Profile, String> {
+    let text = match storage.read(PROFILE) {
+        Ok(Some(text)) => text,
+        Ok(None) => return Ok(Profile::default()),
+        Err(e) => return Err(e.to_string()),
+    };
+
+    let mut lines = text.lines();
+    if lines.next().map(str::trim) != Some(HEADER) {
+        return Err("not a Scoundrel profile (or from a newer version)".to_string());
+    }
+    let fields: HashMap<&str, &str> = lines.filter_map(|l| l.split_once('=')).collect();
+    let num = |k: &str| -> Result<u32, String> {
+        match fields.get(k) {
+            Some(v) => v.parse().map_err(|_| format!("bad number for '{k}'")),
+            None => Ok(0),
+        }
+    };
+
+    Ok(Profile {
+        wins: num("wins")?,
+        losses: num("losses")?,
+        current_streak: num("current_streak")?,
+        best_streak: num("best_streak")?,
+        weapon_kills: num("weapon_kills")?,
+        rooms_faced: num("rooms_faced")?,
+        hp_healed: num("hp_healed")?,
+        seen_lessons: fields
+            .get("seen_lessons")
+            .map(|v| {
+                v.split(',')
+                    .filter(|k| !k.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default(),
+        seed_attempts: fields
+            .get("seed_attempts")
+            .map(|v| {
+                v.split(',')
+                    .filter(|p| !p.is_empty())
+                    .map(|p| {
+                        p.split_once(':')
+                            .and_then(|(s, n)| Some((s.parse().ok()?, n.parse().ok()?)))
+                            .ok_or_else(|| format!("bad seed attempt '{p}'"))
+                    })
+                    .collect::<Result<_, _>>()
+            })
+            .transpose()?
+            .unwrap_or_default(),
+        best_times: fields
+            .get("best_times")
+            .map(|v| {
+                v.split(',')
+                    .filter(|p| !p.is_empty())
+                    .map(|p| {
+                        p.split_once(':')
+                            .and_then(|(s, ms)| Some((s.parse().ok()?, ms.parse().ok()?)))
+                            .ok_or_else(|| format!("bad best time '{p}'"))
+                    })
+                    .collect::<Result<_, _>>()
+            })
+            .transpose()?
+            .unwrap_or_default(),
+        advice_dismissed: num("advice_dismissed")? != 0,
+        ascension: num("ascension")?.min(ASCENSIONS.len() as u32),
+    })
+}
+
+pub fn save(storage: &dyn Storage, profile: &Profile) -> io::Result<()> {
+    let seen: Vec<&str> = profile.seen_lessons.iter().map(String::as_str).collect();
+    let attempts: Vec<String> = profile
+        .seed_attempts
+        .iter()
+        .map(|(seed, n)| format!("{seed}:{n}"))
+        .collect();
+    let times: Vec<String> = profile
+        .best_times
+        .iter()
+        .map(|(seed, ms)| format!("{seed}:{ms}"))
+        .collect();
+    let text = format!(
+        "{HEADER}\nwins={}\nlosses={}\ncurrent_streak={}\nbest_streak={}\nweapon_kills={}\nrooms_faced={}\nhp_healed={}\nseen_lessons={}\nseed_attempts={}\nbest_times={}\nadvice_dismissed={}\nascension={}\n",
+        profile.wins,
+        profile.losses,
+        profile.current_streak,
+        profile.best_streak,
+        profile.weapon_kills,
+        profile.rooms_faced,
+        profile.hp_healed,
+        seen.join(","),
+        attempts.join(","),
+        times.join(","),
+        u8::from(profile.advice_dismissed),
+        profile.ascension
+    );
+    storage.write(PROFILE, &text)
+}
@@ -1,11 +1,136 @@
 //! Rendering helpers
 //! Core game rules and state transitions in `logic.rs`
 
-use crate::logic::Card;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::logic::{Action, Armor, Card, Game};
+use crate::theme::Theme;
 use minui::prelude::*;
 
+// ==============================
+// Glyphs
+// ==============================
+
+/// Characters for suits, the HP bar, badges and panel borders
+#[derive(Clone, Copy, Debug)]
+pub struct Glyphs {
+    pub name: &'static str,
+    /// Spades, clubs, diamonds, hearts
+    pub suits: [&'static str; 4],
+    pub bar_full: &'static str,
+    pub bar_empty: &'static str,
+    /// The health a pending choice would cost, laid over the bar
+    pub bar_forecast: &'static str,
+    pub reshuffle: &'static str,
+    /// Marks treasure cards under `treasure-hunt`
+    pub treasure: &'static str,
+    /// Marks trapped cards under `traps`
+    pub trap: &'static str,
+    /// Marks merchant cards under `merchant`
+    pub merchant: &'static str,
+    /// A face-down card under `fog`
+    pub card_back: &'static str,
+    /// Marks boss cards and the warning before them under `bosses`
+    pub boss: &'static str,
+    /// `status-effects` icons in the Status panel
+    pub poison: &'static str,
+    pub bleed: &'static str,
+    pub single_border: BorderChars,
+    pub double_border: BorderChars,
+    pub rounded_border: BorderChars,
+}
+
+/// The original look; needs a Nerd Font
+pub const NERD_GLYPHS: Glyphs = Glyphs {
+    name: "nerd",
+    suits: ["󱢱", "󱢥", "󱢩", "󱢭"],
+    bar_full: "█",
+    bar_empty: "░",
+    bar_forecast: "▒",
+    reshuffle: "↻",
+    treasure: "★",
+    trap: "⚠",
+    merchant: "$",
+    card_back: "▒▒",
+    boss: "☠",
+    poison: "☣",
+    bleed: "✗",
+    single_border: BorderChars::single_line(),
+    double_border: BorderChars::double_line(),
+    rounded_border: BorderChars::rounded(),
+};
+
+pub const UNICODE_GLYPHS: Glyphs = Glyphs {
+    name: "unicode",
+    suits: ["♠", "♣", "♦", "♥"],
+    ..NERD_GLYPHS
+};
+
+/// For fonts or terminals with no box drawing at all
+pub const ASCII_GLYPHS: Glyphs = Glyphs {
+    name: "ascii",
+    suits: ["S", "C", "D", "H"],
+    bar_full: "#",
+    bar_empty: "-",
+    bar_forecast: "x",
+    reshuffle: "R",
+    treasure: "*",
+    trap: "^",
+    merchant: "$",
+    card_back: "??",
+    boss: "!",
+    poison: "P",
+    bleed: "B",
+    single_border: BorderChars::ascii(),
+    double_border: BorderChars::ascii(),
+    rounded_border: BorderChars::ascii(),
+};
+
+pub const GLYPH_SETS: &[Glyphs] = &[NERD_GLYPHS, UNICODE_GLYPHS, ASCII_GLYPHS];
+
+pub fn glyphs_by_name(name: &str) -> Option<&'static Glyphs> {
+    GLYPH_SETS
+        .iter()
+        .find(|g| g.name.eq_ignore_ascii_case(name))
+}
+
+pub fn glyph_set_names() -> String {
+    GLYPH_SETS
+        .iter()
+        .map(|g| g.name)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Index into `GLYPH_SETS`, picked at startup (and again on a dev-mode config reload).
+/// Card text also ends up in `Game::message`, which `logic.rs` builds far from any UI
+/// state, so this is global rather than passed everywhere.
+static GLYPHS: AtomicUsize = AtomicUsize::new(0);
+
+pub fn set_glyphs(glyphs: &Glyphs) {
+    if let Some(i) = GLYPH_SETS.iter().position(|g| g.name == glyphs.name) {
+        GLYPHS.store(i, Ordering::Relaxed);
+    }
+}
+
+pub fn glyphs() -> &'static Glyphs {
+    &GLYPH_SETS[GLYPHS.load(Ordering::Relaxed)]
+}
+
+// ==============================
+// Text
+// ==============================
+
 /// Returns a short glyph string like `9󰣎` or `A󰋑`
 pub fn card_text(card: Card) -> String {
+    card_face(card, true)
+}
+
+/// `card_text`, with the value left as `?` unless `revealed` (a card in a `dark` room)
+pub fn card_face(card: Card, revealed: bool) -> String {
+    if !revealed {
+        return format!("?{}", suit_text(card));
+    }
     let v = match card.value {
         11 => "J".to_string(),
         12 => "Q".to_string(),
@@ -13,38 +138,39 @@ pub fn card_text(card: Card) -> String {
         14 => "A".to_string(),
         _ => card.value.to_string(),
     };
+    format!("{v}{}", suit_text(card))
+}
 
-    let s = match card.suit {
-        'S' => "󱢱",
-        'C' => "󱢥",
-        'D' => "󱢩",
-        'H' => "󱢭",
+fn suit_text(card: Card) -> &'static str {
+    let suits = glyphs().suits;
+    match card.suit {
+        'S' => suits[0],
+        'C' => suits[1],
+        'D' => suits[2],
+        'H' => suits[3],
         _ => "?",
-    };
-
-    format!("{v}{s}")
+    }
 }
 
-/// Card foreground colors:
-/// - Diamonds/Hearts: red
-/// - Spades/Clubs: white
-pub fn card_color(card: Card) -> ColorPair {
+/// Card colors:
+/// - Diamonds/Hearts: the theme's red
+/// - Spades/Clubs: the theme's black (white on most themes)
+pub fn card_color(card: Card, theme: &Theme) -> ColorPair {
     match card.suit {
-        'D' | 'H' => ColorPair::new(Color::LightRed, Color::Transparent),
-        _ => ColorPair::new(Color::White, Color::Transparent),
+        'D' | 'H' => theme.card_red,
+        _ => theme.card_black,
     }
 }
 
 /// HP text color used for the status line
-pub fn health_color(hp: i32) -> ColorPair {
-    let fg = if hp > 10 {
-        Color::Green
+pub fn health_color(hp: i32, theme: &Theme) -> ColorPair {
+    if hp > 10 {
+        theme.hp_high
     } else if hp > 5 {
-        Color::Yellow
+        theme.hp_mid
     } else {
-        Color::Red
-    };
-    ColorPair::new(fg, Color::Transparent)
+        theme.hp_low
+    }
 }
 
 /// Returns a fixed-width HP bar like `█████░░░░░` (clamped to `[0, max_hp]`)
@@ -52,8 +178,8 @@ pub fn health_bar(hp: i32, max_hp: i32) -> String {
     let max_hp = max_hp.max(0);
     let hp = hp.max(0).min(max_hp);
 
-    let filled = "█".repeat(hp as usize);
-    let empty = "░".repeat((max_hp - hp) as usize);
+    let filled = glyphs().bar_full.repeat(hp as usize);
+    let empty = glyphs().bar_empty.repeat((max_hp - hp) as usize);
     format!("{filled}{empty}")
 }
 
@@ -63,19 +189,113 @@ pub fn health_line(hp: i32, max_hp: i32) -> String {
     format!("Health: {hp}/{max_hp} |{}|", health_bar(hp, max_hp))
 }
 
-/// Formats a weapon label, including the "must be < N" restriction when present
+/// Formats a weapon label, including its name and the "must be < N" restriction when present
 ///
 /// Example outputs:
 /// - `Weapon: None`
-/// - `Weapon: 7 (must be < 10)`
-pub fn weapon_line(weapon: Option<Card>, last_monster_slain_with_weapon: Option<u8>) -> String {
+/// - `Weapon: 7 Sturdy Mace +1 kill (must be < 10)`
+pub fn weapon_line(
+    weapon: Option<Card>,
+    last_monster_slain_with_weapon: Option<u8>,
+    name: Option<&str>,
+) -> String {
     match weapon {
         None => "Weapon: None".to_string(),
         Some(w) => {
             let limit = last_monster_slain_with_weapon
                 .map(|l| format!(" (must be < {l})"))
                 .unwrap_or_default();
-            format!("Weapon: {}{limit}", card_text(w))
+            let name = name.map(|n| format!(" {n}")).unwrap_or_default();
+            format!("Weapon: {}{name}{limit}", card_text(w))
         }
     }
 }
+
+/// Worn armor under the `armor` rule, e.g. `Armor: 3 (-3 a hit, 4 left)`
+pub fn armor_line(armor: Armor) -> String {
+    format!(
+        "Armor: {} (-{} a hit, {} left)",
+        card_text(armor.card),
+        armor.card.value.min(armor.left()),
+        armor.left()
+    )
+}
+
+/// Status badge for the room reshuffle consumable, e.g. `↻ x1`
+pub fn reshuffle_badge(left: u8) -> String {
+    format!("{} x{left}", glyphs().reshuffle)
+}
+
+// ==============================
+// Sharing
+// ==============================
+
+/// Where `share` keeps a copy of the block
+pub const SHARE_FILE: &str = "share.txt";
+
+/// Wordle-style result block for pasting into chat, one row per room:
+/// `⚔` weapon kill, `🩸` hit taken bare-handed, `❤` healed, `🗡` weapon equipped (or a
+/// wasted potion), `⏭` room skipped, `🔀` room reshuffled, `🎒` card stashed in the pack
+/// (using it later shows as a heal or an equip).
+///
+/// Built from the event log alone, with each card's effect read off the HP change.
+pub fn share_text(game: &Game) -> String {
+    let mut rows: Vec<String> = Vec::new();
+    let mut faced = 0u32;
+    let mut hp = game.starting_health();
+
+    for (i, event) in game.events.iter().enumerate() {
+        // Picking a monster with a usable weapon only raises the prompt; the fight is next
+        let prompts = game
+            .events
+            .get(i + 1)
+            .is_some_and(|next| matches!(next.action, Action::Fight { .. }));
+
+        let mark = match event.action {
+            Action::Face => {
+                faced += 1;
+                rows.push(String::new());
+                None
+            }
+            Action::Skip => {
+                rows.push("⏭".to_string());
+                None
+            }
+            Action::Reshuffle | Action::Mulligan => {
+                rows.push("🔀".to_string());
+                None
+            }
+            Action::Play(_) if prompts => None,
+            Action::Fight { weapon: Some(_) } => Some("⚔"),
+            Action::Play(_) | Action::Fight { weapon: None } if event.health < hp => Some("🩸"),
+            Action::Play(_) | Action::Use(_) if event.health > hp => Some("❤"),
+            Action::Play(_) | Action::Use(_) => Some("🗡"),
+            Action::Stash(_) => Some("🎒"),
+            Action::Banish(_) => Some("✖"),
+            // Bare-handed fights always hurt
+            Action::Fight { .. } | Action::Continue | Action::Buy(_) | Action::Leave => None,
+        };
+
+        if let (Some(mark), Some(row)) = (mark, rows.last_mut()) {
+            row.push_str(mark);
+        }
+        hp = event.health;
+    }
+
+    // Dying mid-room means that room wasn't cleared
+    let cleared = if game.survived {
+        faced
+    } else {
+        faced.saturating_sub(1)
+    };
+    let outcome = if game.survived { "survived" } else { "died" };
+    let wielding = game
+        .weapon_name()
+        .map(|n| format!("Wielding: {n}\n"))
+        .unwrap_or_default();
+    format!(
+        "Scoundrel {} ({outcome})\nRooms cleared: {cleared}\n{wielding}{}",
+        game.final_score(),
+        rows.join("\n")
+    )
+}
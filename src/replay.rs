@@ -0,0 +1,220 @@
+//! Replays and the replay viewer
+//!
+//! A replay is the starting deck plus every action taken, which is enough to rebuild the
+//! whole run through `Game::apply`. The viewer just feeds those actions back one at a time
+//! on a timer. Rules that shuffle mid-run (room reshuffles) can't be replayed this way yet.
+//!
+//! Notes typed while watching are attached to the decision on screen and saved with a copy
+//! of the replay in storage, which `watch` prefers over the bundled original from then on.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::io;
+use std::time::{Duration, Instant};
+
+use crate::logic::{Action, Card, Game};
+use crate::rules::{Ruleset, SkipOrder};
+use crate::storage::Storage;
+
+const HEADER: &str = "scoundrel-replay 1";
+
+/// Curated example games bundled into the binary, shown by `watch`
+pub const DEMOS: &[&str] = &[
+    include_str!("../assets/demos/win.replay"),
+    include_str!("../assets/demos/close-loss.replay"),
+];
+
+/// Time between steps in the viewer
+const STEP: Duration = Duration::from_millis(900);
+
+#[derive(Clone, Debug)]
+pub struct Replay {
+    pub title: String,
+    pub rules: Ruleset,
+    /// Full deck before the first room is dealt
+    pub deck: VecDeque<Card>,
+    pub actions: Vec<Action>,
+    /// Player notes keyed by 1-based action number, shown once that action has played
+    pub notes: BTreeMap<usize, String>,
+}
+
+impl Replay {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines();
+        if lines.next().map(str::trim) != Some(HEADER) {
+            return Err("not a Scoundrel replay (or from a newer version)".to_string());
+        }
+
+        let mut replay = Replay {
+            title: String::new(),
+            // Replays from before skip-order put skipped rooms back in order
+            rules: Ruleset {
+                skip_order: SkipOrder::InOrder,
+                ..Ruleset::default()
+            },
+            deck: VecDeque::new(),
+            actions: Vec::new(),
+            notes: BTreeMap::new(),
+        };
+        for (key, value) in lines.filter_map(|l| l.split_once('=')) {
+            match key {
+                "title" => replay.title = value.to_string(),
+                "deck" => {
+                    replay.deck = value
+                        .split(',')
+                        .map(|c| Card::from_code(c).ok_or_else(|| format!("bad card '{c}'")))
+                        .collect::<Result<_, _>>()?
+                }
+                "actions" => {
+                    replay.actions = value
+                        .split(',')
+                        .map(|a| Action::from_code(a).ok_or_else(|| format!("bad action '{a}'")))
+                        .collect::<Result<_, _>>()?
+                }
+                _ if key.starts_with("note.") => {
+                    let step = key["note.".len()..]
+                        .parse()
+                        .map_err(|_| format!("bad note '{key}'"))?;
+                    replay.notes.insert(step, value.to_string());
+                }
+                _ => {
+                    let knob = key
+                        .strip_prefix("rule.")
+                        .ok_or_else(|| format!("unknown replay field '{key}'"))?;
+                    replay.rules.set(knob, value)?;
+                }
+            }
+        }
+        Ok(replay)
+    }
+
+    pub fn encode(&self) -> String {
+        let mut lines = vec![HEADER.to_string(), format!("title={}", self.title)];
+        for (key, value) in self.rules.pairs() {
+            lines.push(format!("rule.{key}={value}"));
+        }
+
+        let deck: Vec<String> = self.deck.iter().map(|c| c.code()).collect();
+        lines.push(format!("deck={}", deck.join(",")));
+        let actions: Vec<String> = self.actions.iter().map(|a| a.code()).collect();
+        lines.push(format!("actions={}", actions.join(",")));
+
+        for (step, note) in &self.notes {
+            lines.push(format!("note.{step}={note}"));
+        }
+        lines.join("\n") + "\n"
+    }
+
+    /// A fresh game dealt from the replay's deck, before any action
+    pub fn start(&self) -> Game {
+        let mut game = Game::new(self.rules.clone());
+        game.deal_cards(self.deck.iter().copied().collect());
+        game.start();
+        game
+    }
+}
+
+/// Storage key for the annotated copy of bundled example `n`
+fn demo_key(n: usize) -> String {
+    format!("replays/demo-{}.replay", n + 1)
+}
+
+/// Example game `n`, with the player's notes if they've made any
+pub fn load_demo(storage: &dyn Storage, n: usize) -> Result<Replay, String> {
+    let bundled = DEMOS
+        .get(n)
+        .ok_or_else(|| format!("There are only {} example games.", DEMOS.len()))?;
+    match storage.read(&demo_key(n)) {
+        Ok(Some(text)) => Replay::parse(&text),
+        _ => Replay::parse(bundled),
+    }
+}
+
+/// A replay being watched
+pub struct Playback {
+    pub replay: Replay,
+    /// Which bundled example this is, for saving notes
+    pub demo: usize,
+    /// Actions applied so far
+    applied: usize,
+    next_step: Instant,
+}
+
+impl Playback {
+    /// Start watching `replay` from its first room
+    pub fn new(replay: Replay, demo: usize) -> (Self, Game) {
+        let game = replay.start();
+        let playback = Playback {
+            replay,
+            demo,
+            applied: 0,
+            next_step: Instant::now() + STEP,
+        };
+        (playback, game)
+    }
+
+    pub fn finished(&self) -> bool {
+        self.applied >= self.replay.actions.len()
+    }
+
+    /// 1-based number of the action on screen, `None` before the first one
+    pub fn step(&self) -> Option<usize> {
+        (self.applied > 0).then_some(self.applied)
+    }
+
+    /// Apply the next action if it's due, showing its note if there is one
+    pub fn tick(&mut self, game: &mut Game) {
+        if Instant::now() < self.next_step {
+            return;
+        }
+        if let Some(&action) = self.replay.actions.get(self.applied) {
+            let _ = game.apply(action);
+            game.settle_death();
+            self.applied += 1;
+            self.next_step = Instant::now() + STEP;
+
+            if let Some(note) = self.replay.notes.get(&self.applied) {
+                game.message = format!("{}  ✎ {note}", game.message);
+            }
+        }
+    }
+
+    /// Attach `note` to the action on screen and save the annotated replay
+    pub fn annotate(&mut self, storage: &dyn Storage, note: &str) -> io::Result<usize> {
+        let step = self
+            .step()
+            .ok_or_else(|| io::Error::other("nothing has happened yet"))?;
+        self.replay.notes.insert(step, note.to_string());
+        storage.write(&demo_key(self.demo), &self.replay.encode())?;
+        Ok(step)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::full_deck;
+
+    #[test]
+    fn start_cuts_the_replay_deck_into_floors() {
+        let mut replay = Replay::parse(DEMOS[0]).unwrap();
+        replay.rules.floors = 2;
+        let game = replay.start();
+
+        let mut dealt: Vec<Card> = game
+            .room_slots
+            .iter()
+            .flatten()
+            .chain(&game.deck)
+            .chain(game.floors_ahead.iter().flatten())
+            .copied()
+            .collect();
+        assert_eq!(
+            dealt[..4],
+            replay.deck.iter().copied().collect::<Vec<_>>()[..4]
+        );
+        dealt.sort_by_key(|c| (c.suit, c.value));
+        let mut expected = full_deck();
+        expected.sort_by_key(|c| (c.suit, c.value));
+        assert_eq!(dealt, expected);
+    }
+}
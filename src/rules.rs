@@ -0,0 +1,742 @@
+//! Variant rules
+//!
+//! Every optional rule lives on `Ruleset` and defaults to the standard game. Knobs are
+//! addressable by name through `Ruleset::set`, so the command line (and anything else that
+//! reads `key=value` pairs) shares one parser. House rules can also live in `rules.toml`
+//! beside `config.toml`, one `knob = value` per line.
+
+/// What happens when a weapon fights a monster of exactly its own value
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EqualFight {
+    /// Standard rules: the difference is 0, so the kill is free
+    #[default]
+    FreeKill,
+    /// The kill is free but the weapon shatters
+    WeaponBreaks,
+    /// Take 1 damage instead of 0
+    Chip,
+}
+
+impl EqualFight {
+    pub fn name(self) -> &'static str {
+        match self {
+            EqualFight::FreeKill => "free-kill",
+            EqualFight::WeaponBreaks => "weapon-breaks",
+            EqualFight::Chip => "chip",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "free-kill" => Some(EqualFight::FreeKill),
+            "weapon-breaks" => Some(EqualFight::WeaponBreaks),
+            "chip" => Some(EqualFight::Chip),
+            _ => None,
+        }
+    }
+}
+
+/// How much of the discard pile the player may look at
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DiscardView {
+    /// Standard rules: only the size of the pile; the rest is down to memory
+    #[default]
+    Hidden,
+    /// The most recently discarded card
+    Top,
+    /// The whole pile, in order
+    Full,
+}
+
+impl DiscardView {
+    pub fn name(self) -> &'static str {
+        match self {
+            DiscardView::Hidden => "hidden",
+            DiscardView::Top => "top",
+            DiscardView::Full => "full",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "hidden" => Some(DiscardView::Hidden),
+            "top" => Some(DiscardView::Top),
+            "full" => Some(DiscardView::Full),
+            _ => None,
+        }
+    }
+}
+
+/// Which monsters a weapon may still fight once it has killed one
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Degrade {
+    /// Standard rules: only monsters weaker than the last one it killed
+    #[default]
+    Strict,
+    /// Monsters up to and including the last one's value
+    Equal,
+    /// Weapons never degrade
+    Off,
+}
+
+impl Degrade {
+    pub fn name(self) -> &'static str {
+        match self {
+            Degrade::Strict => "strict",
+            Degrade::Equal => "equal",
+            Degrade::Off => "off",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "strict" => Some(Degrade::Strict),
+            "equal" => Some(Degrade::Equal),
+            "off" => Some(Degrade::Off),
+            _ => None,
+        }
+    }
+}
+
+/// When a room may be skipped
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SkipPolicy {
+    /// Standard rules: any room, but never two in a row. Facing a room lifts the ban.
+    #[default]
+    NoRepeat,
+    /// The ban never lifts: one skip for the whole run
+    Once,
+    Always,
+    Never,
+}
+
+impl SkipPolicy {
+    pub fn name(self) -> &'static str {
+        match self {
+            SkipPolicy::NoRepeat => "no-repeat",
+            SkipPolicy::Once => "once",
+            SkipPolicy::Always => "always",
+            SkipPolicy::Never => "never",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "no-repeat" => Some(SkipPolicy::NoRepeat),
+            "once" => Some(SkipPolicy::Once),
+            "always" => Some(SkipPolicy::Always),
+            "never" => Some(SkipPolicy::Never),
+            _ => None,
+        }
+    }
+
+    /// For the Status panel, e.g. `Skips: no two in a row`
+    pub fn label(self) -> &'static str {
+        match self {
+            SkipPolicy::NoRepeat => "no two in a row",
+            SkipPolicy::Once => "one per run",
+            SkipPolicy::Always => "any room",
+            SkipPolicy::Never => "off",
+        }
+    }
+}
+
+/// How a skipped room goes back under the dungeon
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SkipOrder {
+    /// Shuffled first, so the order can't be memorized
+    #[default]
+    Shuffled,
+    /// In slot order, as this game did before
+    InOrder,
+}
+
+impl SkipOrder {
+    pub fn name(self) -> &'static str {
+        match self {
+            SkipOrder::Shuffled => "shuffled",
+            SkipOrder::InOrder => "in-order",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "shuffled" => Some(SkipOrder::Shuffled),
+            "in-order" => Some(SkipOrder::InOrder),
+            _ => None,
+        }
+    }
+}
+
+pub struct Knob {
+    pub key: &'static str,
+    /// Accepted values, shown in error messages
+    pub values: &'static str,
+    /// One line for the custom game screen
+    pub help: &'static str,
+    /// Stands for the knob in preset codes. Never change or reuse one once released, or
+    /// old codes would mean something else. Letters ran out, so newer knobs get digits.
+    pub code: char,
+}
+
+pub const KNOBS: &[Knob] = &[
+    Knob {
+        key: "equal-fight",
+        values: "free-kill | weapon-breaks | chip",
+        help: "A weapon against a monster of its own value",
+        code: 'e',
+    },
+    Knob {
+        key: "room-reshuffles",
+        values: "0-9",
+        help: "Uses of shuffling a room back into the dungeon",
+        code: 'r',
+    },
+    Knob {
+        key: "comeback",
+        values: "off | 1-20",
+        help: "A hit this big makes the next room deal at most two monsters",
+        code: 'c',
+    },
+    Knob {
+        key: "discard-view",
+        values: "hidden | top | full",
+        help: "How much of the discard pile you may look at",
+        code: 'd',
+    },
+    Knob {
+        key: "max-health",
+        values: "1-99",
+        help: "Health you start with, and the most potions can heal you to",
+        code: 'h',
+    },
+    Knob {
+        key: "room-interactions",
+        values: "1-4",
+        help: "Cards you play from a room before the next one is dealt",
+        code: 'i',
+    },
+    Knob {
+        key: "potion-limit",
+        values: "0-4",
+        help: "Potions that heal per room; any more are wasted",
+        code: 'p',
+    },
+    Knob {
+        key: "room-heal",
+        values: "0-5",
+        help: "HP healed each time you face a room",
+        code: 'a',
+    },
+    Knob {
+        key: "degrade",
+        values: "strict | equal | off",
+        help: "What a weapon may fight after a kill: weaker, up to equal, or anything",
+        code: 'g',
+    },
+    Knob {
+        key: "skips",
+        values: "no-repeat | once | always | never",
+        help: "When a room may be skipped",
+        code: 's',
+    },
+    Knob {
+        key: "skip-order",
+        values: "shuffled | in-order",
+        help: "Whether a skipped room is shuffled before going under the dungeon",
+        code: 'o',
+    },
+    Knob {
+        key: "move-limit",
+        values: "off | 1-999",
+        help: "Clear the dungeon within this many moves (faces, skips, reshuffles, plays)",
+        code: 'm',
+    },
+    Knob {
+        key: "potion-bonus",
+        values: "no | yes",
+        help: "Finish on full health with a potion and score its value on top",
+        code: 'b',
+    },
+    Knob {
+        key: "treasure-hunt",
+        values: "no | yes",
+        help: "Three cards are treasures; play all three to win early",
+        code: 't',
+    },
+    Knob {
+        key: "shop",
+        values: "no | yes",
+        help: "A shop every 4 rooms; weapon overkill and treasures pay gold",
+        code: 'k',
+    },
+    Knob {
+        key: "bosses",
+        values: "off | 2-12",
+        help: "Every this many rooms a boss (16-18) turns up, with a twist on weapons",
+        code: 'x',
+    },
+    Knob {
+        key: "floors",
+        values: "1-4",
+        help: "Split the dungeon into smaller floors, with a rest (+5 HP, weapon repaired) between",
+        code: 'f',
+    },
+    Knob {
+        key: "status-effects",
+        values: "no | yes",
+        help: "Clubs J+ poison you and spades J+ make you bleed: 1 HP per card played after",
+        code: 'v',
+    },
+    Knob {
+        key: "barehanded-bonus",
+        values: "no | yes",
+        help: "+1 score for every monster fought without a weapon",
+        code: 'u',
+    },
+    Knob {
+        key: "no-skip-multiplier",
+        values: "off | 2-5",
+        help: "Surviving without ever skipping a room multiplies the final score by this",
+        code: 'n',
+    },
+    Knob {
+        key: "mulligan",
+        values: "no | yes",
+        help: "Once, before the first face or skip, redeal the opening room from a shuffled dungeon",
+        code: 'l',
+    },
+    Knob {
+        key: "banish",
+        values: "no | yes",
+        help: "Once a run, remove a room card from the game for good",
+        code: 'z',
+    },
+    Knob {
+        key: "dual-wield",
+        values: "no | yes",
+        help: "Hold two weapons, each degrading on its own, and pick one at each fight",
+        code: 'w',
+    },
+    Knob {
+        key: "armor",
+        values: "off | 2-5",
+        help: "Diamonds up to this are armor: worn, they take their value off every hit for a while",
+        code: 'y',
+    },
+    Knob {
+        key: "treasure-score",
+        values: "no | yes",
+        help: "Three cards are treasures, and playing one adds its value to your score",
+        code: 'j',
+    },
+    Knob {
+        key: "traps",
+        values: "no | yes",
+        help: "Four cards are trapped and go off as their room is faced: spikes or a snare",
+        code: 'q',
+    },
+    Knob {
+        key: "merchant",
+        values: "no | yes",
+        help: "Two cards are merchants: play one to spend gold from overkill on heals or skips",
+        code: '1',
+    },
+    Knob {
+        key: "fog",
+        values: "no | yes",
+        help: "One card in every room is dealt face down, and only shows itself when played",
+        code: '2',
+    },
+    Knob {
+        key: "dark",
+        values: "off | 2-10",
+        help: "About one room in this many is dark: suits show, values only when played",
+        code: '3',
+    },
+    Knob {
+        key: "inventory",
+        values: "no | yes",
+        help: "A pack for up to two potions or weapons, stashed now and used in a later room",
+        code: '4',
+    },
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ruleset {
+    pub equal_fight: EqualFight,
+    /// Uses of the one-off "reshuffle this room into the deck" consumable per run
+    pub room_reshuffles: u8,
+    /// Dynamic difficulty: a single hit of at least this much damage makes the next room
+    /// deal at most two monsters. 0 is off.
+    pub comeback: u8,
+    pub discard_view: DiscardView,
+    pub max_health: u8,
+    /// Cards played from a room before the rest carry over into the next
+    pub room_interactions: u8,
+    /// Potions that heal per room
+    pub potion_limit: u8,
+    /// HP healed on facing each room (up to max health)
+    pub room_heal: u8,
+    pub degrade: Degrade,
+    pub skips: SkipPolicy,
+    pub skip_order: SkipOrder,
+    /// Challenge mode: running out of moves ends the run as a loss. 0 is off.
+    pub move_limit: u16,
+    /// The official scoring rule: surviving at full health with a potion as the last card
+    /// played adds its value to the score
+    pub potion_bonus: bool,
+    /// Three cards are marked when the deck is dealt, and playing all three wins the run
+    pub treasure_hunt: bool,
+    /// Gold from weapon overkill and treasures, spent in a shop every few rooms
+    pub shop: bool,
+    /// A boss is dealt into every this many rooms. 0 is off.
+    pub bosses: u8,
+    /// The dungeon is cut into this many floors, with a rest stop between each
+    pub floors: u8,
+    /// Face-card monsters that hit you leave poison or bleeding behind
+    pub status_effects: bool,
+    /// A point of score for each monster fought bare-handed
+    pub barehanded_bonus: bool,
+    /// A win without a single skip has its score multiplied by this. 0 is off.
+    pub no_skip_multiplier: u8,
+    /// One redeal of the opening room before it's faced or skipped
+    pub mulligan: bool,
+    /// One room card may be taken out of the game entirely
+    pub banish: bool,
+    /// A second weapon hand
+    pub dual_wield: bool,
+    /// Diamonds up to this value are worn as armor instead of wielded. 0 is off.
+    pub armor: u8,
+    /// Treasure cards (marked as for `treasure_hunt`) add their value to the score
+    pub treasure_score: bool,
+    /// A few cards are trapped, and spring before anything can be picked from their room
+    pub traps: bool,
+    /// A couple of cards are merchants, opening a small shop when played
+    pub merchant: bool,
+    /// A foggy dungeon: one room card at a time is dealt face down
+    pub fog: bool,
+    /// Dark rooms: about one room in this many deals its cards without their values.
+    /// 0 is off.
+    pub dark: u8,
+    /// Potions and weapons can be stashed in a small pack and used in a later room
+    pub inventory: bool,
+}
+
+impl Default for Ruleset {
+    fn default() -> Self {
+        Self {
+            equal_fight: EqualFight::default(),
+            room_reshuffles: 0,
+            comeback: 0,
+            discard_view: DiscardView::default(),
+            max_health: 20,
+            room_interactions: 3,
+            potion_limit: 1,
+            room_heal: 0,
+            degrade: Degrade::default(),
+            skips: SkipPolicy::default(),
+            skip_order: SkipOrder::default(),
+            move_limit: 0,
+            potion_bonus: false,
+            treasure_hunt: false,
+            shop: false,
+            bosses: 0,
+            floors: 1,
+            status_effects: false,
+            barehanded_bonus: false,
+            no_skip_multiplier: 0,
+            mulligan: false,
+            banish: false,
+            dual_wield: false,
+            armor: 0,
+            treasure_score: false,
+            traps: false,
+            merchant: false,
+            fog: false,
+            dark: 0,
+            inventory: false,
+        }
+    }
+}
+
+impl Ruleset {
+    /// The relaxed game for new players (`casual` on the main menu): two potions a room,
+    /// and a little health back on every room faced
+    pub fn casual(self) -> Self {
+        Self {
+            potion_limit: 2,
+            room_heal: 2,
+            ..self
+        }
+    }
+
+    /// Set a knob by name, e.g. `set("equal-fight", "chip")`
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let knob = KNOBS
+            .iter()
+            .find(|k| k.key == key)
+            .ok_or_else(|| format!("unknown rule '{key}' (known: {})", knob_names()))?;
+        let bad_value = || format!("bad value '{value}' for {key} (expected {})", knob.values);
+
+        match key {
+            "equal-fight" => self.equal_fight = EqualFight::parse(value).ok_or_else(bad_value)?,
+            "room-reshuffles" => {
+                self.room_reshuffles = value
+                    .parse()
+                    .ok()
+                    .filter(|n| *n <= 9)
+                    .ok_or_else(bad_value)?
+            }
+            "comeback" => {
+                self.comeback = match value {
+                    "off" => 0,
+                    _ => value
+                        .parse()
+                        .ok()
+                        .filter(|n| (1..=20).contains(n))
+                        .ok_or_else(bad_value)?,
+                }
+            }
+            "discard-view" => {
+                self.discard_view = DiscardView::parse(value).ok_or_else(bad_value)?
+            }
+            "max-health" => self.max_health = parse_in(value, 1..=99).ok_or_else(bad_value)?,
+            "room-interactions" => {
+                self.room_interactions = parse_in(value, 1..=4).ok_or_else(bad_value)?
+            }
+            "potion-limit" => self.potion_limit = parse_in(value, 0..=4).ok_or_else(bad_value)?,
+            "room-heal" => self.room_heal = parse_in(value, 0..=5).ok_or_else(bad_value)?,
+            "degrade" => self.degrade = Degrade::parse(value).ok_or_else(bad_value)?,
+            "skips" => self.skips = SkipPolicy::parse(value).ok_or_else(bad_value)?,
+            "skip-order" => self.skip_order = SkipOrder::parse(value).ok_or_else(bad_value)?,
+            "move-limit" => {
+                self.move_limit = match value {
+                    "off" => 0,
+                    _ => value
+                        .parse()
+                        .ok()
+                        .filter(|n| (1..=999).contains(n))
+                        .ok_or_else(bad_value)?,
+                }
+            }
+            "potion-bonus" => {
+                self.potion_bonus = match value {
+                    "no" => false,
+                    "yes" => true,
+                    _ => return Err(bad_value()),
+                }
+            }
+            "treasure-hunt" => {
+                self.treasure_hunt = match value {
+                    "no" => false,
+                    "yes" => true,
+                    _ => return Err(bad_value()),
+                }
+            }
+            "shop" => {
+                self.shop = match value {
+                    "no" => false,
+                    "yes" => true,
+                    _ => return Err(bad_value()),
+                }
+            }
+            "bosses" => {
+                self.bosses = match value {
+                    "off" => 0,
+                    _ => parse_in(value, 2..=12).ok_or_else(bad_value)?,
+                }
+            }
+            "floors" => self.floors = parse_in(value, 1..=4).ok_or_else(bad_value)?,
+            "status-effects" => {
+                self.status_effects = match value {
+                    "no" => false,
+                    "yes" => true,
+                    _ => return Err(bad_value()),
+                }
+            }
+            "barehanded-bonus" => {
+                self.barehanded_bonus = match value {
+                    "no" => false,
+                    "yes" => true,
+                    _ => return Err(bad_value()),
+                }
+            }
+            "no-skip-multiplier" => {
+                self.no_skip_multiplier = match value {
+                    "off" => 0,
+                    _ => parse_in(value, 2..=5).ok_or_else(bad_value)?,
+                }
+            }
+            "mulligan" => {
+                self.mulligan = match value {
+                    "no" => false,
+                    "yes" => true,
+                    _ => return Err(bad_value()),
+                }
+            }
+            "banish" => {
+                self.banish = match value {
+                    "no" => false,
+                    "yes" => true,
+                    _ => return Err(bad_value()),
+                }
+            }
+            "dual-wield" => {
+                self.dual_wield = match value {
+                    "no" => false,
+                    "yes" => true,
+                    _ => return Err(bad_value()),
+                }
+            }
+            "treasure-score" => {
+                self.treasure_score = match value {
+                    "no" => false,
+                    "yes" => true,
+                    _ => return Err(bad_value()),
+                }
+            }
+            "dark" => {
+                self.dark = match value {
+                    "off" => 0,
+                    _ => value
+                        .parse()
+                        .ok()
+                        .filter(|n| (2..=10).contains(n))
+                        .ok_or_else(bad_value)?,
+                }
+            }
+            "inventory" => {
+                self.inventory = match value {
+                    "no" => false,
+                    "yes" => true,
+                    _ => return Err(bad_value()),
+                }
+            }
+            "fog" => {
+                self.fog = match value {
+                    "no" => false,
+                    "yes" => true,
+                    _ => return Err(bad_value()),
+                }
+            }
+            "merchant" => {
+                self.merchant = match value {
+                    "no" => false,
+                    "yes" => true,
+                    _ => return Err(bad_value()),
+                }
+            }
+            "traps" => {
+                self.traps = match value {
+                    "no" => false,
+                    "yes" => true,
+                    _ => return Err(bad_value()),
+                }
+            }
+            "armor" => {
+                self.armor = match value {
+                    "off" => 0,
+                    _ => parse_in(value, 2..=5).ok_or_else(bad_value)?,
+                }
+            }
+            _ => unreachable!("knob listed in KNOBS but not handled: {key}"),
+        }
+        Ok(())
+    }
+
+    /// Current value of a knob in the same text form `set` accepts
+    pub fn get(&self, key: &str) -> Option<String> {
+        let value = match key {
+            "equal-fight" => self.equal_fight.name().to_string(),
+            "room-reshuffles" => self.room_reshuffles.to_string(),
+            "comeback" if self.comeback == 0 => "off".to_string(),
+            "comeback" => self.comeback.to_string(),
+            "discard-view" => self.discard_view.name().to_string(),
+            "max-health" => self.max_health.to_string(),
+            "room-interactions" => self.room_interactions.to_string(),
+            "potion-limit" => self.potion_limit.to_string(),
+            "room-heal" => self.room_heal.to_string(),
+            "degrade" => self.degrade.name().to_string(),
+            "skips" => self.skips.name().to_string(),
+            "skip-order" => self.skip_order.name().to_string(),
+            "move-limit" if self.move_limit == 0 => "off".to_string(),
+            "move-limit" => self.move_limit.to_string(),
+            "potion-bonus" if self.potion_bonus => "yes".to_string(),
+            "potion-bonus" => "no".to_string(),
+            "treasure-hunt" if self.treasure_hunt => "yes".to_string(),
+            "treasure-hunt" => "no".to_string(),
+            "shop" if self.shop => "yes".to_string(),
+            "shop" => "no".to_string(),
+            "bosses" if self.bosses == 0 => "off".to_string(),
+            "bosses" => self.bosses.to_string(),
+            "floors" => self.floors.to_string(),
+            "status-effects" if self.status_effects => "yes".to_string(),
+            "status-effects" => "no".to_string(),
+            "barehanded-bonus" if self.barehanded_bonus => "yes".to_string(),
+            "barehanded-bonus" => "no".to_string(),
+            "no-skip-multiplier" if self.no_skip_multiplier == 0 => "off".to_string(),
+            "no-skip-multiplier" => self.no_skip_multiplier.to_string(),
+            "mulligan" if self.mulligan => "yes".to_string(),
+            "mulligan" => "no".to_string(),
+            "banish" if self.banish => "yes".to_string(),
+            "banish" => "no".to_string(),
+            "dual-wield" if self.dual_wield => "yes".to_string(),
+            "dual-wield" => "no".to_string(),
+            "armor" if self.armor == 0 => "off".to_string(),
+            "armor" => self.armor.to_string(),
+            "treasure-score" if self.treasure_score => "yes".to_string(),
+            "treasure-score" => "no".to_string(),
+            "traps" if self.traps => "yes".to_string(),
+            "traps" => "no".to_string(),
+            "merchant" if self.merchant => "yes".to_string(),
+            "merchant" => "no".to_string(),
+            "dark" if self.dark == 0 => "off".to_string(),
+            "dark" => self.dark.to_string(),
+            "fog" if self.fog => "yes".to_string(),
+            "fog" => "no".to_string(),
+            "inventory" if self.inventory => "yes".to_string(),
+            "inventory" => "no".to_string(),
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    /// Every knob as `(key, value)`, in `KNOBS` order
+    pub fn pairs(&self) -> Vec<(&'static str, String)> {
+        KNOBS
+            .iter()
+            .filter_map(|k| self.get(k.key).map(|v| (k.key, v)))
+            .collect()
+    }
+
+    /// Only the knobs that differ from the standard game
+    pub fn differences(&self) -> Vec<(&'static str, String)> {
+        let standard = Ruleset::default().pairs();
+        self.pairs()
+            .into_iter()
+            .filter(|pair| !standard.contains(pair))
+            .collect()
+    }
+
+    /// e.g. `Standard rules` or `equal-fight=chip, comeback=10`
+    pub fn summary(&self) -> String {
+        let changed = self.differences();
+        if changed.is_empty() {
+            return "Standard rules".to_string();
+        }
+        changed
+            .iter()
+            .map(|(k, v)| format!("{k}={v}"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+fn parse_in(value: &str, range: std::ops::RangeInclusive<u8>) -> Option<u8> {
+    value.parse().ok().filter(|n| range.contains(n))
+}
+
+fn knob_names() -> String {
+    KNOBS.iter().map(|k| k.key).collect::<Vec<_>>().join(", ")
+}
@@ -0,0 +1,661 @@
+//! Save files
+//!
+//! A save is a small versioned `key=value` text file holding everything needed to put a
+//! `Game` back exactly where it was: deck order, room slots, weapon chain, HP, skip and
+//! interaction counters, the discard pile, pending prompts and the variant rules it was started with. The
+//! run's timestamped event log comes along too, so a resumed run keeps its history.
+
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::io;
+
+use crate::clock::{self, PlayClock};
+use crate::logic::{
+    Ability, Action, Affliction, Armor, Card, Game, GameState, Guarded, LoggedEvent, Mutator,
+    StatusEffect, Wielded,
+};
+use crate::rules::Ruleset;
+use crate::storage::Storage;
+
+pub const CURRENT_SAVE: &str = "saves/current.sav";
+/// Written automatically when quitting mid-run, offered back on the next launch
+pub const AUTOSAVE: &str = "saves/autosave.sav";
+
+const HEADER: &str = "scoundrel-save 1";
+
+/// A manual `save` of `game` to `CURRENT_SAVE`, which an ironman run won't allow. The
+/// error is ready to show.
+pub fn save_run(storage: &dyn Storage, game: &Game) -> Result<(), String> {
+    if let Some(reason) = game.ironman_forbids(Guarded::Save) {
+        return Err(reason.to_string());
+    }
+    write(storage, CURRENT_SAVE, game).map_err(|e| format!("Couldn't save: {e}"))
+}
+
+/// The save on the way out, kept even for an ironman run so it can be resumed
+pub fn autosave(storage: &dyn Storage, game: &Game) -> io::Result<()> {
+    write(storage, AUTOSAVE, game)
+}
+
+/// `load`: the run in `CURRENT_SAVE`, to go on with in place of `current`, which an
+/// ironman run won't allow. `Ok(None)` when nothing's saved; the error is ready to show.
+pub fn load_over(storage: &dyn Storage, current: &Game) -> Result<Option<Game>, String> {
+    if let Some(reason) = current.ironman_forbids(Guarded::Undo) {
+        return Err(reason.to_string());
+    }
+    read(storage, CURRENT_SAVE).map_err(|e| format!("Couldn't load save: {e}"))
+}
+
+fn write(storage: &dyn Storage, key: &str, game: &Game) -> io::Result<()> {
+    storage.write(key, &encode(game))
+}
+
+/// `Ok(None)` when there's nothing saved under `key`
+pub fn read(storage: &dyn Storage, key: &str) -> Result<Option<Game>, String> {
+    match storage.read(key) {
+        Ok(Some(text)) => decode(&text).map(Some),
+        Ok(None) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+pub fn exists(storage: &dyn Storage, key: &str) -> bool {
+    matches!(storage.read(key), Ok(Some(_)))
+}
+
+pub fn encode(game: &Game) -> String {
+    let mut lines = vec![HEADER.to_string()];
+    let mut put = |k: &str, v: String| lines.push(format!("{k}={v}"));
+
+    for (key, value) in game.rules.pairs() {
+        put(&format!("rule.{key}"), value);
+    }
+
+    put("state", state_name(game.state).to_string());
+    put("health", game.health.to_string());
+    put("max_health", game.max_health.to_string());
+    // The off hand (`dual-wield`) is saved like the main one, with a `2` on its keys
+    for (hand, suffix) in game.loadout.hands.iter().zip(["", "2"]) {
+        if hand.is_none() && !suffix.is_empty() {
+            continue;
+        }
+        put(&format!("weapon{suffix}"), opt_card(hand.map(|w| w.card)));
+        put(
+            &format!("last_slain{suffix}"),
+            hand.and_then(|w| w.last_slain)
+                .map(|v| v.to_string())
+                .unwrap_or_default(),
+        );
+        put(
+            &format!("weapon_kills{suffix}"),
+            hand.map_or(0, |w| w.kills).to_string(),
+        );
+    }
+    if let Some(armor) = game.armor {
+        put("armor", opt_card(Some(armor.card)));
+        put("armor_absorbed", armor.absorbed.to_string());
+    }
+    put("potions_this_room", game.potions_this_room.to_string());
+    put(
+        "last_potion",
+        game.last_played_potion
+            .map(|v| v.to_string())
+            .unwrap_or_default(),
+    );
+    put("can_skip", game.can_skip.to_string());
+    put("reshuffles_left", game.reshuffles_left.to_string());
+    put("mulligan_used", game.mulligan_used.to_string());
+    put("banished", opt_card(game.banished));
+    put("comeback_pending", game.comeback_pending.to_string());
+    put("survived", game.survived.to_string());
+    put(
+        "interactions_left",
+        game.interactions_left_in_room.to_string(),
+    );
+    put("awaiting_weapon", game.awaiting_weapon_choice.to_string());
+    put("current_monster", opt_card(game.current_monster));
+    put(
+        "room",
+        game.room_slots
+            .iter()
+            .map(|c| c.map(Card::code).unwrap_or_else(|| "-".to_string()))
+            .collect::<Vec<_>>()
+            .join(","),
+    );
+    // `fog`: one digit per slot, 1 for face down
+    put(
+        "hidden",
+        game.hidden
+            .iter()
+            .map(|&h| if h { '1' } else { '0' })
+            .collect(),
+    );
+    // `dark`: the same, 1 for a card whose value doesn't show yet
+    put(
+        "dark",
+        game.dark
+            .iter()
+            .map(|&d| if d { '1' } else { '0' })
+            .collect(),
+    );
+    put(
+        "deck",
+        game.deck
+            .iter()
+            .map(|c| c.code())
+            .collect::<Vec<_>>()
+            .join(","),
+    );
+    put(
+        "discard",
+        game.discard
+            .iter()
+            .map(|c| c.code())
+            .collect::<Vec<_>>()
+            .join(","),
+    );
+    put("message", game.message.clone());
+    put("notes", escape_lines(&game.notes));
+    put("endless", game.endless.to_string());
+    put("laps", game.laps.to_string());
+    put("monsters_slain", game.monsters_slain.to_string());
+    put("sandbox", game.sandbox.to_string());
+    put("ironman", game.ironman.to_string());
+    put("ascension", game.ascension.to_string());
+    put("plus", game.plus.to_string());
+    put("relic", opt_card(game.relic));
+    put(
+        "target",
+        game.target.map(|t| t.to_string()).unwrap_or_default(),
+    );
+    put("attempt", game.attempt.to_string());
+    put("floor", game.floor.to_string());
+    put(
+        "floors_ahead",
+        game.floors_ahead
+            .iter()
+            .map(|deck| card_list(deck))
+            .collect::<Vec<_>>()
+            .join("|"),
+    );
+    put("cards_played", game.cards_played.to_string());
+    put("bonus_score", game.bonus_score.to_string());
+    put("treasure_bonus", game.treasure_bonus.to_string());
+    put(
+        "status_effects",
+        game.status_effects
+            .iter()
+            .map(|e| {
+                let left = e.cards_left.map_or("-".to_string(), |n| n.to_string());
+                format!("{}:{left}:{}", e.affliction.code(), e.since)
+            })
+            .collect::<Vec<_>>()
+            .join(","),
+    );
+    put("treasures", card_list(&game.treasures));
+    put("treasures_found", card_list(&game.treasures_found));
+    put("traps", card_list(&game.traps));
+    put("weapon_snared", game.weapon_snared.to_string());
+    put("merchants", card_list(&game.merchants));
+    put("at_merchant", game.at_merchant.to_string());
+    put("inventory", card_list(&game.inventory));
+    put("gold", game.gold.to_string());
+    put("rooms_resolved", game.rooms_resolved.to_string());
+    put("skip_tokens", game.skip_tokens.to_string());
+    put("started_at", game.started_at_ms.to_string());
+    put(
+        "play_ms",
+        game.play_clock.elapsed_ms(clock::now_ms()).to_string(),
+    );
+    put("seed", game.seed.to_string());
+    put(
+        "mutators",
+        game.mutators
+            .iter()
+            .map(|m| m.code())
+            .collect::<Vec<_>>()
+            .join(","),
+    );
+    put(
+        "events",
+        game.events
+            .iter()
+            .map(|e| {
+                format!(
+                    "{}@{}@{}@{}@{}@{}@{}@{}@{}",
+                    e.turn,
+                    e.at_ms,
+                    e.action.code(),
+                    e.health,
+                    opt_card(e.card),
+                    e.room,
+                    opt_card(e.weapon),
+                    e.last_slain.map(|v| v.to_string()).unwrap_or_default(),
+                    e.ability.map(Ability::code).unwrap_or_default()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(","),
+    );
+
+    lines.join("\n") + "\n"
+}
+
+pub fn decode(text: &str) -> Result<Game, String> {
+    let mut lines = text.lines();
+    if lines.next().map(str::trim) != Some(HEADER) {
+        return Err("not a Scoundrel save (or from a newer version)".to_string());
+    }
+
+    let fields: HashMap<&str, &str> = lines.filter_map(|l| l.split_once('=')).collect();
+    let get = |k: &str| {
+        fields
+            .get(k)
+            .copied()
+            .ok_or_else(|| format!("save is missing '{k}'"))
+    };
+    let num = |k: &str| -> Result<i32, String> {
+        get(k)?.parse().map_err(|_| format!("bad number for '{k}'"))
+    };
+    let flag = |k: &str| -> Result<bool, String> {
+        get(k)?.parse().map_err(|_| format!("bad flag for '{k}'"))
+    };
+
+    let mut rules = Ruleset::default();
+    for (key, value) in fields.iter() {
+        if let Some(knob) = key.strip_prefix("rule.") {
+            rules.set(knob, value)?;
+        }
+    }
+
+    // Saves from before seeded runs get a random one
+    let mut game = match fields.get("seed") {
+        Some(v) => Game::with_seed(rules, v.parse().map_err(|_| "bad 'seed'")?),
+        None => Game::new(rules),
+    };
+
+    game.state = parse_state(get("state")?)?;
+    game.health = num("health")?;
+    game.max_health = num("max_health")?;
+    for (hand, suffix) in ["", "2"].into_iter().enumerate() {
+        let Some(card) = parse_opt_card(
+            fields
+                .get(&*format!("weapon{suffix}"))
+                .copied()
+                .unwrap_or_default(),
+        )?
+        else {
+            continue;
+        };
+        let last_slain = match fields.get(&*format!("last_slain{suffix}")).copied() {
+            None | Some("") => None,
+            Some(v) => Some(v.parse().map_err(|_| format!("bad 'last_slain{suffix}'"))?),
+        };
+        // Older saves didn't count kills: a used weapon had at least one
+        let kills = match fields.get(&*format!("weapon_kills{suffix}")) {
+            Some(v) => v
+                .parse()
+                .map_err(|_| format!("bad 'weapon_kills{suffix}'"))?,
+            None => u32::from(last_slain.is_some()),
+        };
+        game.loadout.hands[hand] = Some(Wielded {
+            card,
+            last_slain,
+            kills,
+        });
+    }
+    if let Some(card) = parse_opt_card(fields.get("armor").copied().unwrap_or_default())? {
+        let absorbed = match fields.get("armor_absorbed") {
+            Some(v) => v.parse().map_err(|_| "bad 'armor_absorbed'")?,
+            None => 0,
+        };
+        game.armor = Some(Armor { card, absorbed });
+    }
+    // Older saves only recorded whether a potion had been drunk
+    game.potions_this_room = match fields.get("potions_this_room") {
+        Some(v) => v.parse().map_err(|_| "bad 'potions_this_room'")?,
+        None => u8::from(flag("potion_used")?),
+    };
+    if let Some(v) = fields.get("last_potion").filter(|v| !v.is_empty()) {
+        game.last_played_potion = Some(v.parse().map_err(|_| "bad 'last_potion'")?);
+    }
+    game.can_skip = flag("can_skip")?;
+    game.reshuffles_left = num("reshuffles_left")? as u8;
+    game.survived = flag("survived")?;
+    game.interactions_left_in_room = num("interactions_left")? as u8;
+    game.awaiting_weapon_choice = flag("awaiting_weapon")?;
+    game.current_monster = parse_opt_card(get("current_monster")?)?;
+
+    let room: Vec<Option<Card>> = get("room")?
+        .split(',')
+        .map(|c| {
+            if c == "-" {
+                Ok(None)
+            } else {
+                parse_card(c).map(Some)
+            }
+        })
+        .collect::<Result<_, _>>()?;
+    game.room_slots = room
+        .try_into()
+        .map_err(|_| "room must have exactly 4 slots")?;
+    if let Some(v) = fields.get("hidden") {
+        let hidden: Vec<bool> = v.chars().map(|c| c == '1').collect();
+        game.hidden = hidden
+            .try_into()
+            .map_err(|_| "hidden must have exactly 4 slots")?;
+    }
+    if let Some(v) = fields.get("dark") {
+        let dark: Vec<bool> = v.chars().map(|c| c == '1').collect();
+        game.dark = dark
+            .try_into()
+            .map_err(|_| "dark must have exactly 4 slots")?;
+    }
+
+    game.deck = get("deck")?
+        .split(',')
+        .filter(|c| !c.is_empty())
+        .map(parse_card)
+        .collect::<Result<VecDeque<_>, _>>()?;
+
+    if let Some(v) = fields.get("mutators") {
+        game.mutators = v
+            .split(',')
+            .filter(|m| !m.is_empty())
+            .map(|m| Mutator::from_code(m).ok_or_else(|| format!("unknown mutator '{m}'")))
+            .collect::<Result<_, _>>()?;
+    }
+
+    // Older saves predate the comeback rule and the discard pile
+    if let Some(v) = fields.get("comeback_pending") {
+        game.comeback_pending = v.parse().map_err(|_| "bad flag for 'comeback_pending'")?;
+    }
+    if let Some(v) = fields.get("discard") {
+        game.discard = v
+            .split(',')
+            .filter(|c| !c.is_empty())
+            .map(parse_card)
+            .collect::<Result<_, _>>()?;
+    }
+
+    game.message = get("message")?.to_string();
+    game.notes = unescape_lines(fields.get("notes").unwrap_or(&""));
+
+    // Older saves predate endless mode
+    if let Some(v) = fields.get("endless") {
+        game.endless = v.parse().map_err(|_| "bad flag for 'endless'")?;
+    }
+    if let Some(v) = fields.get("laps") {
+        game.laps = v.parse().map_err(|_| "bad 'laps'")?;
+    }
+    if let Some(v) = fields.get("monsters_slain") {
+        game.monsters_slain = v.parse().map_err(|_| "bad 'monsters_slain'")?;
+    }
+    if let Some(v) = fields.get("banished") {
+        game.banished = parse_opt_card(v)?;
+    }
+    if let Some(v) = fields.get("mulligan_used") {
+        game.mulligan_used = v.parse().map_err(|_| "bad flag for 'mulligan_used'")?;
+    }
+    if let Some(v) = fields.get("target").filter(|v| !v.is_empty()) {
+        game.target = Some(v.parse().map_err(|_| "bad 'target'")?);
+    }
+    if let Some(v) = fields.get("ironman") {
+        game.ironman = v.parse().map_err(|_| "bad flag for 'ironman'")?;
+    }
+    if let Some(v) = fields.get("plus") {
+        game.plus = v.parse().map_err(|_| "bad 'plus'")?;
+    }
+    if let Some(v) = fields.get("relic") {
+        game.relic = parse_opt_card(v)?;
+    }
+    if let Some(v) = fields.get("ascension") {
+        game.ascension = v.parse().map_err(|_| "bad 'ascension'")?;
+    }
+    if let Some(v) = fields.get("sandbox") {
+        game.sandbox = v.parse().map_err(|_| "bad flag for 'sandbox'")?;
+    }
+    if let Some(v) = fields.get("cards_played") {
+        game.cards_played = v.parse().map_err(|_| "bad 'cards_played'")?;
+    }
+    if let Some(v) = fields.get("treasure_bonus") {
+        game.treasure_bonus = v.parse().map_err(|_| "bad 'treasure_bonus'")?;
+    }
+    if let Some(v) = fields.get("bonus_score") {
+        game.bonus_score = v.parse().map_err(|_| "bad 'bonus_score'")?;
+    }
+    if let Some(v) = fields.get("status_effects") {
+        game.status_effects = v
+            .split(',')
+            .filter(|e| !e.is_empty())
+            .map(parse_status_effect)
+            .collect::<Result<_, _>>()?;
+    }
+    if let Some(v) = fields.get("floor") {
+        game.floor = v.parse().map_err(|_| "bad 'floor'")?;
+    }
+    if let Some(v) = fields.get("floors_ahead") {
+        game.floors_ahead = v
+            .split('|')
+            .filter(|d| !d.is_empty())
+            .map(parse_card_list)
+            .collect::<Result<_, _>>()?;
+    }
+    if let Some(v) = fields.get("attempt") {
+        game.attempt = v.parse().map_err(|_| "bad 'attempt'")?;
+    }
+    if let Some(v) = fields.get("treasures") {
+        game.treasures = parse_card_list(v)?;
+    }
+    if let Some(v) = fields.get("treasures_found") {
+        game.treasures_found = parse_card_list(v)?;
+    }
+    if let Some(v) = fields.get("traps") {
+        game.traps = parse_card_list(v)?;
+    }
+    if let Some(v) = fields.get("weapon_snared") {
+        game.weapon_snared = v.parse().map_err(|_| "bad flag for 'weapon_snared'")?;
+    }
+    if let Some(v) = fields.get("merchants") {
+        game.merchants = parse_card_list(v)?;
+    }
+    if let Some(v) = fields.get("at_merchant") {
+        game.at_merchant = v.parse().map_err(|_| "bad flag for 'at_merchant'")?;
+    }
+    if let Some(v) = fields.get("inventory") {
+        game.inventory = parse_card_list(v)?;
+    }
+    // Older saves predate the shop
+    if let Some(v) = fields.get("gold") {
+        game.gold = v.parse().map_err(|_| "bad 'gold'")?;
+    }
+    if let Some(v) = fields.get("rooms_resolved") {
+        game.rooms_resolved = v.parse().map_err(|_| "bad 'rooms_resolved'")?;
+    }
+    if let Some(v) = fields.get("skip_tokens") {
+        game.skip_tokens = v.parse().map_err(|_| "bad 'skip_tokens'")?;
+    }
+
+    // Saves from before the event log have neither field
+    if let Some(v) = fields.get("started_at") {
+        game.started_at_ms = v.parse().map_err(|_| "bad 'started_at'")?;
+    }
+    if let Some(v) = fields.get("play_ms") {
+        game.play_clock = PlayClock::paused_at(v.parse().map_err(|_| "bad 'play_ms'")?);
+    }
+    if let Some(v) = fields.get("events") {
+        game.events = v
+            .split(',')
+            .filter(|e| !e.is_empty())
+            .map(parse_event)
+            .collect::<Result<_, _>>()?;
+    }
+    Ok(game)
+}
+
+/// Fit multi-line text on one `key=value` line: `\` becomes `\\` and a line break `\n`
+fn escape_lines(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape_lines(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => {
+                out.push('\n');
+                chars.next();
+            }
+            ('\\', Some('\\')) => {
+                out.push('\\');
+                chars.next();
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn opt_card(card: Option<Card>) -> String {
+    card.map(Card::code).unwrap_or_default()
+}
+
+pub fn parse_card(code: &str) -> Result<Card, String> {
+    Card::from_code(code).ok_or_else(|| format!("bad card '{code}'"))
+}
+
+fn parse_opt_card(code: &str) -> Result<Option<Card>, String> {
+    if code.is_empty() {
+        Ok(None)
+    } else {
+        parse_card(code).map(Some)
+    }
+}
+
+fn card_list(cards: &[Card]) -> String {
+    cards.iter().map(|c| c.code()).collect::<Vec<_>>().join(",")
+}
+
+fn parse_card_list(s: &str) -> Result<Vec<Card>, String> {
+    s.split(',')
+        .filter(|c| !c.is_empty())
+        .map(parse_card)
+        .collect()
+}
+
+/// `poison:2:7`: affliction, cards left (`-` until cured), and when it took hold
+fn parse_status_effect(s: &str) -> Result<StatusEffect, String> {
+    let bad = || format!("bad status effect '{s}'");
+    let mut parts = s.split(':');
+    let (Some(code), Some(left), Some(since), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(bad());
+    };
+    Ok(StatusEffect {
+        affliction: Affliction::from_code(code).ok_or_else(bad)?,
+        cards_left: match left {
+            "-" => None,
+            n => Some(n.parse().map_err(|_| bad())?),
+        },
+        since: since.parse().map_err(|_| bad())?,
+    })
+}
+
+/// `turn@at_ms@action@health@card@room@weapon@last_slain`. Older saves stop after the
+/// action (their HP shows as 0) or after the card (room 0, no weapon).
+fn parse_event(s: &str) -> Result<LoggedEvent, String> {
+    let bad = || format!("bad event '{s}'");
+    let parts: Vec<&str> = s.split('@').collect();
+    if !(3..=9).contains(&parts.len()) {
+        return Err(bad());
+    }
+    let field = |i: usize| parts.get(i).copied().unwrap_or_default();
+    let number_or_zero = |i: usize| match field(i) {
+        "" => Ok(0),
+        v => v.parse().map_err(|_| bad()),
+    };
+
+    Ok(LoggedEvent {
+        turn: field(0).parse().map_err(|_| bad())?,
+        at_ms: field(1).parse().map_err(|_| bad())?,
+        action: Action::from_code(field(2)).ok_or_else(bad)?,
+        health: number_or_zero(3)?,
+        card: parse_opt_card(field(4))?,
+        room: number_or_zero(5)?.try_into().map_err(|_| bad())?,
+        weapon: parse_opt_card(field(6))?,
+        last_slain: match field(7) {
+            "" => None,
+            v => Some(v.parse().map_err(|_| bad())?),
+        },
+        ability: match field(8) {
+            "" => None,
+            v => Some(Ability::from_code(v).ok_or_else(bad)?),
+        },
+    })
+}
+
+pub fn state_name(state: GameState) -> &'static str {
+    match state {
+        GameState::MainMenu => "main-menu",
+        GameState::ResumePrompt => "resume-prompt",
+        GameState::RoomChoice => "room-choice",
+        GameState::CardSelection => "card-selection",
+        GameState::CardInteraction => "card-interaction",
+        GameState::Shop => "shop",
+        GameState::Trap => "trap",
+        GameState::GameOver => "game-over",
+    }
+}
+
+pub fn parse_state(s: &str) -> Result<GameState, String> {
+    match s {
+        "main-menu" => Ok(GameState::MainMenu),
+        "resume-prompt" => Ok(GameState::ResumePrompt),
+        "room-choice" => Ok(GameState::RoomChoice),
+        "card-selection" => Ok(GameState::CardSelection),
+        "card-interaction" => Ok(GameState::CardInteraction),
+        "shop" => Ok(GameState::Shop),
+        "trap" => Ok(GameState::Trap),
+        "game-over" => Ok(GameState::GameOver),
+        _ => Err(format!("bad state '{s}'")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages as msg;
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn ironman_run_refuses_save_and_load_but_autosaves() {
+        let storage = MemoryStorage::new();
+        let mut game = Game::with_seed(Ruleset::default(), 7);
+        game.start();
+        save_run(&storage, &game).unwrap();
+
+        game.make_ironman().unwrap();
+        assert_eq!(
+            save_run(&storage, &game),
+            Err(msg::IRONMAN_SAVE.to_string())
+        );
+        assert_eq!(
+            load_over(&storage, &game).err(),
+            Some(msg::IRONMAN_UNDO.to_string())
+        );
+        autosave(&storage, &game).unwrap();
+        assert!(read(&storage, AUTOSAVE).unwrap().unwrap().ironman);
+    }
+
+    #[test]
+    fn notes_keep_their_line_breaks() {
+        let mut game = Game::with_seed(Ruleset::default(), 3);
+        game.start();
+        game.notes = "aces high\nC:\\n is a path, not a break\n\n\\".to_string();
+        let text = encode(&game);
+        assert!(text.lines().any(|l| l.starts_with("notes=aces high\\n")));
+        assert_eq!(decode(&text).unwrap().notes, game.notes);
+    }
+}
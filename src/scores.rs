@@ -0,0 +1,276 @@
+//! Local high score table
+//!
+//! Every finished run is appended to one small text file in storage, one run per line.
+//! Nothing is ever pruned; "top 10" is decided when the table is shown, after a `Query`
+//! has picked out the runs asked for.
+
+use std::io;
+
+use crate::clock;
+use crate::locale;
+use crate::logic::Game;
+use crate::rules::Ruleset;
+use crate::storage::Storage;
+use crate::weekly::Weekly;
+
+pub const SCORES: &str = "scores.txt";
+/// Rows shown on the GameOver screen and by `scores`
+pub const TOP_N: usize = 10;
+
+const HEADER: &str = "scoundrel-scores 2";
+/// Before variants and tags; still read
+const HEADER_V1: &str = "scoundrel-scores 1";
+
+/// What kind of run a score came from, as `scores` filters by it
+pub const VARIANTS: &[&str] = &[
+    "standard",
+    "casual",
+    "endless",
+    "weekly",
+    "marathon",
+    "ascension",
+    "mutators",
+    "plus",
+    "custom",
+];
+
+/// Longest tag allowed
+pub const TAG_MAX: usize = 16;
+
+const DAY_MS: u64 = 86_400_000;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScoreEntry {
+    pub score: i32,
+    pub survived: bool,
+    /// When the run ended (`clock::now_ms`)
+    pub at_ms: u64,
+    /// One of `VARIANTS`. Runs from before variants were kept count as `standard`.
+    pub variant: String,
+    /// Set by the player after the run, e.g. `experiment`
+    pub tag: Option<String>,
+    /// Played without saves, loads or retries
+    pub ironman: bool,
+}
+
+/// The `VARIANTS` entry a finished run goes under
+pub fn variant(game: &Game, marathon: bool) -> &'static str {
+    // A weekly challenge that hasn't started yet is this week's
+    let started = match game.started_at_ms {
+        0 => clock::now_ms(),
+        at => at,
+    };
+    if marathon {
+        "marathon"
+    } else if !game.mutators.is_empty() && Weekly::for_time(started).seed == game.seed {
+        "weekly"
+    } else if !game.mutators.is_empty() {
+        "mutators"
+    } else if game.endless {
+        "endless"
+    } else if game.plus > 0 {
+        "plus"
+    } else if game.ascension > 0 {
+        "ascension"
+    } else if game.rules == Ruleset::default() {
+        "standard"
+    } else if game.rules == Ruleset::default().casual() {
+        "casual"
+    } else {
+        "custom"
+    }
+}
+
+/// A word a run can be tagged with: lowercase letters, digits and dashes, and not a word
+/// `Query::parse` already gives a meaning
+pub fn valid_tag(tag: &str) -> bool {
+    !tag.is_empty()
+        && tag.len() <= TAG_MAX
+        && tag
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        && !VARIANTS.contains(&tag)
+        && !matches!(tag, "all" | "since" | "until" | "ironman")
+}
+
+/// Every recorded run, oldest first. A missing file is an empty table.
+pub fn load(storage: &dyn Storage) -> Result<Vec<ScoreEntry>, String> {
+    let text = match storage.read(SCORES) {
+        Ok(Some(text)) => text,
+        Ok(None) => return Ok(Vec::new()),
+        Err(e) => return Err(e.to_string()),
+    };
+
+    let mut lines = text.lines();
+    if !matches!(lines.next().map(str::trim), Some(HEADER | HEADER_V1)) {
+        return Err("not a Scoundrel score file (or from a newer version)".to_string());
+    }
+    lines
+        .filter(|l| !l.trim().is_empty())
+        .map(parse_line)
+        .collect()
+}
+
+/// Write the whole table back with `entry` appended
+pub fn record(storage: &dyn Storage, entries: &[ScoreEntry], entry: &ScoreEntry) -> io::Result<()> {
+    write(storage, entries.iter().chain([entry]))
+}
+
+/// Write the whole table back as it is, e.g. after tagging a run
+pub fn rewrite(storage: &dyn Storage, entries: &[ScoreEntry]) -> io::Result<()> {
+    write(storage, entries.iter())
+}
+
+fn write<'a>(
+    storage: &dyn Storage,
+    entries: impl Iterator<Item = &'a ScoreEntry>,
+) -> io::Result<()> {
+    let mut text = format!("{HEADER}\n");
+    for e in entries {
+        text.push_str(&format!(
+            "{} {} {} {} {}{}\n",
+            e.score,
+            e.survived,
+            e.at_ms,
+            e.variant,
+            e.tag.as_deref().unwrap_or("-"),
+            if e.ironman { " ironman" } else { "" }
+        ));
+    }
+    storage.write(SCORES, &text)
+}
+
+/// Best `n` runs, highest score first (earlier runs win ties)
+pub fn top(entries: &[ScoreEntry], n: usize) -> Vec<ScoreEntry> {
+    let mut sorted = entries.to_vec();
+    sorted.sort_by(|a, b| b.score.cmp(&a.score).then(a.at_ms.cmp(&b.at_ms)));
+    sorted.truncate(n);
+    sorted
+}
+
+/// `score survived at_ms [variant tag [ironman]]`
+fn parse_line(line: &str) -> Result<ScoreEntry, String> {
+    let bad = || format!("bad score line '{line}'");
+    let mut parts = line.split_whitespace();
+    let (Some(score), Some(survived), Some(at_ms), variant, tag, ironman, None) = (
+        parts.next(),
+        parts.next(),
+        parts.next(),
+        parts.next(),
+        parts.next(),
+        parts.next(),
+        parts.next(),
+    ) else {
+        return Err(bad());
+    };
+    if ironman.is_some_and(|w| w != "ironman") {
+        return Err(bad());
+    }
+
+    Ok(ScoreEntry {
+        score: score.parse().map_err(|_| bad())?,
+        survived: survived.parse().map_err(|_| bad())?,
+        at_ms: at_ms.parse().map_err(|_| bad())?,
+        variant: variant.unwrap_or("standard").to_string(),
+        tag: tag.filter(|t| *t != "-").map(str::to_string),
+        ironman: ironman.is_some(),
+    })
+}
+
+// ==============================
+// Queries
+// ==============================
+
+/// Which runs the table and its totals cover. Empty takes them all.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Query {
+    pub tag: Option<String>,
+    pub variant: Option<String>,
+    /// From the start of this day (`clock::now_ms`)
+    pub since: Option<u64>,
+    /// Up to the end of this day
+    pub until: Option<u64>,
+    /// Only ironman runs
+    pub ironman: bool,
+}
+
+impl Query {
+    /// Words after `scores`, in any order: a variant (`casual`), `since 2026-10-01`,
+    /// `until 2026-10-16`, `ironman`, or a tag. `all` alone is the empty query.
+    pub fn parse(words: &[&str]) -> Option<Query> {
+        let mut query = Query::default();
+        let mut words = words.iter();
+        while let Some(&word) = words.next() {
+            match word {
+                "all" => {}
+                "ironman" => query.ironman = true,
+                "since" => query.since = Some(clock::parse_date(words.next()?)?),
+                "until" => query.until = Some(clock::parse_date(words.next()?)? + DAY_MS - 1),
+                w if VARIANTS.contains(&w) => query.variant = Some(w.to_string()),
+                w if valid_tag(w) => query.tag = Some(w.to_string()),
+                _ => return None,
+            }
+        }
+        Some(query)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Query::default()
+    }
+
+    pub fn matches(&self, entry: &ScoreEntry) -> bool {
+        self.tag
+            .as_ref()
+            .is_none_or(|t| entry.tag.as_ref() == Some(t))
+            && self.variant.as_ref().is_none_or(|v| entry.variant == *v)
+            && self.since.is_none_or(|t| entry.at_ms >= t)
+            && self.until.is_none_or(|t| entry.at_ms <= t)
+            && (!self.ironman || entry.ironman)
+    }
+
+    /// e.g. `serious · casual · since 2026-10-01`
+    pub fn describe(&self) -> String {
+        let mut parts: Vec<String> = Vec::new();
+        parts.extend(self.tag.clone());
+        parts.extend(self.variant.clone());
+        if self.ironman {
+            parts.push("ironman".to_string());
+        }
+        if let Some(t) = self.since {
+            parts.push(format!("since {}", locale::date(t)));
+        }
+        if let Some(t) = self.until {
+            parts.push(format!("until {}", locale::date(t)));
+        }
+        parts.join(" · ")
+    }
+}
+
+/// The runs `query` picks out, oldest first
+pub fn select(entries: &[ScoreEntry], query: &Query) -> Vec<ScoreEntry> {
+    entries
+        .iter()
+        .filter(|e| query.matches(e))
+        .cloned()
+        .collect()
+}
+
+/// Totals for a set of runs, e.g. `12 runs, 5 survived (41%), best 20, average -14`
+pub fn totals(entries: &[ScoreEntry]) -> String {
+    if entries.is_empty() {
+        return "No runs match.".to_string();
+    }
+    let runs = entries.len();
+    let survived = entries.iter().filter(|e| e.survived).count();
+    let best = entries.iter().map(|e| e.score).max().unwrap_or(0);
+    let total: i64 = entries.iter().map(|e| i64::from(e.score)).sum();
+    let s = if runs == 1 { "" } else { "s" };
+    format!(
+        "{} run{s}, {} survived ({}), best {}, average {}",
+        locale::int(runs as i64),
+        locale::int(survived as i64),
+        locale::percent((survived * 100 / runs) as u64),
+        locale::int(best.into()),
+        locale::int(total / runs as i64)
+    )
+}
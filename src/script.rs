@@ -0,0 +1,323 @@
+//! Input scripts
+//!
+//! Plays a scripted session against a fake terminal so a rendering bug seen at one window
+//! size can be reproduced exactly: same dungeon, same keys, same resizes, same frames. Run
+//! one with `scoundrel --script FILE`; every `frame` prints the screen as plain text.
+//!
+//! ```text
+//! scoundrel-script 1
+//! seed 42
+//! size 80x24
+//! type start
+//! enter
+//! frame
+//! resize 52x18
+//! frame
+//! ```
+//!
+//! `seed` and `size` set things up, so they come before anything else. After that:
+//! `type TEXT`, `paste TEXT` (`\n` for a line break), `enter`, `esc`, `backspace`, `tab`,
+//! `up`/`down`/`left`/`right`, `click X Y`, `move X Y`, `resize WxH` and `frame`. `#`
+//! starts a comment.
+//!
+//! Nothing is saved (storage is in-memory) and colors are dropped. Each char takes one
+//! cell, so wide glyphs line up a little differently than in a real terminal. Tooltips and
+//! the stream timer still run on the wall clock, and so does the click latch: card clicks
+//! closer together than `LATCH_MS` count as one.
+
+use minui::{ColorPair, Event, KeyKind, KeyModifiers, KeyWithModifiers, MouseButton, Window};
+
+use crate::config::Config;
+use crate::storage::MemoryStorage;
+use crate::ui::{self, AppState};
+
+const HEADER: &str = "scoundrel-script 1";
+
+/// Until a script says otherwise
+const DEFAULT_SIZE: (u16, u16) = (80, 24);
+
+#[derive(Clone, Debug, PartialEq)]
+enum Step {
+    /// Events passed to `ui::update` as they are
+    Send(Vec<Event>),
+    Resize(u16, u16),
+    Frame,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Script {
+    seed: Option<u64>,
+    size: (u16, u16),
+    steps: Vec<Step>,
+}
+
+impl Script {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines().enumerate();
+        if lines.next().map(|(_, l)| l.trim()) != Some(HEADER) {
+            return Err("not a Scoundrel input script (or from a newer version)".to_string());
+        }
+
+        let mut script = Script {
+            seed: None,
+            size: DEFAULT_SIZE,
+            steps: Vec::new(),
+        };
+        for (i, line) in lines {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (word, rest) = line.split_once(' ').unwrap_or((line, ""));
+            let rest = rest.trim();
+            let at = |e: String| format!("line {}: {e}", i + 1);
+
+            let step = match word {
+                "seed" | "size" if !script.steps.is_empty() => {
+                    return Err(at(format!("'{word}' has to come before the first event")));
+                }
+                "seed" => {
+                    script.seed = Some(rest.parse().map_err(|_| at(format!("bad seed '{rest}'")))?);
+                    continue;
+                }
+                "size" => {
+                    script.size = parse_size(rest).map_err(at)?;
+                    continue;
+                }
+                "type" => Step::Send(rest.chars().map(|c| key(KeyKind::Char(c))).collect()),
+                "paste" => Step::Send(vec![Event::Paste(rest.replace("\\n", "\n"))]),
+                "enter" => Step::Send(vec![key(KeyKind::Enter)]),
+                "esc" => Step::Send(vec![key(KeyKind::Escape)]),
+                "backspace" => Step::Send(vec![key(KeyKind::Backspace)]),
+                "tab" => Step::Send(vec![key(KeyKind::Tab)]),
+                "up" => Step::Send(vec![key(KeyKind::Up)]),
+                "down" => Step::Send(vec![key(KeyKind::Down)]),
+                "left" => Step::Send(vec![key(KeyKind::Left)]),
+                "right" => Step::Send(vec![key(KeyKind::Right)]),
+                "click" => {
+                    let (x, y) = parse_point(rest).map_err(at)?;
+                    Step::Send(vec![
+                        Event::MouseMove { x, y },
+                        Event::MouseClick {
+                            x,
+                            y,
+                            button: MouseButton::Left,
+                        },
+                        Event::MouseRelease {
+                            x,
+                            y,
+                            button: MouseButton::Left,
+                        },
+                    ])
+                }
+                "drag" => {
+                    let words: Vec<&str> = rest.split_whitespace().collect();
+                    let (from, to) = match words.as_slice() {
+                        [x1, y1, x2, y2] => (
+                            parse_point(&format!("{x1} {y1}")),
+                            parse_point(&format!("{x2} {y2}")),
+                        ),
+                        _ => (
+                            Err(format!("bad drag '{rest}' (expected X1 Y1 X2 Y2)")),
+                            Ok((0, 0)),
+                        ),
+                    };
+                    let ((x1, y1), (x2, y2)) = (from.map_err(at)?, to.map_err(at)?);
+                    let button = MouseButton::Left;
+                    Step::Send(vec![
+                        Event::MouseMove { x: x1, y: y1 },
+                        Event::MouseClick {
+                            x: x1,
+                            y: y1,
+                            button,
+                        },
+                        Event::MouseDrag {
+                            x: x2,
+                            y: y2,
+                            button,
+                        },
+                        Event::MouseRelease {
+                            x: x2,
+                            y: y2,
+                            button,
+                        },
+                    ])
+                }
+                "move" => {
+                    let (x, y) = parse_point(rest).map_err(at)?;
+                    Step::Send(vec![Event::MouseMove { x, y }])
+                }
+                "resize" => {
+                    let (w, h) = parse_size(rest).map_err(at)?;
+                    Step::Resize(w, h)
+                }
+                "frame" => Step::Frame,
+                other => return Err(at(format!("unknown step '{other}'"))),
+            };
+            script.steps.push(step);
+        }
+        Ok(script)
+    }
+
+    /// Play the script and return every frame, each headed by its number and size
+    pub fn run(&self, mut config: Config) -> Result<String, String> {
+        if self.seed.is_some() {
+            config.seed = self.seed;
+        }
+        let mut state = AppState::new(Box::new(MemoryStorage::new()), config);
+        let mut window = HeadlessWindow::new(self.size);
+        let mut out = String::new();
+        let mut frames = 0;
+
+        for step in &self.steps {
+            match step {
+                Step::Send(events) => {
+                    for event in events {
+                        ui::update(&mut state, event.clone());
+                    }
+                }
+                Step::Resize(width, height) => {
+                    window = HeadlessWindow::new((*width, *height));
+                    ui::update(
+                        &mut state,
+                        Event::Resize {
+                            width: *width,
+                            height: *height,
+                        },
+                    );
+                }
+                Step::Frame => {
+                    // Same order as the real loop: tick, then redraw from scratch
+                    ui::update(&mut state, Event::Frame);
+                    window.clear_screen().map_err(|e| e.to_string())?;
+                    ui::draw(&mut state, &mut window).map_err(|e| e.to_string())?;
+                    frames += 1;
+                    let (w, h) = window.get_size();
+                    out.push_str(&format!("--- frame {frames} ({w}x{h}) ---\n"));
+                    out.push_str(&window.text());
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Keys arrive the way the terminal backend sends them, with (no) modifiers attached
+fn key(key: KeyKind) -> Event {
+    Event::KeyWithModifiers(KeyWithModifiers {
+        key,
+        mods: KeyModifiers::none(),
+    })
+}
+
+fn parse_size(s: &str) -> Result<(u16, u16), String> {
+    let bad = || format!("bad size '{s}' (expected WxH, e.g. 80x24)");
+    let (w, h) = s.split_once('x').ok_or_else(bad)?;
+    match (w.trim().parse(), h.trim().parse()) {
+        (Ok(w), Ok(h)) if w > 0 && h > 0 => Ok((w, h)),
+        _ => Err(bad()),
+    }
+}
+
+fn parse_point(s: &str) -> Result<(u16, u16), String> {
+    let bad = || format!("bad position '{s}' (expected X Y)");
+    let mut parts = s.split_whitespace().map(str::parse);
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(Ok(x)), Some(Ok(y)), None) => Ok((x, y)),
+        _ => Err(bad()),
+    }
+}
+
+// ==============================
+// Fake terminal
+// ==============================
+
+/// A grid of chars standing in for the terminal. Writes past the edge are clipped, as a
+/// real terminal would.
+struct HeadlessWindow {
+    width: u16,
+    height: u16,
+    rows: Vec<Vec<char>>,
+}
+
+impl HeadlessWindow {
+    fn new((width, height): (u16, u16)) -> Self {
+        Self {
+            width,
+            height,
+            rows: vec![vec![' '; width as usize]; height as usize],
+        }
+    }
+
+    /// The screen with trailing spaces trimmed, one line per row
+    fn text(&self) -> String {
+        let mut out = String::new();
+        for row in &self.rows {
+            let line: String = row.iter().collect();
+            out.push_str(line.trim_end());
+            out.push('\n');
+        }
+        out
+    }
+
+    fn blank(&mut self, y1: u16, x1: u16, y2: u16, x2: u16) {
+        for y in y1..=y2.min(self.height.saturating_sub(1)) {
+            for x in x1..=x2.min(self.width.saturating_sub(1)) {
+                self.rows[y as usize][x as usize] = ' ';
+            }
+        }
+    }
+}
+
+impl Window for HeadlessWindow {
+    fn write_str(&mut self, y: u16, x: u16, s: &str) -> minui::Result<()> {
+        let Some(row) = self.rows.get_mut(y as usize) else {
+            return Ok(());
+        };
+        for (cell, c) in row.iter_mut().skip(x as usize).zip(s.chars()) {
+            *cell = c;
+        }
+        Ok(())
+    }
+
+    fn write_str_colored(
+        &mut self,
+        y: u16,
+        x: u16,
+        s: &str,
+        _colors: ColorPair,
+    ) -> minui::Result<()> {
+        self.write_str(y, x, s)
+    }
+
+    fn flush(&mut self) -> minui::Result<()> {
+        Ok(())
+    }
+
+    fn set_cursor_position(&mut self, _x: u16, _y: u16) -> minui::Result<()> {
+        Ok(())
+    }
+
+    fn show_cursor(&mut self, _show: bool) -> minui::Result<()> {
+        Ok(())
+    }
+
+    fn get_size(&self) -> (u16, u16) {
+        (self.width, self.height)
+    }
+
+    fn clear_screen(&mut self) -> minui::Result<()> {
+        self.blank(0, 0, self.height, self.width);
+        Ok(())
+    }
+
+    fn clear_line(&mut self, y: u16) -> minui::Result<()> {
+        self.blank(y, 0, y, self.width);
+        Ok(())
+    }
+
+    fn clear_area(&mut self, y1: u16, x1: u16, y2: u16, x2: u16) -> minui::Result<()> {
+        self.blank(y1, x1, y2, x2);
+        Ok(())
+    }
+}
@@ -0,0 +1,212 @@
+//! Balance simulation
+//!
+//! `test` on the custom game screen plays a thousand games of the rules on screen with a
+//! simple bot, and `simulate` as many as asked of the rules new runs use, on background
+//! threads while play goes on. Either reports how often the bot survived and what it
+//! scored. The bot plays each room out as well as it can but never thinks past it, so
+//! the numbers are for comparing rule sets with each other, not for predicting your own
+//! results. Games are dealt from seeds 0, 1, 2, ... so the same rules always give the
+//! same report.
+
+use std::ops::Range;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+use crate::locale;
+use crate::logic::{Action, Game, GameState, Ware};
+use crate::rules::Ruleset;
+
+pub const GAMES: u32 = 1000;
+/// Games `simulate` plays when not told how many, and the most it will
+pub const BATCH_GAMES: u32 = 10_000;
+pub const BATCH_MAX: u32 = 1_000_000;
+
+/// Far more than any real run takes; stops a rule combination the bot can't finish
+const MAX_ACTIONS: usize = 2000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Report {
+    pub games: u32,
+    pub wins: u32,
+    pub total_score: i64,
+}
+
+impl Report {
+    /// e.g. `bot survived 3.1% of 1000 games, average score -38`
+    pub fn summary(&self) -> String {
+        let games = self.games.max(1);
+        let permille = self.wins * 1000 / games;
+        format!(
+            "bot survived {} of {} games, average score {}",
+            locale::percent_tenths(permille.into()),
+            locale::int(self.games.into()),
+            locale::int(self.total_score / i64::from(games))
+        )
+    }
+}
+
+/// Games being simulated on one background thread per core. Dropping it lets them finish
+/// unheard.
+pub struct Batch {
+    pub games: u32,
+    done: Arc<AtomicU32>,
+    parts: Receiver<Report>,
+    waiting: usize,
+    report: Report,
+}
+
+impl Batch {
+    /// Start simulating `games` games of `rules`, the seeds shared out between threads
+    pub fn start(rules: Ruleset, games: u32) -> Batch {
+        let threads = thread::available_parallelism()
+            .map_or(1, |n| n.get())
+            .min(games.max(1) as usize);
+        let done = Arc::new(AtomicU32::new(0));
+        let (tx, parts) = mpsc::channel();
+        let per_thread = u64::from(games).div_ceil(threads as u64);
+        for i in 0..threads as u64 {
+            let seeds = i * per_thread..((i + 1) * per_thread).min(u64::from(games));
+            let (rules, done, tx) = (rules.clone(), Arc::clone(&done), tx.clone());
+            thread::spawn(move || {
+                // Nobody waiting any more is fine
+                let _ = tx.send(run(&rules, seeds, &done));
+            });
+        }
+        Batch {
+            games,
+            done,
+            parts,
+            waiting: threads,
+            report: Report {
+                games: 0,
+                wins: 0,
+                total_score: 0,
+            },
+        }
+    }
+
+    /// Games finished so far
+    pub fn progress(&self) -> u32 {
+        self.done.load(Ordering::Relaxed)
+    }
+
+    /// The report, once every thread has sent its share
+    pub fn poll(&mut self) -> Option<Report> {
+        while let Ok(part) = self.parts.try_recv() {
+            self.report.games += part.games;
+            self.report.wins += part.wins;
+            self.report.total_score += part.total_score;
+            self.waiting -= 1;
+        }
+        (self.waiting == 0).then_some(self.report)
+    }
+}
+
+fn run(rules: &Ruleset, seeds: Range<u64>, done: &AtomicU32) -> Report {
+    let mut report = Report {
+        games: (seeds.end - seeds.start) as u32,
+        wins: 0,
+        total_score: 0,
+    };
+    for seed in seeds {
+        let mut game = Game::with_seed(rules.clone(), seed);
+        game.start();
+        play_out(&mut game);
+        if game.survived {
+            report.wins += 1;
+        }
+        report.total_score += i64::from(game.final_score());
+        done.fetch_add(1, Ordering::Relaxed);
+    }
+    report
+}
+
+/// Let the bot finish `game` off
+pub fn play_out(game: &mut Game) {
+    for _ in 0..MAX_ACTIONS {
+        if game.state == GameState::GameOver {
+            break;
+        }
+        let a = choose(game);
+        let _ = game.apply(a);
+        game.settle_death();
+    }
+}
+
+/// The bot's move: whatever plays the room out best, looking no further than the room
+pub fn choose(game: &Game) -> Action {
+    match game.state {
+        // Skip a room that would kill or cost over half of what's left, if it may
+        GameState::RoomChoice => {
+            let outcome = best(game, Action::Face);
+            if outcome < game.health.min(game.health / 2 + 1) * 2 && game.skip_allowed() {
+                Action::Skip
+            } else {
+                Action::Face
+            }
+        }
+        GameState::CardSelection => (0..4)
+            .filter(|i| game.room_slots[*i].is_some())
+            .map(Action::Play)
+            .max_by_key(|a| best(game, *a))
+            .unwrap_or(Action::Continue),
+        GameState::CardInteraction if game.awaiting_weapon_choice => game
+            .legal_actions()
+            .into_iter()
+            .max_by_key(|a| best(game, *a))
+            .unwrap_or(Action::Continue),
+        // Patch up first, then forge or sharpen the weapons
+        GameState::Shop => [Ware::Heal, Ware::Forge, Ware::Repair]
+            .into_iter()
+            .find(|w| game.can_buy(*w))
+            .map_or(Action::Leave, Action::Buy),
+        _ => Action::Continue,
+    }
+}
+
+/// Deep enough for a whole room: three cards, each with a weapon choice and a continue
+const LOOKAHEAD: u32 = 10;
+
+/// How well things stand after `action`, playing the rest of the room out as well as
+/// possible
+fn best(game: &Game, action: Action) -> i32 {
+    look(game, action, LOOKAHEAD)
+}
+
+fn look(game: &Game, action: Action, depth: u32) -> i32 {
+    let mut next = game.clone();
+    next.apply(action);
+    next.settle_death();
+    match next.state {
+        GameState::GameOver if next.survived => i32::MAX - 1,
+        GameState::GameOver => i32::MIN + 1,
+        GameState::CardSelection | GameState::CardInteraction if depth > 0 => {
+            let options: Vec<Action> = match next.state {
+                GameState::CardSelection => (0..4)
+                    .filter(|i| next.room_slots[*i].is_some())
+                    .map(Action::Play)
+                    .collect(),
+                _ if next.awaiting_weapon_choice => next.legal_actions(),
+                _ => vec![Action::Continue],
+            };
+            options
+                .into_iter()
+                .map(|a| look(&next, a, depth - 1))
+                .max()
+                .unwrap_or(i32::MIN)
+        }
+        _ => standing(&next),
+    }
+}
+
+/// Health counts double; a weapon counts for the monsters it can still hit
+fn standing(game: &Game) -> i32 {
+    let weapon = match (game.weapon(), game.last_slain()) {
+        (Some(w), Some(last)) => i32::from(w.value.min(last.saturating_sub(1))),
+        (Some(w), None) => i32::from(w.value),
+        _ => 0,
+    };
+    game.health * 2 + weapon
+}
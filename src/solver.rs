@@ -0,0 +1,279 @@
+//! Monte Carlo solver
+//!
+//! `analyze` weighs up every legal move by playing it out many times over. Each rollout
+//! deals the cards the player can't see (the deck, the floors below, face-down and dark
+//! cards in the room) back into the same places in a fresh random order, makes the move,
+//! and lets the balance bot in `sim` finish the run. How often that survives estimates the
+//! move's odds. Only what's still unseen gets shuffled, so a rollout never knows more than
+//! the player does.
+//!
+//! An `Analysis` plays each move out on a background thread of its own, so play goes on
+//! meanwhile, and reports an `Estimate` per move once they're all in. `analyze` does the
+//! same and waits for them, for `--annotate`.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+use rand::RngCore;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+
+use crate::locale;
+use crate::logic::{Action, Card, Game};
+use crate::sim;
+
+/// Rollouts per move `analyze` plays when not told how many, and the most it will
+pub const ROLLOUTS: u32 = 200;
+pub const ROLLOUTS_MAX: u32 = 10_000;
+
+/// How one move did over its rollouts
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Estimate {
+    pub action: Action,
+    pub rollouts: u32,
+    pub survived: u32,
+    pub total_score: i64,
+}
+
+impl Estimate {
+    /// Survival odds in tenths of a percent
+    pub fn permille(&self) -> u32 {
+        (u64::from(self.survived) * 1000 / u64::from(self.rollouts.max(1))) as u32
+    }
+
+    pub fn mean_score(&self) -> i64 {
+        self.total_score / i64::from(self.rollouts.max(1))
+    }
+
+    /// e.g. `34.5%, average score -12`
+    pub fn summary(&self) -> String {
+        format!(
+            "{}, average score {}",
+            locale::percent_tenths(self.permille().into()),
+            locale::int(self.mean_score())
+        )
+    }
+}
+
+/// Every legal move in `game` played out `rollouts` times, a thread each, best first
+pub fn analyze(game: &Game, rollouts: u32) -> Vec<Estimate> {
+    let seed = seed_for(game);
+    let done = &AtomicU32::new(0);
+    let mut estimates: Vec<Estimate> = thread::scope(|scope| {
+        let threads: Vec<_> = game
+            .legal_actions()
+            .into_iter()
+            .map(|action| scope.spawn(move || estimate(game, action, rollouts, seed, done)))
+            .collect();
+        threads.into_iter().filter_map(|t| t.join().ok()).collect()
+    });
+    rank(&mut estimates);
+    estimates
+}
+
+/// Moves being played out on one background thread each. Dropping it lets them finish
+/// unheard.
+pub struct Analysis {
+    pub rollouts: u32,
+    /// The position being analyzed, so a stale answer can be told apart
+    pub seed: u64,
+    pub moves_made: usize,
+    total: u32,
+    done: Arc<AtomicU32>,
+    parts: Receiver<Estimate>,
+    waiting: usize,
+    estimates: Vec<Estimate>,
+}
+
+impl Analysis {
+    /// Start playing every legal move in `game` out `rollouts` times
+    pub fn start(game: &Game, rollouts: u32) -> Analysis {
+        let actions = game.legal_actions();
+        let seed = seed_for(game);
+        let done = Arc::new(AtomicU32::new(0));
+        let (tx, parts) = mpsc::channel();
+        for &action in &actions {
+            let (game, done, tx) = (game.clone(), Arc::clone(&done), tx.clone());
+            thread::spawn(move || {
+                // Nobody waiting any more is fine
+                let _ = tx.send(estimate(&game, action, rollouts, seed, &done));
+            });
+        }
+        Analysis {
+            rollouts,
+            seed: game.seed,
+            moves_made: game.events.len(),
+            total: rollouts * actions.len() as u32,
+            done,
+            parts,
+            waiting: actions.len(),
+            estimates: Vec::new(),
+        }
+    }
+
+    /// How far along it is, in percent
+    pub fn percent(&self) -> u32 {
+        self.done.load(Ordering::Relaxed) * 100 / self.total.max(1)
+    }
+
+    /// Whether `game` is still where it was when this started
+    pub fn is_for(&self, game: &Game) -> bool {
+        game.seed == self.seed && game.events.len() == self.moves_made
+    }
+
+    /// Every move's estimate, best odds first (then best average score), once all of
+    /// them are in
+    pub fn poll(&mut self) -> Option<Vec<Estimate>> {
+        while let Ok(estimate) = self.parts.try_recv() {
+            self.estimates.push(estimate);
+            self.waiting -= 1;
+        }
+        if self.waiting > 0 {
+            return None;
+        }
+        rank(&mut self.estimates);
+        Some(self.estimates.clone())
+    }
+}
+
+/// Best odds first, then best average score
+fn rank(estimates: &mut [Estimate]) {
+    estimates.sort_by_key(|e| std::cmp::Reverse((e.permille(), e.mean_score())));
+}
+
+/// Different for every position in a run, and the same each time it's asked about
+fn seed_for(game: &Game) -> u64 {
+    game.seed
+        .wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        .wrapping_add(game.events.len() as u64)
+}
+
+/// Play `action` out `rollouts` times. The same `seed` gives the same estimate.
+fn estimate(game: &Game, action: Action, rollouts: u32, seed: u64, done: &AtomicU32) -> Estimate {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut estimate = Estimate {
+        action,
+        rollouts,
+        survived: 0,
+        total_score: 0,
+    };
+    for _ in 0..rollouts {
+        let mut rollout = redeal(game, &mut rng);
+        rollout.apply(action);
+        rollout.settle_death();
+        sim::play_out(&mut rollout);
+        if rollout.survived {
+            estimate.survived += 1;
+        }
+        estimate.total_score += i64::from(rollout.final_score());
+        done.fetch_add(1, Ordering::Relaxed);
+    }
+    estimate
+}
+
+/// A copy of `game` with everything the player can't see shuffled among the places it
+/// could be, and a fresh future for the shuffles still to come. A dark card shows its
+/// suit, so it only trades places with cards of that suit.
+fn redeal(game: &Game, rng: &mut StdRng) -> Game {
+    let mut copy = game.clone();
+    let unseen_slots: Vec<usize> = (0..4)
+        .filter(|&i| game.room_slots[i].is_some() && game.visible_card(i).is_none())
+        .collect();
+    let mut unseen: Vec<Card> = unseen_slots
+        .iter()
+        .filter_map(|&i| game.room_slots[i])
+        .chain(game.deck.iter().copied())
+        .chain(game.floors_ahead.iter().flatten().copied())
+        .collect();
+    unseen.shuffle(rng);
+
+    let suit_shown = |i: usize| game.is_dark(i) && !game.is_hidden(i);
+    for &i in unseen_slots.iter().filter(|&&i| suit_shown(i)) {
+        let suit = game.room_slots[i].map(|c| c.suit);
+        if let Some(at) = unseen.iter().position(|c| Some(c.suit) == suit) {
+            copy.room_slots[i] = Some(unseen.remove(at));
+        }
+    }
+    let mut cards = unseen.into_iter();
+    for &i in unseen_slots.iter().filter(|&&i| !suit_shown(i)) {
+        copy.room_slots[i] = cards.next();
+    }
+    let places = copy
+        .deck
+        .iter_mut()
+        .chain(copy.floors_ahead.iter_mut().flatten());
+    for (place, card) in places.zip(cards) {
+        *place = card;
+    }
+    copy.reseed(rng.next_u64());
+    copy
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::GameState;
+    use crate::rules::Ruleset;
+
+    /// Everything `redeal` may shuffle, with the room, sorted
+    fn all_cards(game: &Game) -> Vec<Card> {
+        let mut cards: Vec<Card> = game
+            .room_slots
+            .iter()
+            .flatten()
+            .chain(&game.deck)
+            .chain(game.floors_ahead.iter().flatten())
+            .copied()
+            .collect();
+        cards.sort_by_key(|c| (c.suit, c.value));
+        cards
+    }
+
+    fn faced_room() -> Game {
+        let rules = Ruleset {
+            floors: 2,
+            ..Ruleset::default()
+        };
+        let mut game = Game::with_seed(rules, 8);
+        game.start();
+        game.apply(Action::Face);
+        assert_eq!(game.state, GameState::CardSelection);
+        game
+    }
+
+    #[test]
+    fn redeal_only_moves_what_the_player_cant_see() {
+        let mut game = faced_room();
+        game.dark[1] = true;
+        game.hidden[2] = true;
+        let suit = game.room_slots[1].unwrap().suit;
+
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut moved = [false; 4];
+        for _ in 0..50 {
+            let copy = redeal(&game, &mut rng);
+            assert_eq!(copy.room_slots[0], game.room_slots[0]);
+            assert_eq!(copy.room_slots[3], game.room_slots[3]);
+            assert_eq!(copy.room_slots[1].unwrap().suit, suit);
+            assert_eq!(all_cards(&copy), all_cards(&game));
+            for (moved, (now, was)) in moved
+                .iter_mut()
+                .zip(copy.room_slots.iter().zip(&game.room_slots))
+            {
+                *moved |= now != was;
+            }
+        }
+        assert_eq!(moved, [false, true, true, false]);
+    }
+
+    #[test]
+    fn estimates_repeat_for_the_same_seed() {
+        let game = faced_room();
+        let run = |seed| estimate(&game, Action::Play(0), 10, seed, &AtomicU32::new(0));
+        assert_eq!(run(5), run(5));
+        assert_eq!(analyze(&game, 10), analyze(&game, 10));
+    }
+}
@@ -0,0 +1,69 @@
+//! Speedrun timer
+//!
+//! Times a run on its play clock, from `start` to GameOver, with a split each time a room
+//! is done. Turned on with `--speedrun` (or `speedrun = true`); the best winning time on
+//! each seed is kept in the profile.
+
+use crate::logic::{Game, GameState};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Speedrun {
+    /// `started_at_ms` of the run being timed, so a new run starts the timer over
+    run: u64,
+    /// `rooms_resolved` at the last split
+    rooms: u32,
+    /// Play time when each room was done
+    pub splits: Vec<u64>,
+    /// Final time, once the run is over
+    pub finished: Option<u64>,
+}
+
+impl Speedrun {
+    /// Catch up with `game`: start over for a new run, split on a finished room, stop at
+    /// GameOver. Called every frame.
+    pub fn tick(&mut self, game: &Game, now: u64) {
+        if game.started_at_ms != self.run {
+            *self = Speedrun {
+                run: game.started_at_ms,
+                rooms: game.rooms_resolved,
+                ..Speedrun::default()
+            };
+        }
+        if self.finished.is_some() || game.started_at_ms == 0 {
+            return;
+        }
+
+        let elapsed = game.play_clock.elapsed_ms(now);
+        if game.rooms_resolved > self.rooms {
+            self.rooms = game.rooms_resolved;
+            self.splits.push(elapsed);
+        }
+        if game.state == GameState::GameOver {
+            // The last room never counts as resolved; the run ending is its split
+            if self.splits.last() != Some(&elapsed) {
+                self.splits.push(elapsed);
+            }
+            self.finished = Some(elapsed);
+        }
+    }
+
+    /// Time on the clock
+    pub fn elapsed(&self, game: &Game, now: u64) -> u64 {
+        self.finished
+            .unwrap_or_else(|| game.play_clock.elapsed_ms(now))
+    }
+
+    /// Time spent in the room on the table so far
+    pub fn room_time(&self, game: &Game, now: u64) -> u64 {
+        self.elapsed(game, now) - self.splits.last().copied().unwrap_or(0)
+    }
+
+    /// How long the last finished room took
+    pub fn last_split(&self) -> Option<u64> {
+        match self.splits.as_slice() {
+            [] => None,
+            [only] => Some(*only),
+            [.., before, last] => Some(last - before),
+        }
+    }
+}
@@ -0,0 +1,406 @@
+//! Persistence backends
+//!
+//! Everything the game keeps between launches goes through the `Storage` trait, keyed by
+//! slash-separated relative names like `saves/current.sav`. Game/UI code never touches
+//! `std::fs` directly, so backends can be swapped without touching the game itself:
+//! - `FsStorage`: the default, one file per key under a root directory
+//! - `MemoryStorage`: nothing leaves the process (tests, or a read-only home directory)
+//!
+//! A SQLite backend would slot in as another `Storage` impl behind an optional feature.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+
+pub trait Storage: Send + Sync {
+    /// Returns `Ok(None)` when nothing has been stored under `key` yet
+    fn read(&self, key: &str) -> io::Result<Option<String>>;
+
+    /// Create or replace the value stored under `key`
+    fn write(&self, key: &str, contents: &str) -> io::Result<()>;
+
+    /// Removing a key that doesn't exist is not an error
+    fn remove(&self, key: &str) -> io::Result<()>;
+
+    /// Every key under the folder `dir` (`""` for all of them) with its size in bytes,
+    /// sorted by key
+    fn list(&self, dir: &str) -> io::Result<Vec<(String, u64)>>;
+
+    /// Short human-readable location, e.g. a directory path or `in-memory`
+    fn location(&self) -> String;
+}
+
+/// Open the default backend rooted at `root`.
+///
+/// Falls back to `MemoryStorage` if the directory can't be created, so a read-only home
+/// directory degrades to "nothing is remembered" instead of refusing to start.
+pub fn open(root: &Path) -> Box<dyn Storage> {
+    match FsStorage::new(root) {
+        Ok(fs) => Box::new(fs),
+        Err(_) => Box::new(MemoryStorage::new()),
+    }
+}
+
+// ==============================
+// Filesystem
+// ==============================
+
+pub struct FsStorage {
+    root: PathBuf,
+}
+
+impl FsStorage {
+    pub fn new(root: &Path) -> io::Result<Self> {
+        fs::create_dir_all(root)?;
+        Ok(Self {
+            root: root.to_path_buf(),
+        })
+    }
+
+    fn path_for(&self, key: &str) -> io::Result<PathBuf> {
+        let rel = Path::new(key);
+        // Keys are relative names, never a way to escape the root
+        if key.is_empty() || !rel.components().all(|c| matches!(c, Component::Normal(_))) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid storage key: {key:?}"),
+            ));
+        }
+        Ok(self.root.join(rel))
+    }
+
+    fn walk(&self, dir: &Path, out: &mut Vec<(String, u64)>) -> io::Result<()> {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        for entry in entries {
+            let entry = entry?;
+            let path = entry.path();
+            let meta = entry.metadata()?;
+            if meta.is_dir() {
+                self.walk(&path, out)?;
+            } else if path.extension().is_none_or(|ext| ext != "tmp")
+                && let Ok(rel) = path.strip_prefix(&self.root)
+            {
+                let key: Vec<String> = rel
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                    .collect();
+                out.push((key.join("/"), meta.len()));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Where a write to `path` goes first: `foo.json` goes through `foo.json.tmp`, so it
+/// never shares a temp file with `foo.csv`
+fn temp_path(path: &Path) -> PathBuf {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    tmp.into()
+}
+
+impl Storage for FsStorage {
+    fn read(&self, key: &str) -> io::Result<Option<String>> {
+        match fs::read_to_string(self.path_for(key)?) {
+            Ok(s) => Ok(Some(s)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn write(&self, key: &str, contents: &str) -> io::Result<()> {
+        let path = self.path_for(key)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // Write to a sibling temp file first so a crash mid-write never leaves a torn file
+        let tmp = temp_path(&path);
+        fs::write(&tmp, contents)?;
+        fs::rename(&tmp, &path)
+    }
+
+    fn remove(&self, key: &str) -> io::Result<()> {
+        match fs::remove_file(self.path_for(key)?) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    fn list(&self, dir: &str) -> io::Result<Vec<(String, u64)>> {
+        let base = if dir.is_empty() {
+            self.root.clone()
+        } else {
+            self.path_for(dir)?
+        };
+        let mut out = Vec::new();
+        self.walk(&base, &mut out)?;
+        out.sort();
+        Ok(out)
+    }
+
+    fn location(&self) -> String {
+        self.root.display().to_string()
+    }
+}
+
+// ==============================
+// In-memory
+// ==============================
+
+#[derive(Default)]
+pub struct MemoryStorage {
+    entries: Mutex<BTreeMap<String, String>>,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn read(&self, key: &str) -> io::Result<Option<String>> {
+        Ok(self.entries.lock().unwrap().get(key).cloned())
+    }
+
+    fn write(&self, key: &str, contents: &str) -> io::Result<()> {
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), contents.to_string());
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> io::Result<()> {
+        self.entries.lock().unwrap().remove(key);
+        Ok(())
+    }
+
+    fn list(&self, dir: &str) -> io::Result<Vec<(String, u64)>> {
+        let prefix = format!("{dir}/");
+        Ok(self
+            .entries
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(key, _)| dir.is_empty() || key.starts_with(&prefix))
+            .map(|(key, value)| (key.clone(), value.len() as u64))
+            .collect())
+    }
+
+    fn location(&self) -> String {
+        "in-memory".to_string()
+    }
+}
+
+// ==============================
+// Retention
+// ==============================
+
+/// Folders that gain a file or two with every run. Saves, scores, the profile and the
+/// example replays are a fixed handful of files, so they're never pruned.
+pub const PRUNED: &[&str] = &["exports"];
+
+/// How much of each `PRUNED` folder to keep (`[storage]` in the config). Files go in
+/// groups sharing a name, so a run's JSON and CSV exports are kept or dropped together.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Retention {
+    /// The newest this many groups. 0 is no limit.
+    pub keep: usize,
+    /// At most this many megabytes a folder. 0 is no limit.
+    pub max_mb: u64,
+}
+
+impl Default for Retention {
+    fn default() -> Self {
+        Self {
+            keep: 100,
+            max_mb: 0,
+        }
+    }
+}
+
+/// Delete the oldest files in every `PRUNED` folder until it's within `retention`.
+/// Returns how many files went and how many bytes that freed.
+pub fn prune(storage: &dyn Storage, retention: Retention) -> io::Result<(usize, u64)> {
+    let (mut files, mut freed) = (0, 0);
+    for dir in PRUNED {
+        // Keys are named after when the run started, so sorted is oldest first
+        let mut groups: Vec<(String, Vec<(String, u64)>)> = Vec::new();
+        for (key, size) in storage.list(dir)? {
+            let stem = key.rsplit_once('.').map_or(key.as_str(), |(s, _)| s);
+            match groups.last_mut() {
+                Some((last, members)) if last == stem => members.push((key, size)),
+                _ => groups.push((stem.to_string(), vec![(key, size)])),
+            }
+        }
+
+        let mut total: u64 = groups.iter().flat_map(|(_, m)| m).map(|(_, s)| s).sum();
+        let mut count = groups.len();
+        for (_, members) in &groups {
+            let too_many = retention.keep > 0 && count > retention.keep;
+            let too_big = retention.max_mb > 0 && total > retention.max_mb * 1024 * 1024;
+            if !too_many && !too_big {
+                break;
+            }
+            for (key, size) in members {
+                storage.remove(key)?;
+                files += 1;
+                freed += size;
+                total -= size;
+            }
+            count -= 1;
+        }
+    }
+    Ok((files, freed))
+}
+
+/// Files and bytes in each top-level folder (loose files under `""`)
+pub fn usage(storage: &dyn Storage) -> io::Result<Vec<(String, usize, u64)>> {
+    let mut folders: Vec<(String, usize, u64)> = Vec::new();
+    for (key, size) in storage.list("")? {
+        let dir = key.split_once('/').map_or("", |(d, _)| d);
+        match folders.iter_mut().find(|(d, _, _)| d == dir) {
+            Some((_, files, bytes)) => {
+                *files += 1;
+                *bytes += size;
+            }
+            None => folders.push((dir.to_string(), 1, size)),
+        }
+    }
+    Ok(folders)
+}
+
+/// `812 B`, `34 KB`, `1.2 MB`
+pub fn size_text(bytes: u64) -> String {
+    match bytes {
+        0..1024 => format!("{bytes} B"),
+        1024..1_048_576 => format!("{} KB", bytes / 1024),
+        _ => format!("{:.1} MB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::{Action, Game};
+    use crate::rules::Ruleset;
+    use crate::save;
+    use crate::scores::{self, ScoreEntry};
+
+    fn entry(score: i32) -> ScoreEntry {
+        ScoreEntry {
+            score,
+            survived: score > 0,
+            at_ms: 1_000 + score.unsigned_abs() as u64,
+            variant: "standard".to_string(),
+            tag: None,
+            ironman: false,
+        }
+    }
+
+    #[test]
+    fn save_round_trips_through_memory() {
+        let storage = MemoryStorage::new();
+        let mut game = Game::with_seed(Ruleset::default(), 11);
+        game.start();
+        game.apply(Action::Face);
+        save::save_run(&storage, &game).unwrap();
+
+        let loaded = save::read(&storage, save::CURRENT_SAVE).unwrap().unwrap();
+        assert_eq!(save::encode(&loaded), save::encode(&game));
+        assert!(save::read(&storage, save::AUTOSAVE).unwrap().is_none());
+    }
+
+    #[test]
+    fn scores_append_and_rewrite() {
+        let storage = MemoryStorage::new();
+        assert_eq!(scores::load(&storage), Ok(Vec::new()));
+
+        scores::record(&storage, &[], &entry(12)).unwrap();
+        let entries = scores::load(&storage).unwrap();
+        scores::record(&storage, &entries, &entry(-30)).unwrap();
+        let mut entries = scores::load(&storage).unwrap();
+        assert_eq!(entries, vec![entry(12), entry(-30)]);
+
+        entries[1].tag = Some("experiment".to_string());
+        scores::rewrite(&storage, &entries).unwrap();
+        assert_eq!(scores::load(&storage).unwrap(), entries);
+    }
+
+    #[test]
+    fn prune_drops_oldest_groups_together() {
+        let storage = MemoryStorage::new();
+        for run in 1..=4 {
+            storage
+                .write(&format!("exports/run-{run}.json"), "{}")
+                .unwrap();
+            storage
+                .write(&format!("exports/run-{run}.csv"), "a,b")
+                .unwrap();
+        }
+        storage.write("scores.txt", "kept").unwrap();
+
+        let retention = Retention { keep: 2, max_mb: 0 };
+        assert_eq!(prune(&storage, retention).unwrap(), (4, 10));
+        let left: Vec<String> = storage
+            .list("")
+            .unwrap()
+            .into_iter()
+            .map(|(k, _)| k)
+            .collect();
+        assert_eq!(
+            left,
+            [
+                "exports/run-3.csv",
+                "exports/run-3.json",
+                "exports/run-4.csv",
+                "exports/run-4.json",
+                "scores.txt",
+            ]
+        );
+        assert_eq!(prune(&storage, retention).unwrap(), (0, 0));
+    }
+
+    #[test]
+    fn temp_files_keep_the_real_extension() {
+        assert_eq!(
+            temp_path(Path::new("exports/run.json")),
+            Path::new("exports/run.json.tmp")
+        );
+        assert_ne!(
+            temp_path(Path::new("run.json")),
+            temp_path(Path::new("run.csv"))
+        );
+    }
+
+    #[test]
+    fn fs_writes_leave_no_temp_files_behind() {
+        let root = std::env::temp_dir().join(format!("scoundrel-storage-{}", std::process::id()));
+        let storage = FsStorage::new(&root).unwrap();
+        storage.write("exports/run.json", "{}").unwrap();
+        storage.write("exports/run.csv", "a,b").unwrap();
+
+        assert_eq!(
+            fs::read_dir(root.join("exports")).unwrap().count(),
+            2,
+            "no stray temp files"
+        );
+        assert_eq!(
+            storage.read("exports/run.json").unwrap().as_deref(),
+            Some("{}")
+        );
+        assert_eq!(
+            storage.read("exports/run.csv").unwrap().as_deref(),
+            Some("a,b")
+        );
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
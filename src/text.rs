@@ -0,0 +1,106 @@
+//! Text input
+//!
+//! Everything typed or pasted into the command, notes and screen inputs passes through
+//! here on its way to a `TextInputState`. Terminals hand over text in a few shapes: one
+//! `Character` per key, a whole bracketed `Paste` (run codes, seed phrases, a line copied
+//! from chat with its newline still on), or characters an input method has composed,
+//! sometimes as a letter followed by a separate accent. This smooths those over so the
+//! box holds what the player meant.
+
+use minui::widgets::TextInputState;
+use minui::{Event, KeyKind};
+
+/// Feed `event` to `input` if it's text or editing. Returns true if it was used.
+pub fn feed(input: &mut TextInputState, event: Event) -> bool {
+    match event {
+        Event::Paste(text) => {
+            let text = clean_paste(&text);
+            if !text.is_empty() {
+                input.insert_str(&text);
+            }
+            true
+        }
+        // Ctrl+letter and Alt+letter are shortcuts, not text. Both at once is AltGr on
+        // some keyboards, which is how a lot of accented letters and symbols get typed.
+        Event::KeyWithModifiers(k)
+            if matches!(k.key, KeyKind::Char(_)) && k.mods.ctrl != k.mods.alt =>
+        {
+            false
+        }
+        _ => input.handle_event(event),
+    }
+}
+
+/// A paste as one line: line breaks and tabs become spaces, other control characters
+/// go, and the newline a copied line usually ends with is dropped rather than submitted
+pub fn clean_paste(text: &str) -> String {
+    let line: String = text
+        .chars()
+        .filter_map(|c| match c {
+            '\n' | '\r' | '\t' => Some(' '),
+            c if c.is_control() => None,
+            c => Some(c),
+        })
+        .collect();
+    compose(line.trim())
+}
+
+/// Join a letter and a combining accent after it into the one precomposed character, so
+/// `e` + `´` typed through an input method matches `é` written in config.toml. Covers
+/// the accents of the Latin alphabets; anything else is left as it is.
+pub fn compose(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if let Some(base) = out.pop() {
+            match precomposed(base, c) {
+                Some(joined) => out.push(joined),
+                None => {
+                    out.push(base);
+                    out.push(c);
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn precomposed(base: char, mark: char) -> Option<char> {
+    let lower = base.is_lowercase();
+    let joined = match (base.to_ascii_lowercase(), mark) {
+        ('a', '\u{300}') => 'à',
+        ('e', '\u{300}') => 'è',
+        ('i', '\u{300}') => 'ì',
+        ('o', '\u{300}') => 'ò',
+        ('u', '\u{300}') => 'ù',
+        ('a', '\u{301}') => 'á',
+        ('e', '\u{301}') => 'é',
+        ('i', '\u{301}') => 'í',
+        ('o', '\u{301}') => 'ó',
+        ('u', '\u{301}') => 'ú',
+        ('y', '\u{301}') => 'ý',
+        ('a', '\u{302}') => 'â',
+        ('e', '\u{302}') => 'ê',
+        ('i', '\u{302}') => 'î',
+        ('o', '\u{302}') => 'ô',
+        ('u', '\u{302}') => 'û',
+        ('a', '\u{303}') => 'ã',
+        ('n', '\u{303}') => 'ñ',
+        ('o', '\u{303}') => 'õ',
+        ('a', '\u{308}') => 'ä',
+        ('e', '\u{308}') => 'ë',
+        ('i', '\u{308}') => 'ï',
+        ('o', '\u{308}') => 'ö',
+        ('u', '\u{308}') => 'ü',
+        ('y', '\u{308}') => 'ÿ',
+        ('a', '\u{30a}') => 'å',
+        ('c', '\u{327}') => 'ç',
+        _ => return None,
+    };
+    if lower {
+        Some(joined)
+    } else {
+        joined.to_uppercase().next()
+    }
+}
@@ -0,0 +1,499 @@
+//! Color themes and contrast checks
+//!
+//! `draw` never names a color directly; it asks the active `Theme` for a role.
+//! The contrast helpers follow WCAG 2.x: text roles should reach 4.5:1 (AA) and
+//! borders 3:1 (non-text UI components).
+
+use minui::prelude::*;
+
+use crate::render::Glyphs;
+
+/// Which WCAG threshold applies to a role
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RoleKind {
+    Text,
+    Border,
+}
+
+impl RoleKind {
+    pub fn min_ratio(self) -> f32 {
+        match self {
+            RoleKind::Text => 4.5,
+            RoleKind::Border => 3.0,
+        }
+    }
+}
+
+/// Per-panel fills, only painted when solid backgrounds are turned on
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PanelBackgrounds {
+    pub root: Color,
+    pub status: Color,
+    pub room: Color,
+    pub message: Color,
+    pub command: Color,
+}
+
+/// Line style of a panel's border
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BorderStyle {
+    Single,
+    Double,
+    Rounded,
+    Ascii,
+}
+
+impl BorderStyle {
+    pub const VALUES: &'static str = "single | double | rounded | ascii";
+
+    pub fn parse(s: &str) -> Option<Self> {
+        let style = match s {
+            "single" => BorderStyle::Single,
+            "double" => BorderStyle::Double,
+            "rounded" => BorderStyle::Rounded,
+            "ascii" => BorderStyle::Ascii,
+            _ => return None,
+        };
+        Some(style)
+    }
+
+    /// The characters to draw it with. The ascii glyph set has no box drawing, so there
+    /// every style comes out as ascii.
+    pub fn chars(self, glyphs: &Glyphs) -> BorderChars {
+        match self {
+            BorderStyle::Single => glyphs.single_border,
+            BorderStyle::Double => glyphs.double_border,
+            BorderStyle::Rounded => glyphs.rounded_border,
+            BorderStyle::Ascii => BorderChars::ascii(),
+        }
+    }
+}
+
+/// How one panel is framed: its border and where its title sits on it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Frame {
+    pub border: BorderStyle,
+    pub title: TitleAlignment,
+}
+
+impl Frame {
+    const fn new(border: BorderStyle, title: TitleAlignment) -> Self {
+        Self { border, title }
+    }
+
+    /// `"rounded"`, `"center"` or both, e.g. `"double center"`. Whatever's left out keeps
+    /// its current value.
+    pub fn parse_over(self, s: &str) -> Option<Self> {
+        let mut frame = self;
+        for word in s.split_whitespace() {
+            match (BorderStyle::parse(word), parse_alignment(word)) {
+                (Some(border), _) => frame.border = border,
+                (_, Some(title)) => frame.title = title,
+                _ => return None,
+            }
+        }
+        Some(frame)
+    }
+}
+
+fn parse_alignment(s: &str) -> Option<TitleAlignment> {
+    let title = match s {
+        "left" => TitleAlignment::Left,
+        "center" => TitleAlignment::Center,
+        "right" => TitleAlignment::Right,
+        _ => return None,
+    };
+    Some(title)
+}
+
+/// Per-panel frames. `side` covers the scores, notes and stream panels, `dialog` the quit
+/// confirmation and rule explanations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PanelFrames {
+    pub root: Frame,
+    pub status: Frame,
+    pub room: Frame,
+    pub message: Frame,
+    pub command: Frame,
+    pub side: Frame,
+    pub dialog: Frame,
+}
+
+/// The look the panels always had: a double frame around everything, single ones inside
+const STANDARD_FRAMES: PanelFrames = PanelFrames {
+    root: Frame::new(BorderStyle::Double, TitleAlignment::Center),
+    status: Frame::new(BorderStyle::Single, TitleAlignment::Left),
+    room: Frame::new(BorderStyle::Single, TitleAlignment::Left),
+    message: Frame::new(BorderStyle::Single, TitleAlignment::Left),
+    command: Frame::new(BorderStyle::Single, TitleAlignment::Left),
+    side: Frame::new(BorderStyle::Single, TitleAlignment::Left),
+    dialog: Frame::new(BorderStyle::Double, TitleAlignment::Center),
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Theme {
+    pub name: &'static str,
+
+    pub root_border: ColorPair,
+    pub panel_border: ColorPair,
+    /// The room panel's border in the Upper Halls; the next two take over deeper down
+    pub room_border: ColorPair,
+    pub catacombs_border: ColorPair,
+    pub depths_border: ColorPair,
+    pub command_border: ColorPair,
+
+    /// Regular message text
+    pub text: ColorPair,
+    /// Hints, footers, previous-input echo
+    pub muted: ColorPair,
+    /// Final score and other one-off callouts
+    pub emphasis: ColorPair,
+
+    pub card_red: ColorPair,
+    pub card_black: ColorPair,
+    pub empty_slot: ColorPair,
+
+    pub hp_high: ColorPair,
+    pub hp_mid: ColorPair,
+    pub hp_low: ColorPair,
+
+    pub badge: ColorPair,
+    pub tooltip: ColorPair,
+
+    pub backgrounds: PanelBackgrounds,
+    pub frames: PanelFrames,
+}
+
+const fn fg(c: Color) -> ColorPair {
+    ColorPair::new(c, Color::Transparent)
+}
+
+pub const DEFAULT: Theme = Theme {
+    name: "default",
+
+    root_border: fg(Color::White),
+    panel_border: fg(Color::DarkGray),
+    room_border: fg(Color::LightBlue),
+    catacombs_border: fg(Color::LightMagenta),
+    depths_border: fg(Color::LightRed),
+    command_border: fg(Color::White),
+
+    text: fg(Color::Reset),
+    muted: fg(Color::DarkGray),
+    emphasis: fg(Color::White),
+
+    card_red: fg(Color::LightRed),
+    card_black: fg(Color::White),
+    empty_slot: fg(Color::DarkGray),
+
+    hp_high: fg(Color::Green),
+    hp_mid: fg(Color::Yellow),
+    hp_low: fg(Color::Red),
+
+    badge: fg(Color::LightCyan),
+    tooltip: ColorPair::new(Color::LightGray, Color::DarkGray),
+
+    backgrounds: PanelBackgrounds {
+        root: Color::rgb(16, 16, 20),
+        status: Color::rgb(24, 24, 30),
+        room: Color::rgb(18, 22, 34),
+        message: Color::rgb(24, 24, 30),
+        command: Color::rgb(24, 24, 30),
+    },
+    frames: STANDARD_FRAMES,
+};
+
+/// Everything bright on a forced black background
+pub const HIGH_CONTRAST: Theme = Theme {
+    name: "high-contrast",
+
+    root_border: ColorPair::new(Color::White, Color::Black),
+    panel_border: ColorPair::new(Color::White, Color::Black),
+    room_border: ColorPair::new(Color::Cyan, Color::Black),
+    catacombs_border: ColorPair::new(Color::LightMagenta, Color::Black),
+    depths_border: ColorPair::new(Color::LightRed, Color::Black),
+    command_border: ColorPair::new(Color::Yellow, Color::Black),
+
+    text: ColorPair::new(Color::White, Color::Black),
+    muted: ColorPair::new(Color::LightGray, Color::Black),
+    emphasis: ColorPair::new(Color::Yellow, Color::Black),
+
+    card_red: ColorPair::new(Color::Red, Color::Black),
+    card_black: ColorPair::new(Color::White, Color::Black),
+    empty_slot: ColorPair::new(Color::LightGray, Color::Black),
+
+    hp_high: ColorPair::new(Color::Green, Color::Black),
+    hp_mid: ColorPair::new(Color::Yellow, Color::Black),
+    hp_low: ColorPair::new(Color::Red, Color::Black),
+
+    badge: ColorPair::new(Color::Cyan, Color::Black),
+    tooltip: ColorPair::new(Color::Black, Color::White),
+
+    backgrounds: PanelBackgrounds {
+        root: Color::Black,
+        status: Color::Black,
+        room: Color::Black,
+        message: Color::Black,
+        command: Color::Black,
+    },
+    frames: STANDARD_FRAMES,
+};
+
+pub const BUILTIN: &[Theme] = &[DEFAULT, HIGH_CONTRAST];
+
+pub fn by_name(name: &str) -> Option<&'static Theme> {
+    BUILTIN.iter().find(|t| t.name.eq_ignore_ascii_case(name))
+}
+
+pub fn names() -> String {
+    BUILTIN
+        .iter()
+        .map(|t| t.name)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl Theme {
+    /// A role by its config key (`card_red`, `muted`, ...)
+    pub fn role_mut(&mut self, key: &str) -> Option<&mut ColorPair> {
+        let role = match key {
+            "root_border" => &mut self.root_border,
+            "panel_border" => &mut self.panel_border,
+            "room_border" => &mut self.room_border,
+            "catacombs_border" => &mut self.catacombs_border,
+            "depths_border" => &mut self.depths_border,
+            "command_border" => &mut self.command_border,
+            "text" => &mut self.text,
+            "muted" => &mut self.muted,
+            "emphasis" => &mut self.emphasis,
+            "card_red" => &mut self.card_red,
+            "card_black" => &mut self.card_black,
+            "empty_slot" => &mut self.empty_slot,
+            "hp_high" => &mut self.hp_high,
+            "hp_mid" => &mut self.hp_mid,
+            "hp_low" => &mut self.hp_low,
+            "badge" => &mut self.badge,
+            "tooltip" => &mut self.tooltip,
+            _ => return None,
+        };
+        Some(role)
+    }
+
+    pub fn background_mut(&mut self, panel: &str) -> Option<&mut Color> {
+        let bg = &mut self.backgrounds;
+        let color = match panel {
+            "root" => &mut bg.root,
+            "status" => &mut bg.status,
+            "room" => &mut bg.room,
+            "message" => &mut bg.message,
+            "command" => &mut bg.command,
+            _ => return None,
+        };
+        Some(color)
+    }
+
+    pub fn frame_mut(&mut self, panel: &str) -> Option<&mut Frame> {
+        let frames = &mut self.frames;
+        let frame = match panel {
+            "root" => &mut frames.root,
+            "status" => &mut frames.status,
+            "room" => &mut frames.room,
+            "message" => &mut frames.message,
+            "command" => &mut frames.command,
+            "side" => &mut frames.side,
+            "dialog" => &mut frames.dialog,
+            _ => return None,
+        };
+        Some(frame)
+    }
+
+    /// Every role with its name and threshold, in display order (used by the contrast audit)
+    pub fn roles(&self) -> Vec<(&'static str, ColorPair, RoleKind)> {
+        vec![
+            ("root border", self.root_border, RoleKind::Border),
+            ("panel border", self.panel_border, RoleKind::Border),
+            ("room border", self.room_border, RoleKind::Border),
+            ("catacombs border", self.catacombs_border, RoleKind::Border),
+            ("depths border", self.depths_border, RoleKind::Border),
+            ("command border", self.command_border, RoleKind::Border),
+            ("text", self.text, RoleKind::Text),
+            ("muted", self.muted, RoleKind::Text),
+            ("emphasis", self.emphasis, RoleKind::Text),
+            ("red card", self.card_red, RoleKind::Text),
+            ("black card", self.card_black, RoleKind::Text),
+            ("empty slot", self.empty_slot, RoleKind::Text),
+            ("hp high", self.hp_high, RoleKind::Text),
+            ("hp mid", self.hp_mid, RoleKind::Text),
+            ("hp low", self.hp_low, RoleKind::Text),
+            ("badge", self.badge, RoleKind::Text),
+            ("tooltip", self.tooltip, RoleKind::Text),
+        ]
+    }
+}
+
+/// `red`, `light-gray`, `#ff8800`, `208` (256-color palette), `reset`, `transparent`
+pub fn parse_color(s: &str) -> Option<Color> {
+    let s = s.trim().to_lowercase();
+    if let Some(hex) = s.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        return Some(Color::rgb(channel(0)?, channel(2)?, channel(4)?));
+    }
+    if let Ok(n) = s.parse::<u8>() {
+        return Some(Color::AnsiValue(n));
+    }
+
+    let color = match s.as_str() {
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "white" => Color::White,
+        "dark-gray" => Color::DarkGray,
+        "light-red" => Color::LightRed,
+        "light-green" => Color::LightGreen,
+        "light-yellow" => Color::LightYellow,
+        "light-blue" => Color::LightBlue,
+        "light-magenta" => Color::LightMagenta,
+        "light-cyan" => Color::LightCyan,
+        "light-gray" => Color::LightGray,
+        "reset" => Color::Reset,
+        "transparent" => Color::Transparent,
+        _ => return None,
+    };
+    Some(color)
+}
+
+/// `"white"` (transparent background) or `"white on black"`
+pub fn parse_pair(s: &str) -> Option<ColorPair> {
+    match s.split_once(" on ") {
+        Some((fg, bg)) => Some(ColorPair::new(parse_color(fg)?, parse_color(bg)?)),
+        None => Some(fg(parse_color(s)?)),
+    }
+}
+
+/// Applies the solid-backgrounds setting to one panel: cells that would be drawn with a
+/// transparent background get the panel's color instead, so nothing shows through on
+/// terminals with background images or translucency.
+#[derive(Clone, Copy, Debug)]
+pub struct Paint(pub Option<Color>);
+
+impl Paint {
+    pub fn new(solid: bool, panel_bg: Color) -> Self {
+        Paint(solid.then_some(panel_bg))
+    }
+
+    pub fn on(self, pair: ColorPair) -> ColorPair {
+        match self.0 {
+            Some(bg) if pair.bg == Color::Transparent => ColorPair::new(pair.fg, bg),
+            _ => pair,
+        }
+    }
+
+    pub fn fill(self, container: Container) -> Container {
+        match self.0 {
+            Some(bg) => container.with_background_color(ColorPair::new(Color::Reset, bg)),
+            None => container,
+        }
+    }
+}
+
+// ==============================
+// Contrast
+// ==============================
+
+/// What we assume the terminal draws for `Reset`/`Transparent`.
+/// There's no portable way to query it, and dark terminals are the common case.
+pub const ASSUMED_TERMINAL_FG: (u8, u8, u8) = (229, 229, 229);
+pub const ASSUMED_TERMINAL_BG: (u8, u8, u8) = (0, 0, 0);
+
+/// Approximate RGB for a color as it is actually emitted.
+///
+/// Note MinUI maps `Light*` to the *dim* ANSI slot and the plain names to the bright slot,
+/// so e.g. `LightBlue` is ANSI 4. Values are xterm's defaults. `None` means "terminal default".
+pub fn to_rgb(color: Color) -> Option<(u8, u8, u8)> {
+    let rgb = match color {
+        Color::Black => ansi_rgb(0),
+        Color::LightRed => ansi_rgb(1),
+        Color::LightGreen => ansi_rgb(2),
+        Color::LightYellow => ansi_rgb(3),
+        Color::LightBlue => ansi_rgb(4),
+        Color::LightMagenta => ansi_rgb(5),
+        Color::LightCyan => ansi_rgb(6),
+        Color::LightGray => ansi_rgb(7),
+        Color::DarkGray => ansi_rgb(8),
+        Color::Red => ansi_rgb(9),
+        Color::Green => ansi_rgb(10),
+        Color::Yellow => ansi_rgb(11),
+        Color::Blue => ansi_rgb(12),
+        Color::Magenta => ansi_rgb(13),
+        Color::Cyan => ansi_rgb(14),
+        Color::White => ansi_rgb(15),
+        Color::AnsiValue(n) => ansi_rgb(n),
+        Color::Rgb { r, g, b } => (r, g, b),
+        Color::Reset | Color::Transparent => return None,
+    };
+    Some(rgb)
+}
+
+/// xterm's 256-color palette
+fn ansi_rgb(n: u8) -> (u8, u8, u8) {
+    const BASE: [(u8, u8, u8); 16] = [
+        (0, 0, 0),
+        (205, 0, 0),
+        (0, 205, 0),
+        (205, 205, 0),
+        (0, 0, 238),
+        (205, 0, 205),
+        (0, 205, 205),
+        (229, 229, 229),
+        (127, 127, 127),
+        (255, 0, 0),
+        (0, 255, 0),
+        (255, 255, 0),
+        (92, 92, 255),
+        (255, 0, 255),
+        (0, 255, 255),
+        (255, 255, 255),
+    ];
+
+    match n {
+        0..=15 => BASE[n as usize],
+        16..=231 => {
+            let i = n - 16;
+            let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+            (level(i / 36), level((i / 6) % 6), level(i % 6))
+        }
+        _ => {
+            let v = 8 + (n - 232) * 10;
+            (v, v, v)
+        }
+    }
+}
+
+fn relative_luminance((r, g, b): (u8, u8, u8)) -> f32 {
+    let channel = |c: u8| {
+        let c = c as f32 / 255.0;
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * channel(r) + 0.7152 * channel(g) + 0.0722 * channel(b)
+}
+
+/// WCAG contrast ratio of a pair, from 1.0 (identical) to 21.0 (black on white)
+pub fn contrast_ratio(pair: ColorPair) -> f32 {
+    let fg = to_rgb(pair.fg).unwrap_or(ASSUMED_TERMINAL_FG);
+    let bg = to_rgb(pair.bg).unwrap_or(ASSUMED_TERMINAL_BG);
+
+    let (a, b) = (relative_luminance(fg), relative_luminance(bg));
+    let (hi, lo) = if a > b { (a, b) } else { (b, a) };
+    (hi + 0.05) / (lo + 0.05)
+}
@@ -6,49 +6,125 @@
 //! - Render the game as nested `Container`s
 //! - Register clickable hitboxes for card slots via `InteractionCache::register`
 
-use std::time::Duration;
+use std::io::{self, Write};
 
 use minui::Window;
 use minui::prelude::*;
 use minui::ui::UiScene;
-use minui::widgets::{ContainerPadding, TextInput, TextInputState, Tooltip, WidgetArea};
-
-use crate::logic::{Game, GameState};
+use minui::widgets::{ContainerPadding, TextInput, TextInputState, WidgetArea};
+
+use crate::achievements::{self, ACHIEVEMENTS};
+use crate::advisor;
+use crate::clipboard;
+use crate::clock;
+use crate::coach;
+use crate::command::{self, Command, CustomCommand, SandboxCommand, Target, Verb, Vocabulary};
+use crate::config::{Config, KeyAction};
+use crate::export;
+#[cfg(feature = "gamepad")]
+use crate::gamepad::{Button, Gamepad};
+use crate::hint;
+use crate::locale;
+use crate::logic::{
+    self, Action, Affliction, Boss, Card, Depth, Game, GameState, HpDelta, Lesson, MUTATORS,
+    Mutator, Setup, Trap, Ware,
+};
+use crate::marathon::{self, Marathon, SessionEntry};
 use crate::messages as msg;
-use crate::render::{card_color, card_text, health_color, health_line, weapon_line};
-
-fn command_placeholder(game: &Game) -> String {
+use crate::observe::Publisher;
+use crate::pacing::Pacing;
+use crate::presets::{self, Preset};
+use crate::profile::{self, Profile};
+use crate::render::{
+    self, Glyphs, armor_line, card_color, card_face, card_text, health_color, health_line,
+    reshuffle_badge, weapon_line,
+};
+use crate::replay::{self, Playback};
+use crate::rules::{DiscardView, KNOBS, Ruleset, SkipPolicy};
+use crate::save;
+use crate::scores::{self, Query, ScoreEntry};
+use crate::sim::{self, Batch, Report};
+use crate::solver::{Analysis, Estimate};
+use crate::speedrun::Speedrun;
+use crate::storage::{self, Storage};
+use crate::text;
+use crate::theme::{self, Paint, Theme};
+use crate::weekly::Weekly;
+
+fn command_placeholder(game: &Game, has_save: bool, vocab: &Vocabulary) -> String {
     // Keep these always-available commands last, since they're "meta" actions
-    let mut parts: Vec<&'static str> = Vec::new();
+    let mut parts: Vec<String> = Vec::new();
+    let word = |verb| vocab.first(verb).to_string();
+    let yes_no = || format!("{}/{}", vocab.first(Verb::Yes), vocab.first(Verb::No));
 
     match game.state {
         GameState::MainMenu => {
-            parts.push("start");
+            parts.push(word(Verb::Start));
+            if has_save {
+                parts.push("load".to_string());
+            }
+        }
+        GameState::ResumePrompt => {
+            parts.push(yes_no());
         }
         GameState::RoomChoice => {
-            parts.push("f");
-            if game.can_skip {
-                parts.push("s");
+            parts.push(word(Verb::Face));
+            if game.legal_actions().contains(&Action::Skip) {
+                parts.push(word(Verb::Skip));
+            }
+            if game.reshuffles_left > 0 {
+                parts.push(word(Verb::Reshuffle));
+            }
+            if game.mulligan_available() {
+                parts.push("mulligan".to_string());
             }
         }
         GameState::CardSelection => {
-            parts.push("1..4");
+            parts.push("1..4".to_string());
+            if game
+                .legal_actions()
+                .iter()
+                .any(|a| matches!(a, Action::Banish(_)))
+            {
+                parts.push("banish N".to_string());
+            }
+            let legal = game.legal_actions();
+            if legal.iter().any(|a| matches!(a, Action::Stash(_))) {
+                parts.push("inv N".to_string());
+            }
+            if legal.iter().any(|a| matches!(a, Action::Use(_))) {
+                parts.push("use N".to_string());
+            }
         }
         GameState::CardInteraction => {
-            if game.awaiting_weapon_choice {
-                parts.push("y/n");
+            if game.choosing_hand() {
+                parts.push(format!("1 | 2 | {}", word(Verb::No)));
+            } else if game.awaiting_weapon_choice {
+                parts.push(yes_no());
             } else {
-                parts.push("(Enter)");
+                parts.push("(Enter)".to_string());
             }
         }
+        GameState::Trap => parts.push("(Enter)".to_string()),
+        GameState::Shop => {
+            parts.extend(game.legal_actions().into_iter().filter_map(|a| match a {
+                Action::Buy(ware) => Some(ware.code().to_string()),
+                _ => None,
+            }));
+            parts.push("leave".to_string());
+        }
         GameState::GameOver => {
-            parts.push("restart");
+            if game.survived && !game.sandbox {
+                parts.push("plus".to_string());
+            }
+            parts.push("retry".to_string());
+            parts.push("tag".to_string());
         }
     }
 
     // Global commands (always valid options)
-    parts.push("restart");
-    parts.push("exit");
+    parts.push(word(Verb::Restart));
+    parts.push(word(Verb::Exit));
 
     parts.join(" | ")
 }
@@ -58,40 +134,259 @@ fn command_placeholder(game: &Game) -> String {
 // ==============================
 
 pub const ID_INPUT: InteractionId = 1;
+pub const ID_NOTES: InteractionId = 2;
 pub const ID_CARD_1: InteractionId = 101;
 pub const ID_CARD_2: InteractionId = 102;
 pub const ID_CARD_3: InteractionId = 103;
 pub const ID_CARD_4: InteractionId = 104;
+/// Drop targets for a dragged card: the HP line and the weapon line
+pub const ID_HEALTH: InteractionId = 110;
+pub const ID_WEAPON: InteractionId = 111;
+/// Sandbox card picker: one id per card, in `logic::full_deck` order
+pub const ID_PICKER: InteractionId = 200;
 
 // ==============================
 // AppState
 // ==============================
 
+/// The custom game screen: rules being put together, and the last reply to a command
+pub struct CustomGame {
+    pub rules: Ruleset,
+    pub message: String,
+    /// The last `test`, running or done
+    pub balance: Option<Balance>,
+}
+
+/// Bot games simulated on the custom game screen
+pub struct Balance {
+    /// The rules tested, to tell when the ones on screen have moved on
+    pub rules: Ruleset,
+    pub pending: Option<Batch>,
+    pub report: Option<Report>,
+}
+
+/// How long a toast stays up
+const TOAST_MS: u64 = 8000;
+
+/// A note in the top right corner, over whatever screen is up, until `until_ms`
+pub struct Toast {
+    pub title: &'static str,
+    pub text: String,
+    pub until_ms: u64,
+}
+
+/// The sandbox screen: the position being set up, where clicked cards go, and the last
+/// reply to a command
+pub struct Sandbox {
+    pub setup: Setup,
+    pub target: Target,
+    pub message: String,
+}
+
+/// What Ctrl+Q does in the middle of a run (outside a run it always quits straight away)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum QuitMode {
+    /// Quit without saving
+    Instant,
+    /// Ask first; confirming autosaves
+    #[default]
+    Confirm,
+    /// Autosave and quit without asking
+    SaveAndQuit,
+}
+
+/// Where card tooltips go (`tooltips` under `[display]`)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TooltipMode {
+    /// Floating by the mouse
+    #[default]
+    Overlay,
+    /// On the last line of the Message panel, for terminals where overlays leave a mess
+    Message,
+}
+
+impl TooltipMode {
+    pub const VALUES: &str = "overlay | message";
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "overlay" => Some(TooltipMode::Overlay),
+            "message" => Some(TooltipMode::Message),
+            _ => None,
+        }
+    }
+}
+
+impl QuitMode {
+    pub const VALUES: &str = "instant | confirm | save";
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "instant" => Some(QuitMode::Instant),
+            "confirm" => Some(QuitMode::Confirm),
+            "save" => Some(QuitMode::SaveAndQuit),
+            _ => None,
+        }
+    }
+}
+
 pub struct AppState {
     pub game: Game,
 
+    /// Where anything that outlives the process is kept (saves, scores, profile)
+    pub storage: Box<dyn Storage>,
+    /// Cached so the main menu can offer `load` without hitting storage every frame
+    pub has_save: bool,
+
     pub ui: UiScene,
     pub input: TextInputState,
 
     pub mouse_down: bool,
     pub dragging: bool,
-
+    /// Room slot pressed on, played where the mouse lets go of it (`drop_card`)
+    pub drag_card: Option<usize>,
+
+    /// Settings from `config.toml` and the command line
+    pub config: Config,
+    /// Every finished run, oldest first. `None` if the score file couldn't be read,
+    /// in which case nothing is recorded so it doesn't get overwritten.
+    pub scores: Option<Vec<ScoreEntry>>,
+    /// Marathon session in progress
+    pub marathon: Option<Marathon>,
+    /// Just-stopped session, shown full screen until dismissed
+    pub marathon_summary: Option<Marathon>,
+    /// Finished sessions, oldest first. `None` if unreadable, like `scores`.
+    pub marathon_board: Option<Vec<SessionEntry>>,
+    /// This week's challenge, shown before it starts (`weekly` on the main menu)
+    pub briefing: Option<Weekly>,
+    /// Open while building a custom game (`custom` on the main menu)
+    pub custom: Option<CustomGame>,
+    /// Open while setting up a position (`sandbox` on the main menu)
+    pub sandbox: Option<Sandbox>,
+    /// Saved rule presets. `None` if unreadable, like `scores`.
+    pub presets: Option<Vec<Preset>>,
+    /// Lifetime wins, losses and streaks. `None` if unreadable, like `scores`.
+    pub profile: Option<Profile>,
+    /// First-time rule explanations still to be read; the first one is on screen
+    pub lessons: Vec<Lesson>,
+    /// `started_at_ms` of the last run written to the table, so each run is recorded once
+    pub recorded_run: Option<u64>,
+    /// Picks up `config.toml` edits while running (dev builds only)
+    #[cfg(feature = "dev")]
+    pub hot_reload: Option<crate::dev::HotReload>,
+    /// Card highlighted for gamepad play (`None` until a gamepad moves it)
+    pub focused_card: Option<usize>,
+    #[cfg(feature = "gamepad")]
+    pub gamepad: Option<Gamepad>,
+    /// Example game being watched (`watch`). Its game is never saved or scored.
+    pub demo: Option<Playback>,
+    /// High score panel outside GameOver (toggled by `scores`)
+    pub show_scores: bool,
+    /// Which runs the score panel covers (`scores casual since ...`)
+    pub score_query: Query,
+    /// Threat / pickup tags on room cards (`ranks`, or `room_ranks` in config)
+    pub show_ranks: bool,
+    /// HP change of picking each room card (`preview`, or `hp_preview` in config)
+    pub show_preview: bool,
+    /// Full-screen contrast test pattern (toggled by `contrast`)
+    pub show_contrast_audit: bool,
+    /// Full-screen achievement progress (toggled by `achievements`)
+    pub show_achievements: bool,
+    /// Full-screen move log (toggled by `log`)
+    pub show_log: bool,
+
+    /// Notes panel under the command box (toggled by `notes`). Its text lives in
+    /// `game.notes` so it's saved with the run; `notes_input` is the editor for it.
+    pub show_notes: bool,
+    pub notes_input: TextInputState,
+    /// Typing goes to the notes instead of the command box
+    pub notes_focused: bool,
+
+    /// `simulate` running in the background
+    pub batch: Option<Batch>,
+    /// `analyze` running in the background
+    pub analysis: Option<Analysis>,
+    /// A note in the corner that goes away on its own
+    pub toast: Option<Toast>,
+    /// Run timer and room splits (`--speedrun`), caught up with the game every frame
+    pub speedrun: Option<Speedrun>,
+    /// Frame rate and idling, low power or not (`pacing::Pacing`)
+    pub pacing: Pacing,
+    /// Hands the game's state to read-only observers after every update
+    pub publisher: Publisher,
+    /// `banish N` is waiting on a yes before the card in that slot goes
+    pub confirming_banish: Option<usize>,
+    /// `forge` is waiting on a yes before both weapons go into the fire
+    pub confirming_forge: bool,
+    /// The "Quit?" dialog is up
+    pub confirming_quit: bool,
     pub should_quit: bool,
     pub mouse_pos: (u16, u16),
     pub card_hovers: [HoverTracker; 4],
 }
 
 impl AppState {
-    pub fn new() -> Self {
+    pub fn new(storage: Box<dyn Storage>, config: Config) -> Self {
         let mut input = TextInputState::new();
         input.set_focused(true);
+        let pacing = Pacing::new(&config, false);
+        let speedrun = config.speedrun.then(Speedrun::default);
+
+        let mut game = match config.seed {
+            Some(seed) => Game::with_seed(config.rules.clone(), seed),
+            None => Game::new(config.rules.clone()),
+        };
+        if save::exists(storage.as_ref(), save::AUTOSAVE) {
+            game.state = GameState::ResumePrompt;
+            game.message = msg::AUTOSAVE_FOUND.to_string();
+        }
 
         Self {
-            game: Game::new(),
+            game,
+            has_save: save::exists(storage.as_ref(), save::CURRENT_SAVE),
+            scores: scores::load(storage.as_ref()).ok(),
+            marathon: None,
+            marathon_summary: None,
+            marathon_board: marathon::load(storage.as_ref()).ok(),
+            briefing: None,
+            custom: None,
+            sandbox: None,
+            presets: presets::load(storage.as_ref()).ok(),
+            profile: profile::load(storage.as_ref()).ok(),
+            lessons: Vec::new(),
+            recorded_run: None,
+            #[cfg(feature = "dev")]
+            hot_reload: None,
+            focused_card: None,
+            #[cfg(feature = "gamepad")]
+            gamepad: Gamepad::open(),
+            demo: None,
+            show_scores: false,
+            score_query: Query::default(),
+            show_ranks: config.room_ranks,
+            show_preview: config.hp_preview,
+            storage,
             ui: UiScene::new(),
             input,
             mouse_down: false,
             dragging: false,
+            drag_card: None,
+            config,
+            show_contrast_audit: false,
+            show_achievements: false,
+            show_log: false,
+            show_notes: false,
+            notes_input: TextInputState::new(),
+            notes_focused: false,
+            batch: None,
+            analysis: None,
+            toast: None,
+            speedrun,
+            pacing,
+            publisher: Publisher::default(),
+            confirming_banish: None,
+            confirming_forge: false,
+            confirming_quit: false,
             should_quit: false,
             mouse_pos: (0, 0),
             card_hovers: [
@@ -103,575 +398,3350 @@ impl AppState {
         }
     }
 
-    fn set_last_command_feedback(&mut self, cmd: &str) {
-        self.game.last_command_feedback = format!("{}{}", msg::CMD_PREFIX, cmd);
-    }
-}
-
-// ==============================
-// Update
-// ==============================
-
-pub fn update(state: &mut AppState, event: Event) -> bool {
-    if state.should_quit {
-        return false;
-    }
-
-    // Quit (Ctrl+Q only)
-    if let Event::KeyWithModifiers(k) = event {
-        if matches!(k.key, KeyKind::Char('q')) && k.mods.ctrl {
-            return false;
+    fn save_run(&mut self) {
+        if !self.game.in_progress() {
+            self.game.message = msg::NOTHING_TO_SAVE.to_string();
+            return;
         }
-    }
-
-    // Apply scene policies (focus/capture bookkeeping)
-    let _effects = state.ui.apply_policies(&event);
-
-    // Mouse events: click-to-focus input / click-to-select cards / drag selection in input
-    match event {
-        Event::MouseMove { x, y } => {
-            state.mouse_pos = (x, y);
-
-            // Check which card slots are being hovered
-            let hit = state.ui.hit_test_id(x, y);
-            for i in 0..4 {
-                let card_id = match i {
-                    0 => ID_CARD_1,
-                    1 => ID_CARD_2,
-                    2 => ID_CARD_3,
-                    _ => ID_CARD_4,
-                };
-
-                if hit == Some(card_id) {
-                    state.card_hovers[i].start_hover();
-                } else {
-                    state.card_hovers[i].end_hover();
-                }
+        match save::save_run(self.storage.as_ref(), &self.game) {
+            Ok(()) => {
+                self.has_save = true;
+                self.game.message = format!(
+                    "Run saved to {}/{}. Type 'load' to resume it later.",
+                    self.storage.location(),
+                    save::CURRENT_SAVE
+                );
             }
+            Err(e) => self.game.message = e,
         }
-        Event::MouseClick { x, y, button: _ } => {
-            state.mouse_down = true;
-            state.dragging = false;
+    }
 
-            let hit = state.ui.hit_test_id(x, y);
-            match hit {
-                Some(ID_INPUT) => {
-                    state.input.set_focused(true);
-                    state.input.click_set_cursor(x);
-                    return true;
-                }
-                Some(ID_CARD_1) => {
-                    // Only allow clicking cards when we're actually in the selection state.
-                    // If not, show state-appropriate guidance (avoid stale/incorrect MUST_FACE_FIRST).
-                    if state.game.state == GameState::CardSelection {
-                        let _ = state.game.play_card_from_slot(0);
-                    } else {
-                        state.game.message = match state.game.state {
-                            GameState::RoomChoice => msg::NEED_FACE_OR_SKIP.to_string(),
-                            GameState::CardInteraction => {
-                                if state.game.awaiting_weapon_choice {
-                                    msg::NEED_Y_OR_N.to_string()
-                                } else {
-                                    msg::HINT_INTERACTION_ACK.to_string()
-                                }
-                            }
-                            GameState::MainMenu => msg::NEED_START.to_string(),
-                            GameState::GameOver => msg::RESTART_HELP.to_string(),
-                            GameState::CardSelection => msg::NEED_SELECT_CARD.to_string(),
-                        };
-                    }
-                    return true;
-                }
-                Some(ID_CARD_2) => {
-                    // Only allow clicking cards when we're actually in the selection state.
-                    // If not, show state-appropriate guidance (avoid stale/incorrect MUST_FACE_FIRST).
-                    if state.game.state == GameState::CardSelection {
-                        let _ = state.game.play_card_from_slot(1);
-                    } else {
-                        state.game.message = match state.game.state {
-                            GameState::RoomChoice => msg::NEED_FACE_OR_SKIP.to_string(),
-                            GameState::CardInteraction => {
-                                if state.game.awaiting_weapon_choice {
-                                    msg::NEED_Y_OR_N.to_string()
-                                } else {
-                                    msg::HINT_INTERACTION_ACK.to_string()
-                                }
-                            }
-                            GameState::MainMenu => msg::NEED_START.to_string(),
-                            GameState::GameOver => msg::RESTART_HELP.to_string(),
-                            GameState::CardSelection => msg::NEED_SELECT_CARD.to_string(),
-                        };
-                    }
-                    return true;
-                }
-                Some(ID_CARD_3) => {
-                    // Only allow clicking cards when we're actually in the selection state.
-                    // If not, show state-appropriate guidance (avoid stale/incorrect MUST_FACE_FIRST).
-                    if state.game.state == GameState::CardSelection {
-                        let _ = state.game.play_card_from_slot(2);
-                    } else {
-                        state.game.message = match state.game.state {
-                            GameState::RoomChoice => msg::NEED_FACE_OR_SKIP.to_string(),
-                            GameState::CardInteraction => {
-                                if state.game.awaiting_weapon_choice {
-                                    msg::NEED_Y_OR_N.to_string()
-                                } else {
-                                    msg::HINT_INTERACTION_ACK.to_string()
-                                }
-                            }
-                            GameState::MainMenu => msg::NEED_START.to_string(),
-                            GameState::GameOver => msg::RESTART_HELP.to_string(),
-                            GameState::CardSelection => msg::NEED_SELECT_CARD.to_string(),
-                        };
-                    }
-                    return true;
-                }
-                Some(ID_CARD_4) => {
-                    // Only allow clicking cards when we're actually in the selection state.
-                    // If not, show state-appropriate guidance (avoid stale/incorrect MUST_FACE_FIRST).
-                    if state.game.state == GameState::CardSelection {
-                        let _ = state.game.play_card_from_slot(3);
-                    } else {
-                        state.game.message = match state.game.state {
-                            GameState::RoomChoice => msg::NEED_FACE_OR_SKIP.to_string(),
-                            GameState::CardInteraction => {
-                                if state.game.awaiting_weapon_choice {
-                                    msg::NEED_Y_OR_N.to_string()
-                                } else {
-                                    msg::HINT_INTERACTION_ACK.to_string()
-                                }
-                            }
-                            GameState::MainMenu => msg::NEED_START.to_string(),
-                            GameState::GameOver => msg::RESTART_HELP.to_string(),
-                            GameState::CardSelection => msg::NEED_SELECT_CARD.to_string(),
-                        };
-                    }
-                    return true;
-                }
-                _ => {
-                    // click outside: stop drag
-                    state.mouse_down = false;
-                    state.dragging = false;
-                    return true;
-                }
-            }
+    /// Deal this run's dungeon again, and keep count of the goes at it
+    fn retry_run(&mut self) {
+        let seed = self.game.seed;
+        let tried = self.profile.as_ref().map_or(1, |p| p.attempts_at(seed));
+        let attempt = tried.max(self.game.attempt) + 1;
+        if let Err(reason) = self.game.retry(attempt) {
+            self.game.message = reason.to_string();
+            return;
         }
-        Event::MouseDrag { x, y: _, button: _ } => {
-            if !state.mouse_down {
-                return true;
-            }
-            state.dragging = true;
-            if state.input.is_focused() {
-                state.input.drag_select_to(x);
+
+        if let Some(profile) = &mut self.profile {
+            profile.record_attempt(seed, attempt);
+            if let Err(e) = profile::save(self.storage.as_ref(), profile) {
+                self.game.message = format!("{} (Couldn't update stats: {e})", self.game.message);
             }
-            return true;
         }
-        Event::MouseRelease { x, y: _, button: _ } => {
-            if state.mouse_down && state.dragging && state.input.is_focused() {
-                state.input.drag_select_to(x);
+    }
+
+    fn export_run(&mut self) {
+        match export::write(self.storage.as_ref(), &self.game) {
+            Ok(key) => {
+                self.game.message = format!(
+                    "Run exported to {}/{key} (and .csv)",
+                    self.storage.location()
+                );
+                // Trim the oldest exports as new ones come in; failing that isn't worth
+                // spoiling the export over
+                let _ = storage::prune(self.storage.as_ref(), self.config.retention);
             }
-            state.mouse_down = false;
-            state.dragging = false;
-            return true;
+            Err(e) => self.game.message = format!("Couldn't export: {e}"),
         }
-        _ => {}
     }
 
-    // Enter submits the command (modifier-aware + legacy)
-    if let Event::KeyWithModifiers(k) = event {
-        if matches!(k.key, KeyKind::Enter) {
-            submit_command(state);
-            return true;
+    /// `ascend`: a run on rung `level` of the ladder, or the highest one unlocked
+    fn ascend(&mut self, level: Option<u32>) {
+        let top = self.profile.as_ref().map_or(0, |p| p.ascension);
+        let level = level.unwrap_or(top);
+        if top == 0 {
+            self.game.message = msg::ASCENSION_LOCKED.to_string();
+            return;
         }
-    }
-    if matches!(event, Event::Enter) {
-        submit_command(state);
-        return true;
+        if level > top {
+            self.game.message =
+                format!("Ascension {level} isn't unlocked yet. You're up to ascension {top}.");
+            return;
+        }
+        // Same dungeon the menu was about to deal, so --seed still applies
+        self.game = Game::with_seed(logic::ascension_rules(level), self.game.seed);
+        self.game.ascension = level;
+        self.game.start();
+        let newest = logic::ASCENSIONS[level as usize - 1].description();
+        self.game.message = match level {
+            1 => format!("Ascension 1: {newest}."),
+            _ => format!("Ascension {level}: {newest}, on top of every rung below."),
+        };
     }
 
-    // Let TextInput consume typing/editing
-    if state.input.handle_event(event) {
-        return true;
-    }
+    /// `mutators NAME...` or `mutators CODE`: a run on your rules with those mutators, and
+    /// the code to pass on to a friend
+    fn start_mutated(&mut self, words: &[String]) {
+        let mutators = match words {
+            [] => {
+                let names: Vec<&str> = MUTATORS.iter().map(|m| m.code()).collect();
+                self.game.message =
+                    format!("Mutators: {}. {}", names.join(", "), msg::MUTATORS_HELP);
+                return;
+            }
+            [code] if Mutator::from_code(code).is_none() => presets::mutators_from_code(code),
+            names => names
+                .iter()
+                .map(|w| {
+                    Mutator::from_code(w).ok_or_else(|| format!("There's no mutator called '{w}'."))
+                })
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map(|picked| {
+                    MUTATORS
+                        .iter()
+                        .copied()
+                        .filter(|m| picked.contains(m))
+                        .collect()
+                }),
+        };
+        let mutators = match mutators {
+            Ok(mutators) => mutators,
+            Err(reason) => {
+                self.game.message = reason;
+                return;
+            }
+        };
 
-    true
-}
+        // Same dungeon the menu was about to deal, so --seed still applies
+        self.game = Game::with_seed(self.config.rules.clone(), self.game.seed);
+        self.game.mutators = mutators;
+        self.game.start();
+        let twists: Vec<&str> = self.game.mutators.iter().map(|m| m.description()).collect();
+        self.game.message = format!(
+            "{}. Share code: {}",
+            twists.join(". "),
+            presets::mutator_code(&self.game.mutators)
+        );
+    }
 
-fn submit_command(state: &mut AppState) {
-    let raw = state.input.text().trim().to_string();
+    /// The main menu's difficulty tip, if it has one for this profile
+    fn advice(&self) -> Option<String> {
+        self.profile
+            .as_ref()?
+            .advice(self.scores.as_deref()?, self.show_ranks)
+    }
 
-    // Empty Enter:
-    // - Only continues in CardInteraction when NOT awaiting weapon choice
-    // - Otherwise it's a no-op to avoid accidental actions
-    if raw.is_empty() {
-        state.input.set_text("");
-        if state.game.state == GameState::CardInteraction && !state.game.awaiting_weapon_choice {
-            state.game.continue_after_interaction();
-        }
-        return;
+    /// `dismiss`: no more difficulty tips, for good
+    fn dismiss_advice(&mut self) {
+        let Some(profile) = &mut self.profile else {
+            self.game.message = msg::ACHIEVEMENTS_UNAVAILABLE.to_string();
+            return;
+        };
+        profile.advice_dismissed = true;
+        self.game.message = match profile::save(self.storage.as_ref(), profile) {
+            Ok(()) => msg::ADVICE_DISMISSED.to_string(),
+            Err(e) => format!("{} (Couldn't update stats: {e})", msg::ADVICE_DISMISSED),
+        };
     }
 
-    let cmd = raw;
-    state.set_last_command_feedback(&cmd);
-    state.input.set_text("");
+    /// `storage`: what each folder holds, in one line
+    fn show_storage(&mut self) {
+        self.game.message = match storage::usage(self.storage.as_ref()) {
+            Ok(folders) if folders.is_empty() => {
+                format!("Nothing stored yet in {}.", self.storage.location())
+            }
+            Ok(folders) => {
+                let parts: Vec<String> = folders
+                    .iter()
+                    .map(|(dir, files, bytes)| {
+                        let name = if dir.is_empty() { "other" } else { dir };
+                        format!("{name} {files} ({})", storage::size_text(*bytes))
+                    })
+                    .collect();
+                let total: u64 = folders.iter().map(|(_, _, bytes)| bytes).sum();
+                format!(
+                    "{}: {} · {} in all. {}",
+                    self.storage.location(),
+                    parts.join(", "),
+                    storage::size_text(total),
+                    msg::STORAGE_PRUNE_HINT
+                )
+            }
+            Err(e) => format!("Couldn't look at storage: {e}"),
+        };
+    }
 
-    // Global exit/restart
-    if cmd.eq_ignore_ascii_case("exit") || cmd.eq_ignore_ascii_case("quit") {
-        state.should_quit = true;
-        return;
+    /// `storage prune`
+    fn prune_storage(&mut self) {
+        self.game.message = match storage::prune(self.storage.as_ref(), self.config.retention) {
+            Ok((0, _)) => msg::STORAGE_NOTHING_TO_PRUNE.to_string(),
+            Ok((files, bytes)) => format!(
+                "Deleted {files} old export files, freeing {}.",
+                storage::size_text(bytes)
+            ),
+            Err(e) => format!("Couldn't prune: {e}"),
+        };
     }
-    if cmd.eq_ignore_ascii_case("restart") {
-        state.game.reset_to_playing();
-        return;
+
+    /// Copy the result block, and keep a file copy for terminals that ignore the clipboard
+    fn share_run(&mut self) {
+        let text = render::share_text(&self.game);
+        let copied = clipboard::copy(&text).is_ok();
+
+        let path = format!("{}/{}", self.storage.location(), render::SHARE_FILE);
+        self.game.message = match self.storage.write(render::SHARE_FILE, &text) {
+            Ok(()) if copied => format!("{} Also saved to {path}", msg::SHARE_COPIED),
+            Ok(()) => format!("Result saved to {path}"),
+            Err(_) if copied => msg::SHARE_COPIED.to_string(),
+            Err(e) => format!("Couldn't share: {e}"),
+        };
     }
 
-    match state.game.state {
-        GameState::MainMenu => {
-            if cmd.eq_ignore_ascii_case("start") || cmd.eq_ignore_ascii_case("s") {
-                state.game.state = GameState::RoomChoice;
-                state.game.fill_room();
-                state.game.message = msg::ENTERED_DUNGEON.to_string();
-            } else {
-                state.game.message = msg::NEED_START.to_string();
+    fn load_run(&mut self) {
+        match save::load_over(self.storage.as_ref(), &self.game) {
+            Ok(Some(game)) => {
+                self.game = game;
+                self.game.message = msg::RUN_LOADED.to_string();
             }
+            Ok(None) => self.game.message = msg::NO_SAVE_FOUND.to_string(),
+            Err(e) => self.game.message = e,
         }
+    }
 
-        GameState::RoomChoice => {
-            // Accept either the short forms (f/s) or the clearer words (face/skip)
-            if cmd.eq_ignore_ascii_case("f") || cmd.eq_ignore_ascii_case("face") {
-                state.game.face_room();
-            } else if cmd.eq_ignore_ascii_case("s") || cmd.eq_ignore_ascii_case("skip") {
-                state.game.skip_room();
-            } else if state.game.can_skip {
-                state.game.message = msg::NEED_FACE_OR_SKIP.to_string();
-            } else {
-                state.game.message = msg::NEED_FACE_ONLY.to_string();
-            }
+    /// Called on every way out of the app so quitting never loses a run
+    fn autosave(&self) {
+        if self.game.in_progress() && self.demo.is_none() {
+            // Best effort: we're exiting either way
+            let _ = save::autosave(self.storage.as_ref(), &self.game);
         }
+    }
 
-        GameState::CardSelection => {
-            if let Ok(n) = cmd.parse::<usize>() {
-                let idx = n.saturating_sub(1);
-                let _ = state.game.play_card_from_slot(idx);
-            } else {
-                state.game.message = msg::NEED_SELECT_CARD.to_string();
+    #[cfg(feature = "dev")]
+    fn poll_hot_reload(&mut self) {
+        let Some(result) = self.hot_reload.as_mut().and_then(|r| r.poll()) else {
+            return;
+        };
+        match result {
+            Ok(config) => {
+                // Theme, glyphs and keys take effect right away. Rules belong to a run, so a
+                // run in progress keeps its own and the new ones apply from the next one.
+                crate::render::set_glyphs(config.glyphs);
+                crate::locale::set_locale(config.locale);
+                self.config = config;
+                if !self.game.in_progress() {
+                    self.game.rules = self.config.rules.clone();
+                    self.game.reshuffles_left = self.game.rules.room_reshuffles;
+                }
+                self.game.message = msg::CONFIG_RELOADED.to_string();
             }
+            Err(e) => self.game.message = format!("config.toml not reloaded: {e}"),
         }
+    }
 
-        GameState::CardInteraction => {
-            if state.game.awaiting_weapon_choice {
-                if cmd.eq_ignore_ascii_case("y") {
-                    let _ = state.game.answer_weapon_prompt(true);
-                } else if cmd.eq_ignore_ascii_case("n") {
-                    let _ = state.game.answer_weapon_prompt(false);
-                } else {
-                    state.game.message = msg::NEED_Y_OR_N.to_string();
-                }
-            } else if cmd.eq_ignore_ascii_case("ok") {
-                state.game.continue_after_interaction();
-            } else {
-                // Ignore other commands during acknowledgement step
+    fn watch_demo(&mut self, n: usize) {
+        match replay::load_demo(self.storage.as_ref(), n) {
+            Ok(replay) => {
+                let (playback, game) = Playback::new(replay, n);
+                self.game = game;
+                self.game.message = format!("Watching: {}", playback.replay.title);
+                self.demo = Some(playback);
             }
+            Err(e) => self.game.message = format!("Couldn't load the example game: {e}"),
         }
+    }
 
-        GameState::GameOver => {
-            // Non-global commands in GameOver just show help
-            state.game.message = msg::RESTART_HELP.to_string();
+    /// Enter while watching: attach what's typed as a note, or stop if nothing is
+    fn submit_demo_note(&mut self) {
+        let note = self.input.text().trim().to_string();
+        self.input.set_text("");
+        let Some(demo) = &mut self.demo else {
+            return;
+        };
+        if note.is_empty() {
+            self.stop_demo();
+            return;
         }
+
+        self.game.message = match demo.annotate(self.storage.as_ref(), &note) {
+            Ok(step) => format!("Note saved on step {step}: ✎ {note}"),
+            Err(_) if demo.step().is_none() => msg::DEMO_NOTE_TOO_EARLY.to_string(),
+            Err(e) => format!("Couldn't save the note: {e}"),
+        };
     }
 
-    // Death check safeguard (some sequences may reduce HP outside continue)
-    if state.game.health <= 0 && state.game.state != GameState::GameOver {
-        state.game.survived = false;
-        state.game.state = GameState::GameOver;
-        state.game.message = msg::YOU_DIED.to_string();
+    fn stop_demo(&mut self) {
+        self.demo = None;
+        self.game = Game::new(self.config.rules.clone());
+        self.game.message = msg::DEMO_STOPPED.to_string();
+    }
+
+    /// Queue explanations for rules this profile hasn't run into before. Without a
+    /// readable profile there's nowhere to remember them, so none are shown.
+    fn take_lessons(&mut self) {
+        let fresh = std::mem::take(&mut self.game.lessons);
+        if self.demo.is_some() {
+            return;
+        }
+        let Some(profile) = &mut self.profile else {
+            return;
+        };
+
+        let before = self.lessons.len();
+        for lesson in fresh {
+            if profile.seen_lessons.insert(lesson.key().to_string()) {
+                self.lessons.push(lesson);
+            }
+        }
+        if self.lessons.len() > before
+            && let Err(e) = profile::save(self.storage.as_ref(), profile)
+        {
+            self.game.message = format!("{} (Couldn't update stats: {e})", self.game.message);
+        }
+    }
+
+    /// Say so, once, when a fight leaves a weapon unable to touch anything left: a toast,
+    /// and the terminal bell unless `bell` is off
+    fn take_dead_weapon(&mut self) {
+        let Some(card) = self.game.dead_weapon.take() else {
+            return;
+        };
+        if self.demo.is_some() {
+            return;
+        }
+        self.toast = Some(Toast {
+            title: msg::DEAD_WEIGHT_TITLE,
+            text: format!("{} can't fight any monster left.", card_text(card)),
+            until_ms: clock::now_ms() + TOAST_MS,
+        });
+        if self.config.bell {
+            let mut out = io::stdout();
+            let _ = out.write_all(b"\x07").and_then(|()| out.flush());
+        }
+    }
+
+    /// Append a run to the high score table the first time we see it over
+    fn record_finished_run(&mut self) {
+        if self.demo.is_some()
+            || self.game.sandbox
+            || self.game.state != GameState::GameOver
+            || self.recorded_run == Some(self.game.started_at_ms)
+        {
+            return;
+        }
+        self.recorded_run = Some(self.game.started_at_ms);
+
+        let variant = scores::variant(&self.game, self.marathon.is_some());
+        if let Some(profile) = &mut self.profile {
+            profile.record(self.game.survived);
+            let on_ladder =
+                self.game.plus == 0 && (self.game.ascension > 0 || variant == "standard");
+            if let Some(level) = profile.climb(self.game.ascension, self.game.survived && on_ladder)
+            {
+                self.game.message = format!(
+                    "{} Ascension {level} unlocked: {}. Type 'ascend' on the main menu.",
+                    self.game.message,
+                    logic::ASCENSIONS[level as usize - 1].description()
+                );
+            }
+            achievements::tally(profile, &self.game);
+            if let Some(time) = self.speedrun.as_ref().and_then(|t| t.finished)
+                && self.game.survived
+                && profile.record_time(self.game.seed, time)
+            {
+                self.game.message = format!(
+                    "{} New best time on this seed: {}!",
+                    self.game.message,
+                    locale::time(time)
+                );
+            }
+            if let Err(e) = profile::save(self.storage.as_ref(), profile) {
+                self.game.message = format!("{} (Couldn't update stats: {e})", self.game.message);
+            }
+        }
+
+        let Some(table) = &mut self.scores else {
+            return;
+        };
+        let entry = ScoreEntry {
+            score: self.game.final_score(),
+            survived: self.game.survived,
+            at_ms: clock::now_ms(),
+            variant: variant.to_string(),
+            tag: None,
+            ironman: self.game.ironman,
+        };
+        match scores::record(self.storage.as_ref(), table, &entry) {
+            Ok(()) => table.push(entry),
+            Err(e) => {
+                self.game.message =
+                    format!("{} (Couldn't record the score: {e})", self.game.message)
+            }
+        }
+    }
+
+    /// Tag the run that just ended, as its line in the score table
+    fn tag_run(&mut self, tag: Option<String>) {
+        let Some(table) = &mut self.scores else {
+            self.game.message = msg::SCORES_UNAVAILABLE.to_string();
+            return;
+        };
+        let Some(entry) = table
+            .last_mut()
+            .filter(|_| self.recorded_run == Some(self.game.started_at_ms))
+        else {
+            self.game.message = msg::NOTHING_TO_TAG.to_string();
+            return;
+        };
+        entry.tag = tag;
+        self.game.message = match &entry.tag {
+            Some(tag) => format!("Run tagged '{tag}'. See runs like it with 'scores {tag}'."),
+            None => "Tag taken off.".to_string(),
+        };
+        if let Err(e) = scores::rewrite(self.storage.as_ref(), table) {
+            self.game.message = format!("Couldn't save the tag: {e}");
+        }
+    }
+
+    /// In a marathon, bank the finished run and deal the next dungeon straight away
+    fn continue_marathon(&mut self) {
+        if self.game.state != GameState::GameOver {
+            return;
+        }
+        let Some(session) = &mut self.marathon else {
+            return;
+        };
+
+        let (score, survived) = (self.game.final_score(), self.game.survived);
+        session.runs.push((score, survived));
+        let outcome = if survived { "survived" } else { "died" };
+        let message = format!(
+            "Run {} over: {outcome}, {score}. Marathon total {}. Next dungeon!",
+            session.runs.len(),
+            session.total()
+        );
+
+        self.game.reset_to_playing();
+        self.game.message = message;
+    }
+
+    fn start_marathon(&mut self) {
+        self.marathon = Some(Marathon::default());
+        self.game.start();
+        self.game.message = msg::MARATHON_STARTED.to_string();
+    }
+
+    fn open_custom_game(&mut self) {
+        self.custom = Some(CustomGame {
+            rules: self.config.rules.clone(),
+            message: msg::CUSTOM_HELP.to_string(),
+            balance: None,
+        });
+    }
+
+    /// Pick up a finished `test` on the custom game screen
+    fn poll_balance(&mut self) {
+        if let Some(balance) = self.custom.as_mut().and_then(|c| c.balance.as_mut())
+            && let Some(batch) = &mut balance.pending
+            && let Some(report) = batch.poll()
+        {
+            balance.report = Some(report);
+            balance.pending = None;
+        }
+    }
+
+    /// `simulate`: play `games` bot games of the rules new runs use, in the background
+    fn start_batch(&mut self, games: u32) {
+        if let Some(batch) = &self.batch {
+            self.game.message = format!(
+                "A simulation is already running ({}/{} games).",
+                batch.progress(),
+                batch.games
+            );
+            return;
+        }
+        self.batch = Some(Batch::start(self.config.rules.clone(), games));
+        self.game.message = format!(
+            "Simulating {games} bot games in the background. Play on; you'll be told when it's done."
+        );
+    }
+
+    /// `analyze`: play every legal move out `rollouts` times, in the background
+    fn start_analysis(&mut self, rollouts: u32) {
+        if let Some(analysis) = &self.analysis {
+            self.game.message = format!(
+                "An analysis is already running ({}% done).",
+                analysis.percent()
+            );
+            return;
+        }
+        let moves = self.game.legal_actions().len();
+        if moves < 2 {
+            self.game.message = msg::ANALYZE_NOTHING.to_string();
+            return;
+        }
+        self.analysis = Some(Analysis::start(&self.game, rollouts));
+        self.game.message = format!(
+            "Playing each of {moves} moves out {rollouts} times in the background. You'll be told the odds when it's done."
+        );
+    }
+
+    /// The odds `analyze` came back with, best first
+    fn show_analysis(&mut self, estimates: &[Estimate], rollouts: u32, now: u64) {
+        let Some(best) = estimates.first() else {
+            return;
+        };
+        let odds: Vec<String> = estimates
+            .iter()
+            .map(|e| {
+                format!(
+                    "{} {} (avg {})",
+                    command::describe_action(e.action, &self.game),
+                    locale::percent_tenths(e.permille().into()),
+                    locale::int(e.mean_score())
+                )
+            })
+            .collect();
+        self.game.message = format!(
+            "Survival odds over {rollouts} rollouts: {}",
+            odds.join(" · ")
+        );
+        self.toast = Some(Toast {
+            title: msg::ANALYSIS_TITLE,
+            text: format!(
+                "Best: {}, {}",
+                command::describe_action(best.action, &self.game),
+                best.summary()
+            ),
+            until_ms: now + TOAST_MS,
+        });
+    }
+
+    /// Something is moving on its own (a demo, a simulation, a toast, a gamepad to poll),
+    /// so ticks can't be skipped even in low power
+    pub fn busy(&self) -> bool {
+        #[cfg(feature = "gamepad")]
+        if self.gamepad.is_some() {
+            return true;
+        }
+        self.demo.is_some()
+            || self.batch.is_some()
+            || self.analysis.is_some()
+            || (self.speedrun.is_some() && self.game.play_clock.is_running())
+            || self.toast.is_some()
+            || self
+                .custom
+                .as_ref()
+                .and_then(|c| c.balance.as_ref())
+                .is_some_and(|b| b.pending.is_some())
+    }
+
+    /// Whether the run's play clock should be running. It counts whenever the board is in
+    /// front of the player and waiting on them: choosing a room, picking cards, the weapon
+    /// prompt and other acknowledgements, and the shop. It stops on the menus and GameOver,
+    /// under the quit dialog and rule explanations, and behind full-screen pages
+    /// (achievements, contrast audit, marathon summary, weekly briefing, custom game,
+    /// sandbox), and never runs for a demo.
+    pub fn clock_counts(&self) -> bool {
+        self.game.in_progress()
+            && self.demo.is_none()
+            && !self.confirming_quit
+            && self.lessons.is_empty()
+            && !self.show_achievements
+            && !self.show_log
+            && !self.show_contrast_audit
+            && self.marathon_summary.is_none()
+            && self.briefing.is_none()
+            && self.custom.is_none()
+            && self.sandbox.is_none()
+    }
+
+    /// Start or stop the play clock to match `clock_counts`
+    fn sync_clock(&mut self) {
+        let counts = self.clock_counts();
+        self.game.play_clock.set_running(counts, clock::now_ms());
+    }
+
+    /// Pick up a finished `simulate` or `analyze`, and take down a toast that's been up long enough
+    fn poll_batch(&mut self) {
+        let now = clock::now_ms();
+        if self.toast.as_ref().is_some_and(|t| t.until_ms <= now) {
+            self.toast = None;
+        }
+        if let Some(report) = self.batch.as_mut().and_then(Batch::poll) {
+            self.batch = None;
+            self.toast = Some(Toast {
+                title: msg::SIMULATION_TITLE,
+                text: report.summary(),
+                until_ms: now + TOAST_MS,
+            });
+        }
+        if let Some(estimates) = self.analysis.as_mut().and_then(Analysis::poll)
+            && let Some(analysis) = self.analysis.take()
+        {
+            if analysis.is_for(&self.game) {
+                self.show_analysis(&estimates, analysis.rollouts, now);
+            } else {
+                self.toast = Some(Toast {
+                    title: msg::ANALYSIS_TITLE,
+                    text: msg::ANALYSIS_STALE.to_string(),
+                    until_ms: now + TOAST_MS,
+                });
+            }
+        }
+    }
+
+    /// Enter on the custom game screen
+    fn submit_custom_command(&mut self) {
+        let raw = self.input.text().trim().to_string();
+        self.input.set_text("");
+        let Some(custom) = &mut self.custom else {
+            return;
+        };
+        if raw.is_empty() {
+            return;
+        }
+
+        let Some(command) = command::parse_custom(&raw) else {
+            custom.message = format!("'{raw}' isn't something this screen understands.");
+            return;
+        };
+        custom.message = match command {
+            CustomCommand::Set(key, value) => match custom.rules.set(&key, &value) {
+                Ok(()) => format!("{key} is now {value}."),
+                Err(e) => e,
+            },
+            CustomCommand::Code => format!(
+                "Share code: {}  (paste it into another custom game screen)",
+                presets::to_code(&custom.rules)
+            ),
+            CustomCommand::Import(code) => match presets::from_code(&code) {
+                Ok(rules) => {
+                    custom.rules = rules;
+                    "Loaded the rules from that code.".to_string()
+                }
+                Err(e) => e,
+            },
+            CustomCommand::Test => {
+                custom.balance = Some(Balance {
+                    rules: custom.rules.clone(),
+                    pending: Some(Batch::start(custom.rules.clone(), sim::GAMES)),
+                    report: None,
+                });
+                format!("Simulating {} bot games of these rules...", sim::GAMES)
+            }
+            CustomCommand::Reset => {
+                custom.rules = Ruleset::default();
+                "Back to the standard rules.".to_string()
+            }
+            CustomCommand::Load(name) => {
+                let found = self.presets.iter().flatten().find(|p| p.name == name);
+                match found {
+                    Some(preset) => {
+                        custom.rules = preset.rules.clone();
+                        format!("Loaded preset '{name}'.")
+                    }
+                    None => format!("No preset called '{name}'."),
+                }
+            }
+            CustomCommand::Save(name) if !presets::valid_name(&name) => {
+                msg::BAD_PRESET_NAME.to_string()
+            }
+            CustomCommand::Save(name) => {
+                let Some(list) = &mut self.presets else {
+                    custom.message = msg::PRESETS_UNAVAILABLE.to_string();
+                    return;
+                };
+                let preset = Preset {
+                    name: name.clone(),
+                    rules: custom.rules.clone(),
+                };
+                match list.iter_mut().find(|p| p.name == name) {
+                    Some(existing) => *existing = preset,
+                    None => list.push(preset),
+                }
+                match presets::save(self.storage.as_ref(), list) {
+                    Ok(()) => format!("Saved preset '{name}'."),
+                    Err(e) => format!("Couldn't save the preset: {e}"),
+                }
+            }
+            CustomCommand::Delete(name) => {
+                let Some(list) = &mut self.presets else {
+                    custom.message = msg::PRESETS_UNAVAILABLE.to_string();
+                    return;
+                };
+                let before = list.len();
+                list.retain(|p| p.name != name);
+                if list.len() == before {
+                    format!("No preset called '{name}'.")
+                } else {
+                    match presets::save(self.storage.as_ref(), list) {
+                        Ok(()) => format!("Deleted preset '{name}'."),
+                        Err(e) => format!("Couldn't save the presets: {e}"),
+                    }
+                }
+            }
+            CustomCommand::Play => {
+                self.game = Game::new(custom.rules.clone());
+                self.game.start();
+                self.game.message = format!("Custom game: {}", custom.rules.summary());
+                self.custom = None;
+                return;
+            }
+            CustomCommand::Back => {
+                self.custom = None;
+                return;
+            }
+        };
+    }
+
+    fn open_sandbox(&mut self) {
+        self.sandbox = Some(Sandbox {
+            setup: Setup::new(self.config.rules.clone()),
+            target: Target::Room,
+            message: msg::SANDBOX_HELP.to_string(),
+        });
+    }
+
+    /// Enter on the sandbox screen
+    fn submit_sandbox_command(&mut self) {
+        let raw = self.input.text().trim().to_string();
+        self.input.set_text("");
+        let Some(sandbox) = &mut self.sandbox else {
+            return;
+        };
+        if raw.is_empty() {
+            return;
+        }
+
+        let Some(command) = command::parse_sandbox(&raw) else {
+            sandbox.message = format!("'{raw}' isn't something this screen understands.");
+            return;
+        };
+        let setup = &mut sandbox.setup;
+        sandbox.message = match command {
+            SandboxCommand::Health(n) => {
+                let max = i32::from(setup.rules.max_health);
+                match n.parse::<i32>() {
+                    Ok(hp) if (1..=max).contains(&hp) => {
+                        setup.health = hp;
+                        format!("HP is now {hp}.")
+                    }
+                    _ => format!("HP has to be between 1 and {max}."),
+                }
+            }
+            SandboxCommand::Weapon(code, _) if code == "none" => {
+                setup.weapon = None;
+                setup.last_slain = None;
+                "Empty-handed.".to_string()
+            }
+            SandboxCommand::Weapon(code, last) => {
+                let last = match last.map(|l| l.parse::<u8>()) {
+                    None => Ok(None),
+                    Some(Ok(v)) if (2..=14).contains(&v) => Ok(Some(v)),
+                    Some(_) => Err("The last kill is a monster value, 2-14.".to_string()),
+                };
+                match (command::parse_cards(&[code]), last) {
+                    (Ok(cards), Ok(_)) if cards[0].suit != 'D' => {
+                        "Only diamonds can be wielded.".to_string()
+                    }
+                    (Ok(cards), Ok(last)) => {
+                        setup.remove(cards[0]);
+                        setup.weapon = Some(cards[0]);
+                        setup.last_slain = last;
+                        format!("Wielding {}.", card_text(cards[0]))
+                    }
+                    (Err(e), _) | (_, Err(e)) => e,
+                }
+            }
+            SandboxCommand::Room(codes) => match command::parse_cards(&codes) {
+                Ok(cards) if cards.len() > 4 => "A room holds at most 4 cards.".to_string(),
+                Ok(cards) => {
+                    cards.iter().for_each(|c| setup.remove(*c));
+                    setup.room = cards;
+                    "Room set.".to_string()
+                }
+                Err(e) => e,
+            },
+            SandboxCommand::Deck(codes) => match command::parse_cards(&codes) {
+                Ok(cards) => {
+                    cards.iter().for_each(|c| setup.remove(*c));
+                    setup.deck = cards;
+                    "Top of the deck set.".to_string()
+                }
+                Err(e) => e,
+            },
+            SandboxCommand::Remove(codes) => match command::parse_cards(&codes) {
+                Ok(cards) => {
+                    cards.iter().for_each(|c| setup.remove(*c));
+                    "Taken back out.".to_string()
+                }
+                Err(e) => e,
+            },
+            SandboxCommand::Fill(fill) => {
+                setup.fill = fill;
+                if fill {
+                    "The rest of the deck is shuffled in under your cards.".to_string()
+                } else {
+                    "Only the cards you placed are dealt.".to_string()
+                }
+            }
+            SandboxCommand::Pick(target) => {
+                sandbox.target = target;
+                format!("Clicked cards now go to {}.", target.label())
+            }
+            SandboxCommand::Clear => {
+                *setup = Setup::new(self.config.rules.clone());
+                "Cleared.".to_string()
+            }
+            SandboxCommand::Play => match self.game.play_setup(setup) {
+                Ok(()) => {
+                    self.game.message = msg::SANDBOX_STARTED.to_string();
+                    self.sandbox = None;
+                    return;
+                }
+                Err(e) => e,
+            },
+            SandboxCommand::Back => {
+                self.sandbox = None;
+                return;
+            }
+        };
+    }
+
+    /// A click in the sandbox card picker: take the card back, or place it
+    fn pick_sandbox_card(&mut self, card: Card) {
+        let Some(sandbox) = &mut self.sandbox else {
+            return;
+        };
+        let setup = &mut sandbox.setup;
+        sandbox.message = if setup.places(card) {
+            setup.remove(card);
+            format!("Took {} back out.", card_text(card))
+        } else {
+            match sandbox.target {
+                Target::Room if setup.room.len() >= 4 => "The room is full.".to_string(),
+                Target::Weapon if card.suit != 'D' => "Only diamonds can be wielded.".to_string(),
+                Target::Room => {
+                    setup.room.push(card);
+                    format!("{} goes in the room.", card_text(card))
+                }
+                Target::Deck => {
+                    setup.deck.push(card);
+                    format!("{} goes next in the deck.", card_text(card))
+                }
+                Target::Weapon => {
+                    setup.weapon = Some(card);
+                    setup.last_slain = None;
+                    format!("Wielding {}.", card_text(card))
+                }
+            }
+        };
+    }
+
+    /// `stop`: the unfinished run doesn't count
+    fn stop_marathon(&mut self) {
+        let Some(session) = self.marathon.take() else {
+            self.game.message = msg::NO_MARATHON.to_string();
+            return;
+        };
+
+        self.game = Game::new(self.config.rules.clone());
+        if session.runs.is_empty() {
+            self.game.message = msg::MARATHON_EMPTY.to_string();
+            return;
+        }
+
+        if let Some(board) = &mut self.marathon_board {
+            let entry = session.entry(clock::now_ms());
+            match marathon::record(self.storage.as_ref(), board, entry) {
+                Ok(()) => board.push(entry),
+                Err(e) => self.game.message = format!("Couldn't record the marathon: {e}"),
+            }
+        }
+        self.marathon_summary = Some(session);
+    }
+
+    /// The quit key, according to `quit_mode`. Returns whether the app should keep running.
+    fn request_quit(&mut self) -> bool {
+        if !self.game.in_progress() || self.demo.is_some() {
+            return false;
+        }
+
+        match self.config.quit_mode {
+            QuitMode::Instant => false,
+            QuitMode::SaveAndQuit => {
+                self.autosave();
+                false
+            }
+            QuitMode::Confirm => {
+                self.confirming_quit = true;
+                true
+            }
+        }
+    }
+
+    /// Answer the launch-time "Resume previous run?" prompt
+    fn answer_resume_prompt(&mut self, resume: bool) {
+        let loaded = if resume {
+            save::read(self.storage.as_ref(), save::AUTOSAVE)
+        } else {
+            Ok(None)
+        };
+        // The autosave is single-use either way
+        let _ = self.storage.remove(save::AUTOSAVE);
+
+        match loaded {
+            Ok(Some(game)) => {
+                self.game = game;
+                self.game.message = msg::RUN_RESUMED.to_string();
+            }
+            Ok(None) => {
+                self.game.state = GameState::MainMenu;
+                self.game.message = msg::AUTOSAVE_DISCARDED.to_string();
+            }
+            Err(e) => {
+                self.game.state = GameState::MainMenu;
+                self.game.message = format!("Couldn't resume: {e}");
+            }
+        }
+    }
+
+    /// Move typing between the notes panel and the command box
+    fn focus_notes(&mut self, focused: bool) {
+        self.notes_focused = focused && self.show_notes;
+        self.notes_input.set_focused(self.notes_focused);
+        self.input.set_focused(!self.notes_focused);
+    }
+
+    /// Keep the notes editor showing this run's notes, e.g. after a load or a new run
+    fn sync_notes(&mut self) {
+        if self.notes_focused {
+            self.game.notes = self.notes_input.text().to_string();
+        } else if self.notes_input.text() != self.game.notes {
+            self.notes_input.set_text(&self.game.notes);
+        }
+    }
+
+    fn set_last_command_feedback(&mut self, cmd: &str) {
+        self.game.last_command_feedback = format!("{}{}", msg::CMD_PREFIX, cmd);
     }
 }
 
 // ==============================
-// Draw
+// Update
 // ==============================
 
-pub fn draw(state: &mut AppState, window: &mut dyn Window) -> minui::Result<()> {
-    let (w, h) = window.get_size();
+pub fn update(state: &mut AppState, event: Event) -> bool {
+    // Example games play back the way they were recorded
+    if state.demo.is_none() {
+        state.game.acknowledge = state.config.acknowledge;
+    }
+    let keep_running = handle_event(state, event);
+    if let Some(timer) = &mut state.speedrun {
+        timer.tick(&state.game, clock::now_ms());
+    }
+    // One place to notice a run ending, however the last action arrived
+    state.record_finished_run();
+    state.continue_marathon();
+    state.take_lessons();
+    state.take_dead_weapon();
+    state.sync_notes();
+    state.poll_balance();
+    state.poll_batch();
+    state.sync_clock();
+    state
+        .publisher
+        .publish(&state.game, state.config.streamer_mode);
+    keep_running
+}
 
-    // New immediate-mode scene frame: clears registrations
-    state.ui.begin_frame();
+fn handle_event(state: &mut AppState, event: Event) -> bool {
+    if state.should_quit {
+        return false;
+    }
 
-    // Cursor is applied at end_frame
-    window.clear_cursor_request();
+    #[cfg(feature = "dev")]
+    if matches!(event, Event::Frame) {
+        state.poll_hot_reload();
+    }
 
-    // Clear full screen
-    if h > 0 && w > 0 {
-        window.clear_area(0, 0, h.saturating_sub(1), w.saturating_sub(1))?;
+    #[cfg(feature = "gamepad")]
+    if matches!(event, Event::Frame) {
+        let presses = state
+            .gamepad
+            .as_ref()
+            .map(Gamepad::drain)
+            .unwrap_or_default();
+        for button in presses {
+            gamepad_press(state, button);
+        }
+        if state.should_quit {
+            return false;
+        }
     }
 
-    // Root container (whole game UI)
-    let margin: u16 = 1;
-    let root_x = margin;
-    let root_y = margin;
-    let root_w = w.saturating_sub(margin * 2).max(1);
-    let root_h = h.saturating_sub(margin * 2).max(1);
+    let shortcut = state.config.keys.action_for(&event);
+    let quit_key = shortcut == Some(KeyAction::Quit);
 
-    let root_panel = Container::new()
-        .with_position_and_size(root_x, root_y, root_w, root_h)
-        .with_layout_direction(LayoutDirection::Vertical)
-        .with_border()
-        .with_border_chars(BorderChars::double_line())
-        .with_border_color(ColorPair::new(Color::White, Color::Transparent))
-        .with_title("Scoundrel")
-        .with_title_alignment(TitleAlignment::Center)
-        .with_padding(ContainerPadding::uniform(0));
-    root_panel.draw(window)?;
+    // The quit dialog swallows everything until it's answered (the quit key again counts as yes)
+    if state.confirming_quit {
+        match event.as_legacy_key_event() {
+            _ if quit_key => {
+                state.autosave();
+                return false;
+            }
+            Some(Event::Character('y' | 'Y')) | Some(Event::Enter) => {
+                state.autosave();
+                return false;
+            }
+            Some(Event::Character('n' | 'N')) | Some(Event::Escape) => {
+                state.confirming_quit = false;
+            }
+            _ => {}
+        }
+        return true;
+    }
 
-    // Inner content area (inside root border)
-    let inner_x = root_x + 1;
-    let inner_y = root_y + 1;
-    let inner_w = root_w.saturating_sub(2).max(1);
+    // Quit (Ctrl+Q unless rebound)
+    if quit_key {
+        return state.request_quit();
+    }
 
-    // Fixed panel heights (stable layout)
-    let status_h: u16 = 5;
-    let room_h: u16 = 6;
-    let msg_h: u16 = 5;
-    let cmd_h: u16 = 3;
+    // A rule explanation holds the game until it's been read
+    if !state.lessons.is_empty() {
+        if matches!(
+            event.as_legacy_key_event(),
+            Some(Event::Enter) | Some(Event::Escape)
+        ) {
+            state.lessons.remove(0);
+        }
+        return true;
+    }
+
+    // Watching an example game: it plays itself, typing pauses it for a note, Esc leaves
+    if let Some(demo) = &mut state.demo {
+        match event.as_legacy_key_event() {
+            Some(Event::Escape) => state.stop_demo(),
+            Some(Event::Enter) => state.submit_demo_note(),
+            _ if matches!(event, Event::Frame) => {
+                if state.input.text().is_empty() {
+                    demo.tick(&mut state.game);
+                }
+            }
+            _ => {
+                text::feed(&mut state.input, event);
+            }
+        }
+        return true;
+    }
+
+    // Weekly briefing: Enter begins the challenge, Esc backs out
+    if let Some(weekly) = &state.briefing {
+        match event.as_legacy_key_event() {
+            Some(Event::Enter) => {
+                state.game = weekly.game();
+                state.game.start();
+                state.game.message = msg::WEEKLY_STARTED.to_string();
+                state.briefing = None;
+            }
+            Some(Event::Escape) => state.briefing = None,
+            _ => {}
+        }
+        return true;
+    }
+
+    // The custom game screen takes over the keyboard until it's closed
+    if state.custom.is_some() {
+        match event.as_legacy_key_event() {
+            Some(Event::Escape) => state.custom = None,
+            Some(Event::Enter) => state.submit_custom_command(),
+            _ => {
+                text::feed(&mut state.input, event);
+            }
+        }
+        return true;
+    }
+
+    // Likewise the sandbox, plus clicks on its card picker
+    if state.sandbox.is_some() {
+        match event.as_legacy_key_event() {
+            Some(Event::Escape) => state.sandbox = None,
+            Some(Event::Enter) => state.submit_sandbox_command(),
+            _ => {
+                if let Event::MouseClick { x, y, .. } = event
+                    && let Some(id) = state.ui.hit_test_id(x, y)
+                    && let Some(card) = id
+                        .checked_sub(ID_PICKER)
+                        .and_then(|i| logic::full_deck().get(i).copied())
+                {
+                    state.pick_sandbox_card(card);
+                } else {
+                    text::feed(&mut state.input, event);
+                }
+            }
+        }
+        return true;
+    }
+
+    // The marathon summary stays up until dismissed
+    if state.marathon_summary.is_some() {
+        if matches!(
+            event.as_legacy_key_event(),
+            Some(Event::Enter | Event::Escape)
+        ) {
+            state.marathon_summary = None;
+        }
+        return true;
+    }
+
+    // Esc backs out of the contrast audit and the achievements
+    if state.show_contrast_audit && matches!(event.as_legacy_key_event(), Some(Event::Escape)) {
+        state.show_contrast_audit = false;
+        return true;
+    }
+    if state.show_achievements && matches!(event.as_legacy_key_event(), Some(Event::Escape)) {
+        state.show_achievements = false;
+        return true;
+    }
+    if state.show_log && matches!(event.as_legacy_key_event(), Some(Event::Escape)) {
+        state.show_log = false;
+        return true;
+    }
+
+    // The notes panel has the keyboard while focused; Enter, Esc or Tab hand it back
+    if state.notes_focused
+        && let Some(key) = event.as_legacy_key_event()
+    {
+        match key {
+            Event::Enter | Event::Escape | Event::Tab => state.focus_notes(false),
+            _ => {
+                text::feed(&mut state.notes_input, event);
+            }
+        }
+        return true;
+    }
+    // A paste isn't a key, so it needs sending to the notes separately
+    if state.notes_focused && matches!(event, Event::Paste(_)) {
+        text::feed(&mut state.notes_input, event);
+        return true;
+    }
+    if state.show_notes && matches!(event.as_legacy_key_event(), Some(Event::Tab)) {
+        state.focus_notes(true);
+        return true;
+    }
+
+    // Apply scene policies (focus/capture bookkeeping)
+    let _effects = state.ui.apply_policies(&event);
+
+    // Mouse events: click-to-focus input / click-to-select cards / drag selection in input
+    match event {
+        Event::MouseMove { x, y } => {
+            state.mouse_pos = (x, y);
+
+            // Check which card slots are being hovered
+            let hit = state.ui.hit_test_id(x, y);
+            for i in 0..4 {
+                let card_id = match i {
+                    0 => ID_CARD_1,
+                    1 => ID_CARD_2,
+                    2 => ID_CARD_3,
+                    _ => ID_CARD_4,
+                };
+
+                if hit == Some(card_id) {
+                    state.card_hovers[i].start_hover();
+                } else {
+                    state.card_hovers[i].end_hover();
+                }
+            }
+        }
+        Event::MouseClick { x, y, button: _ } => {
+            state.mouse_down = true;
+            state.dragging = false;
+
+            let hit = state.ui.hit_test_id(x, y);
+            match hit {
+                Some(ID_INPUT) => {
+                    state.focus_notes(false);
+                    state.input.click_set_cursor(x);
+                    return true;
+                }
+                Some(ID_NOTES) => {
+                    state.focus_notes(true);
+                    state.notes_input.click_set_cursor(x);
+                    return true;
+                }
+                Some(id) if (ID_CARD_1..=ID_CARD_4).contains(&id) => {
+                    // Only allow clicking cards when we're actually in the selection state.
+                    // If not, show state-appropriate guidance (avoid stale/incorrect MUST_FACE_FIRST).
+                    if state.game.state == GameState::CardSelection {
+                        // Played on release, so it can be dragged onto the weapon or HP bar
+                        state.drag_card = Some(id - ID_CARD_1);
+                    } else {
+                        state.game.message = match state.game.state {
+                            GameState::RoomChoice => msg::NEED_FACE_OR_SKIP.to_string(),
+                            GameState::CardInteraction => {
+                                if state.game.awaiting_weapon_choice {
+                                    msg::NEED_Y_OR_N.to_string()
+                                } else {
+                                    msg::HINT_INTERACTION_ACK.to_string()
+                                }
+                            }
+                            GameState::MainMenu => msg::NEED_START.to_string(),
+                            GameState::ResumePrompt => msg::NEED_Y_OR_N.to_string(),
+                            GameState::GameOver => msg::RESTART_HELP.to_string(),
+                            GameState::CardSelection => msg::NEED_SELECT_CARD.to_string(),
+                            GameState::Shop => msg::NEED_SHOP.to_string(),
+                            GameState::Trap => msg::HINT_TRAP.to_string(),
+                        };
+                    }
+                    return true;
+                }
+                _ => {
+                    // click outside: stop drag
+                    state.mouse_down = false;
+                    state.dragging = false;
+                    return true;
+                }
+            }
+        }
+        Event::MouseDrag { x, y, button: _ } => {
+            if !state.mouse_down {
+                return true;
+            }
+            state.dragging = true;
+            state.mouse_pos = (x, y);
+            if state.input.is_focused() {
+                state.input.drag_select_to(x);
+            }
+            return true;
+        }
+        Event::MouseRelease { x, y, button: _ } => {
+            if let Some(slot) = state.drag_card.take() {
+                drop_card(state, slot, x, y);
+            }
+            if state.mouse_down && state.dragging && state.input.is_focused() {
+                state.input.drag_select_to(x);
+            }
+            state.mouse_down = false;
+            state.dragging = false;
+            return true;
+        }
+        _ => {}
+    }
+
+    // Shortcut keys bound under [keys] run their command, once nothing else wants the key
+    if let Some(command) = shortcut.and_then(KeyAction::command) {
+        run_command(state, command);
+        return true;
+    }
+
+    // Enter submits the command (modifier-aware + legacy)
+    if let Event::KeyWithModifiers(k) = event
+        && matches!(k.key, KeyKind::Enter)
+    {
+        submit_command(state);
+        return true;
+    }
+    if matches!(event, Event::Enter) {
+        submit_command(state);
+        return true;
+    }
+
+    // Let TextInput consume typing/editing
+    if text::feed(&mut state.input, event) {
+        return true;
+    }
+
+    true
+}
+
+/// Let go of the card picked up from `slot`. Back on its own slot it's a plain click; a
+/// diamond dropped on the weapon line is equipped and a heart on the HP line is drunk.
+fn drop_card(state: &mut AppState, slot: usize, x: u16, y: u16) {
+    let Some(card) = state.game.room_slots[slot] else {
+        return;
+    };
+    match state.ui.hit_test_id(x, y) {
+        Some(id) if id == ID_CARD_1 + slot => {}
+        // Where a face-down card may go would give it away
+        Some(ID_WEAPON | ID_HEALTH) if state.game.is_hidden(slot) => {
+            state.game.message = msg::DROP_HIDDEN.to_string();
+            return;
+        }
+        Some(ID_WEAPON) if card.suit == 'D' => {}
+        Some(ID_HEALTH) if card.suit == 'H' => {}
+        Some(ID_WEAPON) => {
+            state.game.message = msg::DROP_ON_WEAPON.to_string();
+            return;
+        }
+        Some(ID_HEALTH) => {
+            state.game.message = msg::DROP_ON_HEALTH.to_string();
+            return;
+        }
+        _ => return,
+    }
+    let _ = state.game.press(Action::Play(slot));
+}
+
+/// Where the card being dragged can be dropped, if anywhere but back on its slot
+fn drop_target(state: &AppState) -> Option<InteractionId> {
+    // The suit shows even in the dark
+    let slot = state.drag_card?;
+    if state.game.is_hidden(slot) {
+        return None;
+    }
+    let card = state.game.room_slots[slot]?;
+    match card.suit {
+        'D' => Some(ID_WEAPON),
+        'H' => Some(ID_HEALTH),
+        _ => None,
+    }
+}
+
+fn submit_command(state: &mut AppState) {
+    let raw = state.input.text().trim().to_string();
+
+    // Empty Enter:
+    // - Only continues in CardInteraction when NOT awaiting weapon choice, or past a trap
+    // - Otherwise it's a no-op to avoid accidental actions
+    if raw.is_empty() {
+        state.input.set_text("");
+        if (state.game.state == GameState::CardInteraction && !state.game.awaiting_weapon_choice)
+            || state.game.state == GameState::Trap
+        {
+            let _ = state.game.apply(Action::Continue);
+        }
+        return;
+    }
+
+    let cmd = raw;
+    state.set_last_command_feedback(&cmd);
+    state.input.set_text("");
+
+    // Whatever comes after `banish N` answers it; only a yes lets the card go
+    if let Some(slot) = state.confirming_banish.take() {
+        if state.config.commands.means(Verb::Yes, &cmd) {
+            let _ = state.game.apply(Action::Banish(slot));
+        } else {
+            state.game.message = msg::BANISH_KEPT.to_string();
+        }
+        return;
+    }
+    if std::mem::take(&mut state.confirming_forge) {
+        if state.config.commands.means(Verb::Yes, &cmd) {
+            let _ = state.game.apply(Action::Buy(Ware::Forge));
+        } else {
+            state.game.message = msg::FORGE_KEPT.to_string();
+        }
+        return;
+    }
+
+    match command::parse(&cmd, &state.game, &state.config.commands) {
+        Some(command) => run_command(state, command),
+        None => {
+            // Ignore other commands during the acknowledgement step
+            if let Some(help) = unknown_command_help(&state.game) {
+                state.game.message = help.to_string();
+            }
+        }
+    }
+}
+
+/// Carry out a command, however it was entered (typed, gamepad)
+fn run_command(state: &mut AppState, command: Command) {
+    match command {
+        Command::Exit => {
+            state.autosave();
+            state.should_quit = true;
+            return;
+        }
+        // A marathon run can't be rerolled or swapped out
+        Command::Restart
+        | Command::Retry
+        | Command::Save
+        | Command::Load
+        | Command::Seed(Some(_))
+            if state.marathon.is_some() =>
+        {
+            state.game.message = msg::MARATHON_LOCKED.to_string();
+        }
+        Command::Restart => state.game.reset_to_playing(),
+        Command::Retry => state.retry_run(),
+        Command::Plus(relic) => {
+            if let Err(reason) = state.game.new_game_plus(relic) {
+                state.game.message = reason.to_string();
+            }
+        }
+        Command::Save => state.save_run(),
+        Command::Load => state.load_run(),
+        Command::Export => state.export_run(),
+        Command::Share => state.share_run(),
+        Command::Marathon => state.start_marathon(),
+        Command::Custom => state.open_custom_game(),
+        Command::Sandbox => state.open_sandbox(),
+        Command::Weekly => state.briefing = Some(Weekly::for_time(clock::now_ms())),
+        Command::Stop => state.stop_marathon(),
+        Command::Contrast => state.show_contrast_audit = !state.show_contrast_audit,
+        Command::Achievements => state.show_achievements = !state.show_achievements,
+        Command::Log => state.show_log = !state.show_log,
+        Command::Storage => state.show_storage(),
+        Command::Dismiss => state.dismiss_advice(),
+        Command::Prune => state.prune_storage(),
+        Command::Scores => state.show_scores = !state.show_scores,
+        Command::ScoresFor(query) => {
+            state.score_query = query;
+            state.show_scores = true;
+        }
+        Command::Tag(tag) => state.tag_run(tag),
+        Command::Ranks => state.show_ranks = !state.show_ranks,
+        Command::Preview => state.show_preview = !state.show_preview,
+        Command::Simulate(games) => state.start_batch(games),
+        Command::Analyze(rollouts) => state.start_analysis(rollouts),
+        Command::Discard => state.game.message = state.game.discard_summary(),
+        Command::Inventory => state.game.message = state.game.inventory_summary(),
+        Command::Hint => {
+            state.game.message = match hint::hint(&state.game) {
+                Some(hint) => format!(
+                    "Hint: {}. {}",
+                    command::describe_action(hint.action, &state.game),
+                    hint.reason
+                ),
+                None => msg::HINT_NOTHING.to_string(),
+            }
+        }
+        Command::Notes => {
+            state.show_notes = !state.show_notes;
+            state.focus_notes(state.show_notes);
+        }
+        Command::Seed(None) if state.config.streamer_mode => {
+            state.game.message = msg::SEED_HIDDEN.to_string()
+        }
+        Command::Seed(None) => {
+            state.game.message = format!(
+                "This dungeon's seed is {}. Start it again with 'seed {0}' or --seed {0}.",
+                state.game.seed
+            )
+        }
+        Command::Seed(Some(seed)) => {
+            if let Err(reason) = state.game.deal_seed(state.config.rules.clone(), seed) {
+                state.game.message = reason.to_string();
+            }
+        }
+        Command::Start => state.game.start(),
+        Command::Casual => {
+            // Same dungeon the menu was about to deal, so --seed still applies
+            state.game = Game::with_seed(state.config.rules.clone().casual(), state.game.seed);
+            state.game.start();
+            state.game.message = msg::CASUAL_STARTED.to_string();
+        }
+        Command::Endless => {
+            state.game.endless = true;
+            state.game.start();
+            state.game.message = msg::ENDLESS_STARTED.to_string();
+        }
+        Command::Target(n) => {
+            state.game.target = Some(n);
+            state.game.start();
+            state.game.message = format!("{} {n}.", msg::TARGET_STARTED);
+        }
+        Command::Ascend(level) => state.ascend(level),
+        Command::Mutators(words) => state.start_mutated(&words),
+        Command::Ironman => match state.game.make_ironman() {
+            Ok(()) => {
+                state.game.start();
+                state.game.message = msg::IRONMAN_STARTED.to_string();
+            }
+            Err(reason) => state.game.message = reason.to_string(),
+        },
+        Command::Watch(n) => state.watch_demo(n),
+        Command::Resume(resume) => state.answer_resume_prompt(resume),
+        Command::Banish(slot) => match state.game.illegal_reason(Action::Banish(slot)) {
+            Some(reason) => state.game.message = reason.to_string(),
+            None => {
+                let card = match state.game.visible_card(slot) {
+                    Some(card) => card_text(card),
+                    None if !state.game.is_hidden(slot) && state.game.is_dark(slot) => {
+                        match state.game.room_slots[slot] {
+                            Some(card) => format!("the dark {}", card_face(card, false)),
+                            None => "the dark card".to_string(),
+                        }
+                    }
+                    None => "the face-down card".to_string(),
+                };
+                state.game.message = format!("Banish {card} from the game for good? (y/n)");
+                state.confirming_banish = Some(slot);
+            }
+        },
+        Command::Forge => match state.game.illegal_reason(Action::Buy(Ware::Forge)) {
+            Some(reason) => state.game.message = reason.to_string(),
+            None => {
+                if let [Some(main), Some(off)] = state.game.loadout.hands
+                    && let Some(forged) = state.game.loadout.forged()
+                {
+                    state.game.message = format!(
+                        "Forge {} and {} into {} for {} gold? (y/n)",
+                        card_text(main.card),
+                        card_text(off.card),
+                        card_text(forged),
+                        Ware::Forge.price()
+                    );
+                    state.confirming_forge = true;
+                }
+            }
+        },
+        Command::Game(action) => {
+            let _ = state.game.apply(action);
+        }
+    }
+
+    state.game.settle_death();
+}
+
+// ==============================
+// Gamepad
+// ==============================
+
+/// Handle one button press. Dialogs and the example-game viewer get first say, the same
+/// way they swallow keys.
+#[cfg(feature = "gamepad")]
+fn gamepad_press(state: &mut AppState, button: Button) {
+    if state.confirming_quit {
+        match button {
+            Button::A => {
+                state.autosave();
+                state.should_quit = true;
+            }
+            Button::B => state.confirming_quit = false,
+            _ => {}
+        }
+        return;
+    }
+    if !state.lessons.is_empty() {
+        if matches!(button, Button::A | Button::B) {
+            state.lessons.remove(0);
+        }
+        return;
+    }
+    if state.demo.is_some() {
+        if matches!(button, Button::A | Button::B) {
+            state.stop_demo();
+        }
+        return;
+    }
+
+    match button {
+        Button::Left => move_card_focus(state, -1),
+        Button::Right => move_card_focus(state, 1),
+        _ => match gamepad_command(state, button) {
+            // Latched, so one bouncy press can't face the room and play a card too
+            Some(Command::Game(action)) => {
+                let _ = state.game.press(action);
+                state.game.settle_death();
+            }
+            Some(command) => run_command(state, command),
+            None => {}
+        },
+    }
+}
+
+/// A confirms whatever the screen is asking, B backs out or declines, X skips the room
+#[cfg(feature = "gamepad")]
+fn gamepad_command(state: &AppState, button: Button) -> Option<Command> {
+    let game = &state.game;
+    let command = match (button, game.state) {
+        (Button::B, _) if state.show_contrast_audit => Command::Contrast,
+        (Button::B, _) if state.show_achievements => Command::Achievements,
+        (Button::B, _) if state.show_log => Command::Log,
+        (Button::B, _) if state.show_scores => Command::Scores,
+
+        (Button::A, GameState::MainMenu) => Command::Start,
+        (Button::A, GameState::ResumePrompt) => Command::Resume(true),
+        (Button::B, GameState::ResumePrompt) => Command::Resume(false),
+        (Button::A, GameState::RoomChoice) => Command::Game(Action::Face),
+        (Button::X, GameState::RoomChoice) => Command::Game(Action::Skip),
+        (Button::A, GameState::CardSelection) => Command::Game(Action::Play(focused_slot(state)?)),
+        (Button::A, GameState::CardInteraction) if game.awaiting_weapon_choice => {
+            Command::Game(game.weapon_answer())
+        }
+        (Button::B, GameState::CardInteraction) if game.awaiting_weapon_choice => {
+            Command::Game(Action::Fight { weapon: None })
+        }
+        (Button::A, GameState::CardInteraction | GameState::Trap) => {
+            Command::Game(Action::Continue)
+        }
+        (Button::A, GameState::GameOver) => Command::Restart,
+        (Button::X, GameState::GameOver) => Command::Retry,
+        _ => return None,
+    };
+    Some(command)
+}
+
+/// The highlighted card, falling back to the leftmost one when its slot has been played
+fn focused_slot(state: &AppState) -> Option<usize> {
+    let focus = state.focused_card?;
+    let occupied = |i: usize| state.game.room_slots[i].is_some();
+    if occupied(focus) {
+        Some(focus)
+    } else {
+        (0..4).find(|&i| occupied(i))
+    }
+}
+
+/// Step the focused card left or right, skipping empty slots
+#[cfg(feature = "gamepad")]
+fn move_card_focus(state: &mut AppState, step: isize) {
+    let occupied: Vec<usize> = (0..4)
+        .filter(|&i| state.game.room_slots[i].is_some())
+        .collect();
+    if occupied.is_empty() {
+        return;
+    }
+
+    let next = match focused_slot(state).and_then(|f| occupied.iter().position(|&i| i == f)) {
+        Some(pos) => (pos as isize + step).rem_euclid(occupied.len() as isize) as usize,
+        None => 0,
+    };
+    state.focused_card = Some(occupied[next]);
+}
+
+/// What to tell the player when their input isn't a command on this screen
+fn unknown_command_help(game: &Game) -> Option<&'static str> {
+    let help = match game.state {
+        GameState::MainMenu => msg::NEED_START,
+        GameState::ResumePrompt => msg::NEED_Y_OR_N,
+        GameState::RoomChoice if game.legal_actions().contains(&Action::Skip) => {
+            msg::NEED_FACE_OR_SKIP
+        }
+        GameState::RoomChoice => msg::NEED_FACE_ONLY,
+        GameState::CardSelection => msg::NEED_SELECT_CARD,
+        GameState::CardInteraction if game.awaiting_weapon_choice => msg::NEED_Y_OR_N,
+        GameState::CardInteraction | GameState::Trap => return None,
+        GameState::Shop => msg::NEED_SHOP,
+        GameState::GameOver => msg::RESTART_HELP,
+    };
+    Some(help)
+}
+
+// ==============================
+// Draw
+// ==============================
+
+pub fn draw(state: &mut AppState, window: &mut dyn Window) -> minui::Result<()> {
+    let (w, h) = window.get_size();
+    let theme = state.config.theme.clone();
+    let glyphs = state.config.glyphs;
+
+    // New immediate-mode scene frame: clears registrations
+    state.ui.begin_frame();
+
+    // Cursor is applied at end_frame
+    window.clear_cursor_request();
+
+    // Clear full screen
+    if h > 0 && w > 0 {
+        window.clear_area(0, 0, h.saturating_sub(1), w.saturating_sub(1))?;
+    }
+
+    if let Some(session) = &state.marathon_summary {
+        draw_marathon_summary(window, session, state.marathon_board.as_deref(), h)?;
+        return finish_frame(state, window, &theme, glyphs, w);
+    }
+
+    if let Some(weekly) = &state.briefing {
+        draw_weekly_briefing(window, weekly, state.config.streamer_mode)?;
+        return finish_frame(state, window, &theme, glyphs, w);
+    }
+
+    if state.custom.is_some() {
+        draw_custom_game(window, state, w, h)?;
+        return finish_frame(state, window, &theme, glyphs, w);
+    }
+
+    if state.sandbox.is_some() {
+        draw_sandbox(window, state, w, h)?;
+        return finish_frame(state, window, &theme, glyphs, w);
+    }
+
+    if state.show_contrast_audit {
+        draw_contrast_audit(window, w, h, &state.config)?;
+        return finish_frame(state, window, &theme, glyphs, w);
+    }
+
+    if state.show_achievements {
+        draw_achievements(window, state.profile.as_ref(), w)?;
+        return finish_frame(state, window, &theme, glyphs, w);
+    }
+
+    if state.show_log {
+        draw_move_log(window, &state.game, h)?;
+        return finish_frame(state, window, &theme, glyphs, w);
+    }
+
+    if state.game.state == GameState::Shop {
+        draw_shop(window, state, w, h)?;
+        return finish_frame(state, window, &theme, glyphs, w);
+    }
+
+    // Per-panel painters (no-ops unless solid backgrounds are on)
+    let bg = theme.backgrounds;
+    let solid = state.config.solid_backgrounds;
+    let root_paint = Paint::new(solid, bg.root);
+    let status_paint = Paint::new(solid, bg.status);
+    let room_paint = Paint::new(solid, bg.room);
+    let msg_paint = Paint::new(solid, bg.message);
+    let cmd_paint = Paint::new(solid, bg.command);
+
+    // The margin around the root border too, so nothing at all shows through
+    if solid {
+        root_paint
+            .fill(Container::new().with_position_and_size(0, 0, w, h))
+            .draw(window)?;
+    }
+
+    // Root container (whole game UI)
+    let margin: u16 = 1;
+    let root_x = margin;
+    let root_y = margin;
+    let root_w = w.saturating_sub(margin * 2).max(1);
+    let root_h = h.saturating_sub(margin * 2).max(1);
+
+    let mut title = match &state.marathon {
+        Some(session) => format!(
+            "Scoundrel · Marathon run {} · total {}",
+            session.runs.len() + 1,
+            session.total()
+        ),
+        None if scores::variant(&state.game, false) == "weekly" => {
+            "Scoundrel · Weekly challenge".to_string()
+        }
+        None if !state.game.mutators.is_empty() => format!(
+            "Scoundrel · Mutators {}",
+            presets::mutator_code(&state.game.mutators)
+        ),
+        None => "Scoundrel".to_string(),
+    };
+    if state.game.in_progress() && state.game.ironman {
+        title += " · Ironman";
+    }
+    if state.game.plus > 0 {
+        title += &format!(" · New Game+ {}", state.game.plus);
+    }
+    if state.game.in_progress() && state.game.floor_count() > 1 {
+        title += &format!(" · Floor {}/{}", state.game.floor, state.game.floor_count());
+    }
+    if let Some(batch) = &state.batch {
+        title += &format!(
+            " · simulating {}",
+            locale::percent(u64::from(batch.progress()) * 100 / u64::from(batch.games))
+        );
+    }
+    if let Some(analysis) = &state.analysis {
+        title += &format!(
+            " · analyzing {}",
+            locale::percent(analysis.percent().into())
+        );
+    }
+    let root_panel = root_paint
+        .fill(Container::new())
+        .with_position_and_size(root_x, root_y, root_w, root_h)
+        .with_layout_direction(LayoutDirection::Vertical)
+        .with_border()
+        .with_border_chars(theme.frames.root.border.chars(glyphs))
+        .with_border_color(root_paint.on(theme.root_border))
+        .with_title(&title)
+        .with_title_alignment(theme.frames.root.title)
+        .with_padding(ContainerPadding::uniform(0));
+    root_panel.draw(window)?;
+
+    // Inner content area (inside root border)
+    let inner_x = root_x + 1;
+    let inner_y = root_y + 1;
+    let inner_w = root_w.saturating_sub(2).max(1);
+
+    // Fixed panel heights (stable layout)
+    let status_h: u16 = 5;
+    let room_h: u16 = 6;
+    let msg_h: u16 = 5;
+    let cmd_h: u16 = 3;
+
+    // Shared geometry
+    let content_x = inner_x + 1;
+
+    // ==============================
+    // Status panel
+    // ==============================
+    let status_y = inner_y;
+
+    status_paint
+        .fill(Container::new())
+        .with_position_and_size(inner_x, status_y, inner_w, status_h)
+        .with_layout_direction(LayoutDirection::Vertical)
+        .with_border()
+        .with_border_chars(theme.frames.status.border.chars(glyphs))
+        .with_border_color(status_paint.on(theme.panel_border))
+        .with_title(status_title(state))
+        .with_title_alignment(theme.frames.status.title)
+        .with_padding(ContainerPadding::uniform(0))
+        .draw(window)?;
+
+    // A card being dragged lights up the line it can be dropped on, more so under the mouse
+    let target = drop_target(state);
+    let (mouse_x, mouse_y) = state.mouse_pos;
+    let drop_color = |id, row: u16, text: &str, normal| match target {
+        Some(t) if t == id => {
+            let under_mouse = mouse_y == row
+                && (content_x..content_x + text.chars().count() as u16).contains(&mouse_x);
+            if under_mouse {
+                theme.badge
+            } else {
+                theme.emphasis
+            }
+        }
+        _ => normal,
+    };
+
+    // Health line + color
+    let hp_line = health_line(state.game.health, state.game.max_health);
+    window.write_str_colored(
+        status_y + 1,
+        content_x,
+        &hp_line,
+        status_paint.on(drop_color(
+            ID_HEALTH,
+            status_y + 1,
+            &hp_line,
+            health_color(state.game.health, &theme),
+        )),
+    )?;
+    state.ui.cache_mut().register(
+        ID_HEALTH,
+        WidgetArea {
+            x: content_x,
+            y: status_y + 1,
+            width: hp_line.chars().count() as u16,
+            height: 1,
+        },
+    );
+
+    // Afflictions in a row after the bar
+    let afflictions: Vec<String> = state
+        .game
+        .status_effects
+        .iter()
+        .map(|e| match (e.affliction, e.cards_left) {
+            (Affliction::Poison, Some(n)) => format!("{} Poisoned ({n})", glyphs.poison),
+            (Affliction::Poison, None) => format!("{} Poisoned", glyphs.poison),
+            (Affliction::Bleed, _) => format!("{} Bleeding", glyphs.bleed),
+        })
+        .collect();
+    if !afflictions.is_empty() {
+        window.write_str_colored(
+            status_y + 1,
+            content_x + hp_line.chars().count() as u16 + 2,
+            &afflictions.join("  "),
+            status_paint.on(theme.hp_low),
+        )?;
+    }
+
+    // What the card in view or the weapon prompt would cost, hatched over the end of the bar
+    if let Some(dmg) = damage_forecast(state) {
+        let hp = state.game.health.clamp(0, state.game.max_health);
+        let loss = dmg.min(hp);
+        let bar_x = format!("Health: {}/{} |", state.game.health, state.game.max_health)
+            .chars()
+            .count() as i32;
+        window.write_str_colored(
+            status_y + 1,
+            content_x + (bar_x + hp - loss) as u16,
+            &glyphs.bar_forecast.repeat(loss as usize),
+            status_paint.on(theme.hp_low),
+        )?;
+    }
+
+    // Weapon + deck lines. A weapon nothing left is weak enough for is tagged, and the
+    // line dims once that's every weapon held.
+    let dead_tag = |hand| {
+        if state.game.dead_weight(hand) {
+            msg::DEAD_WEIGHT_TAG
+        } else {
+            ""
+        }
+    };
+    let mut weapon = weapon_line(
+        state.game.weapon(),
+        state.game.last_slain(),
+        state.game.weapon_name().as_deref(),
+    ) + dead_tag(0);
+    if let Some(off) = state.game.loadout.off() {
+        let off = weapon_line(
+            Some(off.card),
+            off.last_slain,
+            state.game.hand_name(1).as_deref(),
+        );
+        weapon += &format!("  ·  Off hand:{}", off.trim_start_matches("Weapon:"));
+        weapon += dead_tag(1);
+    }
+    if let Some(armor) = state.game.armor {
+        weapon += &format!("  ·  {}", armor_line(armor));
+    }
+    let all_dead = state.game.weapon().is_some()
+        && (0..2)
+            .all(|hand| state.game.loadout.hands[hand].is_none() || state.game.dead_weight(hand));
+    let weapon_color = if all_dead { theme.muted } else { theme.text };
+    window.write_str_colored(
+        status_y + 2,
+        content_x,
+        &weapon,
+        status_paint.on(drop_color(ID_WEAPON, status_y + 2, &weapon, weapon_color)),
+    )?;
+    state.ui.cache_mut().register(
+        ID_WEAPON,
+        WidgetArea {
+            x: content_x,
+            y: status_y + 2,
+            width: weapon.chars().count() as u16,
+            height: 1,
+        },
+    );
+
+    let mut deck_line = format!(
+        "Cards left in Dungeon: {}  Discard: {}",
+        state.game.deck.len(),
+        state.game.discard.len()
+    );
+    if state.game.rules.discard_view != DiscardView::Hidden
+        && let Some(&top) = state.game.discard.last()
+    {
+        deck_line += &format!(" (top {})", card_text(top));
+    }
+    if state.game.endless {
+        deck_line += &format!("  Lap {}", state.game.laps + 1);
+    }
+    if !state.game.treasures.is_empty() {
+        // Where the ones still out there are is the whole game, so name them
+        let left: Vec<String> = state
+            .game
+            .treasures
+            .iter()
+            .filter(|t| !state.game.treasures_found.contains(t))
+            .map(|t| card_text(*t))
+            .collect();
+        deck_line += &format!(
+            "  {} {}/{} {}",
+            glyphs.treasure,
+            state.game.treasures_found.len(),
+            state.game.treasures.len(),
+            left.join(" ")
+        );
+        if state.game.rules.treasure_score {
+            deck_line += &format!(" (+{})", state.game.treasure_bonus);
+        }
+    }
+    if state.game.earns_gold() {
+        deck_line += &format!("  Gold {}", state.game.gold);
+    }
+    if state.game.skip_tokens > 0 {
+        deck_line += &format!("  Skip tokens {}", state.game.skip_tokens);
+    }
+    window.write_str_colored(
+        status_y + 3,
+        content_x,
+        &deck_line,
+        status_paint.on(theme.text),
+    )?;
+
+    // Seed in the top right, so a run can be shared or replayed (but not on stream)
+    if !state.config.streamer_mode {
+        let seed = format!("Seed {}", state.game.seed);
+        window.write_str_colored(
+            status_y + 1,
+            (inner_x + inner_w).saturating_sub(seed.chars().count() as u16 + 2),
+            &seed,
+            status_paint.on(theme.muted),
+        )?;
+    }
+
+    // The turn, and which skip rule is in force, under the seed
+    if state.game.in_progress() {
+        // A card still being resolved is the turn in play, not the one after
+        let turn = state.game.turns() + usize::from(state.game.state != GameState::CardInteraction);
+        let mut skips = format!("Turn {turn} · Skips: {}", state.game.rules.skips.label());
+        if state.game.rules.skips == SkipPolicy::Once && state.game.has_skipped() {
+            skips += " (used)";
+        }
+        window.write_str_colored(
+            status_y + 2,
+            (inner_x + inner_w).saturating_sub(skips.chars().count() as u16 + 2),
+            &skips,
+            status_paint.on(theme.muted),
+        )?;
+    }
+
+    // Move-limit challenge: the count that matters most goes where it can't be missed
+    if state.game.in_progress()
+        && let Some(left) = state.game.moves_left()
+    {
+        let moves = format!("{left} moves left");
+        let color = if left <= 5 {
+            theme.hp_low
+        } else {
+            theme.emphasis
+        };
+        window.write_str_colored(
+            status_y + 3,
+            (inner_x + inner_w).saturating_sub(moves.chars().count() as u16 + 2),
+            &moves,
+            status_paint.on(color),
+        )?;
+    }
+
+    // Consumables sit at the end of the deck line
+    if state.game.reshuffles_left > 0 {
+        let icon = reshuffle_badge(state.game.reshuffles_left);
+        window.write_str_colored(
+            status_y + 3,
+            content_x + deck_line.chars().count() as u16 + 3,
+            &icon,
+            status_paint.on(theme.badge),
+        )?;
+    }
+
+    // ==============================
+    // Dungeon room panel
+    // ==============================
+    let room_y = status_y + status_h + 1;
+
+    // The panel takes on the depth of the run; outside a run it's just the room
+    let (room_title, room_border) = if state.game.in_progress() {
+        let depth = state.game.depth();
+        let border = match depth {
+            Depth::UpperHalls => theme.room_border,
+            Depth::Catacombs => theme.catacombs_border,
+            Depth::TheDepths => theme.depths_border,
+        };
+        (depth.name(), border)
+    } else {
+        ("Dungeon Room", theme.room_border)
+    };
+
+    room_paint
+        .fill(Container::new())
+        .with_position_and_size(inner_x, room_y, inner_w, room_h)
+        .with_layout_direction(LayoutDirection::Vertical)
+        .with_border()
+        .with_border_chars(theme.frames.room.border.chars(glyphs))
+        .with_border_color(room_paint.on(room_border))
+        .with_title(room_title)
+        .with_title_alignment(theme.frames.room.title)
+        .with_padding(ContainerPadding::uniform(0))
+        .draw(window)?;
+
+    // Cards (stable slots)
+    let card_area_x = inner_x + 1;
+    let card_area_y = room_y + 1;
+
+    let card_w: u16 = ((inner_w.saturating_sub(5)) / 4).max(10);
+    let card_h: u16 = 3;
+    let gap: u16 = 1;
+
+    // Only while there's a choice to make
+    let ranks = if state.show_ranks && state.game.in_progress() {
+        advisor::room_ranks(&state.game)
+    } else {
+        [None; 4]
+    };
+
+    for (i, rank) in ranks.into_iter().enumerate() {
+        let x = card_area_x + (card_w + gap) * (i as u16);
+        let y0 = card_area_y;
+
+        let id = match i {
+            0 => ID_CARD_1,
+            1 => ID_CARD_2,
+            2 => ID_CARD_3,
+            _ => ID_CARD_4,
+        };
+
+        let focused =
+            focused_slot(state) == Some(i) && state.game.state == GameState::CardSelection;
+        let boss = state.game.visible_card(i).and_then(Boss::of);
+        let (border_chars, border_color) = if focused {
+            (glyphs.double_border, theme.emphasis)
+        } else if boss.is_some() {
+            (glyphs.double_border, theme.hp_low)
+        } else {
+            (glyphs.single_border, theme.panel_border)
+        };
+
+        room_paint
+            .fill(Container::new())
+            .with_position_and_size(x, y0, card_w, card_h)
+            .with_layout_direction(LayoutDirection::Vertical)
+            .with_border()
+            .with_border_chars(border_chars)
+            .with_border_color(room_paint.on(border_color))
+            .with_padding(ContainerPadding::uniform(0))
+            .draw(window)?;
+
+        let (label, colors) = match state.game.room_slots[i] {
+            Some(_) if state.game.is_hidden(i) => (
+                format!("[{}] {} face down", i + 1, glyphs.card_back),
+                theme.muted,
+            ),
+            Some(c) if state.game.is_dark(i) => (
+                format!("[{}] {} dark", i + 1, card_face(c, false)),
+                card_color(c, &theme),
+            ),
+            Some(c) if let Some(boss) = boss => (
+                format!(
+                    "[{}] {} {} {}",
+                    i + 1,
+                    glyphs.boss,
+                    card_text(c),
+                    boss.name()
+                ),
+                card_color(c, &theme),
+            ),
+            Some(c) if state.game.is_treasure(c) => (
+                format!("[{}] {} {}", i + 1, card_text(c), glyphs.treasure),
+                card_color(c, &theme),
+            ),
+            Some(c) if state.game.traps.contains(&c) => (
+                format!("[{}] {} {}", i + 1, card_text(c), glyphs.trap),
+                card_color(c, &theme),
+            ),
+            Some(c) if state.game.merchants.contains(&c) => (
+                format!("[{}] {} {}", i + 1, card_text(c), glyphs.merchant),
+                card_color(c, &theme),
+            ),
+            Some(c) => (
+                format!("[{}] {}", i + 1, card_text(c)),
+                card_color(c, &theme),
+            ),
+            None => ("[ ] empty".to_string(), theme.empty_slot),
+        };
+
+        window.write_str_colored(y0 + 1, x + 1, &label, room_paint.on(colors))?;
+        // A special monster says what it does along the bottom edge, before it's fought
+        let mut ability_w = 0;
+        if let Some(ability) = state
+            .game
+            .visible_card(i)
+            .and_then(|c| state.game.ability_of(c))
+        {
+            let tag = clip(
+                &format!(" {} ", ability.tag()),
+                usize::from(card_w.saturating_sub(4)),
+            );
+            ability_w = tag.chars().count() as u16;
+            window.write_str_colored(y0 + card_h - 1, x + 2, &tag, room_paint.on(theme.hp_low))?;
+        }
+        // `preview`: what picking it would do to your health, at the other end of that edge
+        if state.show_preview
+            && let Some(delta) = state.game.pick_hp_delta(i)
+        {
+            let tag = format!(" {} ", hp_delta_tag(&delta));
+            let tag_x = (x + card_w).saturating_sub(tag.chars().count() as u16 + 2);
+            let color = match delta {
+                HpDelta::Fixed(hp) if hp > 0 => theme.hp_high,
+                HpDelta::Fixed(0) => theme.muted,
+                _ => theme.hp_low,
+            };
+            // Dropped when it would run into the ability tag
+            if tag_x > x + 2 + ability_w {
+                window.write_str_colored(y0 + card_h - 1, tag_x, &tag, room_paint.on(color))?;
+            }
+        }
+        if let Some(rank) = rank {
+            let tag = rank.tag();
+            let tag_x = (x + card_w).saturating_sub(tag.len() as u16 + 2);
+            // Dropped when the card is too narrow to fit it after the label
+            if tag_x > x + 1 + label.chars().count() as u16 {
+                window.write_str_colored(y0 + 1, tag_x, &tag, room_paint.on(theme.muted))?;
+            }
+        }
+
+        // Click hitbox
+        state.ui.cache_mut().register(
+            id,
+            WidgetArea {
+                x,
+                y: y0,
+                width: card_w,
+                height: card_h,
+            },
+        );
+    }
+
+    // Room footer
+    let mut footer = match state.game.state {
+        GameState::CardSelection => Some(format!(
+            "Interactions left in this room: {}",
+            state.game.interactions_left_in_room
+        )),
+        GameState::MainMenu | GameState::GameOver => state.profile.as_ref().map(|p| p.summary()),
+        _ => None,
+    };
+
+    // `bosses`: fair warning, a room ahead
+    let mut footer_color = theme.muted;
+    if state.game.in_progress()
+        && let Some(boss) = state.game.boss_ahead()
+    {
+        let warning = format!(
+            "{} Next room: the {} ({}), {}",
+            glyphs.boss,
+            boss.name(),
+            boss.card().value,
+            boss.twist()
+        );
+        footer = Some(match footer {
+            Some(f) => format!("{f}  ·  {warning}"),
+            None => warning,
+        });
+        footer_color = theme.hp_low;
+    }
+
+    if let Some(footer) = footer {
+        window.write_str_colored(room_y + 4, content_x, &footer, room_paint.on(footer_color))?;
+    }
+
+    // ==============================
+    // Message panel (hint + message + previous input / score)
+    // ==============================
+    let msg_y = room_y + room_h + 1;
+
+    msg_paint
+        .fill(Container::new())
+        .with_position_and_size(inner_x, msg_y, inner_w, msg_h)
+        .with_layout_direction(LayoutDirection::Vertical)
+        .with_border()
+        .with_border_chars(theme.frames.message.border.chars(glyphs))
+        .with_border_color(msg_paint.on(theme.panel_border))
+        .with_title("Message")
+        .with_title_alignment(theme.frames.message.title)
+        .with_padding(ContainerPadding::uniform(0))
+        .draw(window)?;
+
+    // Hint line in message box, which the pace coach takes over at room choices
+    let hint = if state.game.state == GameState::MainMenu && state.has_save {
+        msg::HINT_MAIN_CAN_LOAD.to_string()
+    } else if let Some(nudge) = coach::nudge(&state.game) {
+        nudge
+    } else {
+        state_hint(&state.game).to_string()
+    };
+    window.write_str_colored(msg_y + 1, content_x, &hint, msg_paint.on(theme.muted))?;
+
+    let message = if state.game.message.is_empty() {
+        match state.game.state {
+            GameState::MainMenu => "Welcome, Scoundrel.".to_string(),
+            GameState::ResumePrompt => msg::AUTOSAVE_FOUND.to_string(),
+            GameState::RoomChoice => msg::NEED_FACE_OR_SKIP.to_string(),
+            GameState::CardSelection => "Choose a card.".to_string(),
+            GameState::CardInteraction => {
+                if state.game.awaiting_weapon_choice {
+                    msg::NEED_Y_OR_N.to_string()
+                } else {
+                    msg::HINT_INTERACTION_ACK.to_string()
+                }
+            }
+            GameState::Shop => msg::NEED_SHOP.to_string(),
+            GameState::Trap => msg::HINT_TRAP.to_string(),
+            GameState::GameOver => state.game.remaining_summary_line(),
+        }
+    } else {
+        state.game.message.clone()
+    };
+
+    window.write_str_colored(msg_y + 2, content_x, &message, msg_paint.on(theme.text))?;
+
+    // Previous input / score line directly under message (no extra blank line)
+    if state.game.state == GameState::GameOver {
+        let mut score_line = match state.game.score_breakdown() {
+            Some(parts) => format!(
+                "FINAL SCORE: {} ({parts})",
+                locale::int(state.game.final_score().into())
+            ),
+            None => format!(
+                "FINAL SCORE: {}",
+                locale::int(state.game.final_score().into())
+            ),
+        };
+        if let Some(name) = state.game.weapon_name() {
+            score_line.push_str(&format!("  ·  wielding {name}"));
+        }
+        if state.game.ironman {
+            score_line.push_str("  ·  ironman");
+        }
+        if state.game.ascension > 0 {
+            score_line.push_str(&format!("  ·  ascension {}", state.game.ascension));
+        }
+        if !state.game.mutators.is_empty() {
+            let code = presets::mutator_code(&state.game.mutators);
+            score_line.push_str(&format!("  ·  mutators {code}"));
+        }
+        if let Some(target) = state.game.target {
+            let verdict = if state.game.final_score() >= target {
+                "hit"
+            } else {
+                "missed"
+            };
+            score_line.push_str(&format!("  ·  target {target} {verdict}"));
+        }
+        if let Some(card) = state.game.banished {
+            score_line.push_str(&format!("  ·  banished {}", card_text(card)));
+        }
+        if state.game.attempt > 1 {
+            score_line.push_str(&format!("  ·  attempt {}", state.game.attempt));
+        }
+        window.write_str_colored(
+            msg_y + 3,
+            content_x,
+            &score_line,
+            msg_paint.on(theme.emphasis),
+        )?;
+    } else if state.config.tooltips == TooltipMode::Message
+        && let Some(text) = hovered_tooltip(state)
+    {
+        let text = clip(&text, usize::from(inner_w.saturating_sub(2)));
+        window.write_str_colored(msg_y + 3, content_x, &text, msg_paint.on(theme.tooltip))?;
+    } else if state.game.state == GameState::MainMenu
+        && let Some(advice) = state.advice()
+    {
+        let advice = clip(&advice, usize::from(inner_w.saturating_sub(2)));
+        window.write_str_colored(msg_y + 3, content_x, &advice, msg_paint.on(theme.emphasis))?;
+    } else if !state.game.last_command_feedback.is_empty() {
+        window.write_str_colored(
+            msg_y + 3,
+            content_x,
+            &state.game.last_command_feedback,
+            msg_paint.on(theme.muted),
+        )?;
+    }
+
+    // ==============================
+    // Command panel + TextInput
+    // ==============================
+    let cmd_y = msg_y + msg_h + 1;
+
+    cmd_paint
+        .fill(Container::new())
+        .with_position_and_size(inner_x, cmd_y, inner_w, cmd_h)
+        .with_layout_direction(LayoutDirection::Vertical)
+        .with_border()
+        .with_border_chars(theme.frames.command.border.chars(glyphs))
+        .with_border_color(cmd_paint.on(theme.command_border))
+        .with_title("Command")
+        .with_title_alignment(theme.frames.command.title)
+        .with_padding(ContainerPadding::uniform(0))
+        .draw(window)?;
+
+    let input_x = content_x;
+    let input_y = cmd_y + 1;
+    let input_w = inner_w.saturating_sub(2).max(10);
+
+    let mut input_widget = TextInput::new()
+        .with_position(input_x, input_y)
+        .with_width(input_w)
+        .with_border(true)
+        .with_placeholder(command_placeholder(
+            &state.game,
+            state.has_save,
+            &state.config.commands,
+        ));
+    if solid {
+        // TextInput's defaults all use transparent backgrounds
+        input_widget = input_widget
+            .with_text_color(cmd_paint.on(theme.text))
+            .with_placeholder_color(cmd_paint.on(theme.muted))
+            .with_border_color(cmd_paint.on(theme.command_border));
+    }
+
+    input_widget.draw_with_id(window, &mut state.input, state.ui.cache_mut(), ID_INPUT)?;
+
+    // Live interpretation of what Enter would do, right under the command box
+    // (or the viewer's status while an example game plays)
+    let preview = match &state.demo {
+        Some(demo) if !state.input.text().is_empty() => Some(format!(
+            "▶ {} · {} {}",
+            demo.replay.title,
+            msg::DEMO_NOTE_PAUSED,
+            demo.step().unwrap_or(0)
+        )),
+        Some(demo) if demo.finished() => Some(format!("▶ {}", msg::DEMO_OVER)),
+        Some(demo) => Some(format!("▶ {} · {}", demo.replay.title, msg::DEMO_PLAYING)),
+        None => command::preview(state.input.text(), &state.game, &state.config.commands),
+    };
+    if let Some(preview) = preview {
+        window.write_str_colored(
+            cmd_y + cmd_h,
+            content_x,
+            &preview,
+            root_paint.on(theme.muted),
+        )?;
+    }
+
+    // ==============================
+    // High scores (GameOver, or on request)
+    // ==============================
+    let scores_y = cmd_y + cmd_h + 1;
+    let scores_h = (scores::TOP_N as u16 + 2).min((root_y + root_h - 1).saturating_sub(scores_y));
+
+    let show_scores = state.game.state == GameState::GameOver || state.show_scores;
+    if show_scores && scores_h >= 3 {
+        let scores_title = if state.score_query.is_empty() {
+            msg::SCORES_TITLE.to_string()
+        } else {
+            format!("{} · {}", msg::SCORES_TITLE, state.score_query.describe())
+        };
+        status_paint
+            .fill(Container::new())
+            .with_position_and_size(inner_x, scores_y, inner_w, scores_h)
+            .with_border()
+            .with_border_chars(theme.frames.side.border.chars(glyphs))
+            .with_border_color(status_paint.on(theme.panel_border))
+            .with_title(&scores_title)
+            .with_title_alignment(theme.frames.side.title)
+            .with_padding(ContainerPadding::uniform(0))
+            .draw(window)?;
+
+        let rows = score_rows(state);
+        for (i, (line, highlight)) in rows.iter().take(scores_h as usize - 2).enumerate() {
+            let color = if *highlight {
+                theme.emphasis
+            } else {
+                theme.text
+            };
+            window.write_str_colored(
+                scores_y + 1 + i as u16,
+                content_x,
+                line,
+                status_paint.on(color),
+            )?;
+        }
+    }
+
+    // ==============================
+    // Inventory (in the same spot, when scores aren't up)
+    // ==============================
+    let inventory_h: u16 = 3;
+    let mut notes_y = scores_y;
+    if state.game.rules.inventory
+        && state.game.in_progress()
+        && !show_scores
+        && notes_y + inventory_h < root_y + root_h
+    {
+        status_paint
+            .fill(Container::new())
+            .with_position_and_size(inner_x, notes_y, inner_w, inventory_h)
+            .with_border()
+            .with_border_chars(theme.frames.side.border.chars(glyphs))
+            .with_border_color(status_paint.on(theme.panel_border))
+            .with_title(msg::INVENTORY_TITLE)
+            .with_title_alignment(theme.frames.side.title)
+            .with_padding(ContainerPadding::uniform(0))
+            .draw(window)?;
+
+        let mut x = content_x;
+        for i in 0..logic::INVENTORY_SIZE {
+            let (text, color) = match state.game.inventory.get(i) {
+                Some(&card) => (
+                    format!("{}) {}", i + 1, card_text(card)),
+                    card_color(card, &theme),
+                ),
+                None => (format!("{}) empty", i + 1), theme.empty_slot),
+            };
+            window.write_str_colored(notes_y + 1, x, &text, status_paint.on(color))?;
+            x += text.chars().count() as u16 + 4;
+        }
+        window.write_str_colored(
+            notes_y + 1,
+            x,
+            msg::INVENTORY_HELP,
+            status_paint.on(theme.muted),
+        )?;
+        notes_y += inventory_h;
+    }
+
+    // ==============================
+    // Notes (under the inventory, when scores aren't up)
+    // ==============================
+    let notes_h: u16 = 3;
+    let mut stream_y = notes_y;
+    if state.show_notes && !show_scores && notes_y + notes_h < root_y + root_h {
+        status_paint
+            .fill(Container::new())
+            .with_position_and_size(inner_x, notes_y, inner_w, notes_h)
+            .with_border()
+            .with_border_chars(theme.frames.side.border.chars(glyphs))
+            .with_border_color(status_paint.on(if state.notes_focused {
+                theme.command_border
+            } else {
+                theme.panel_border
+            }))
+            .with_title(msg::NOTES_TITLE)
+            .with_title_alignment(theme.frames.side.title)
+            .with_padding(ContainerPadding::uniform(0))
+            .draw(window)?;
+
+        let mut notes_widget = TextInput::new()
+            .with_position(content_x, notes_y + 1)
+            .with_width(inner_w.saturating_sub(2).max(10))
+            .with_border(false)
+            .with_placeholder(msg::NOTES_PLACEHOLDER);
+        if solid {
+            notes_widget = notes_widget
+                .with_text_color(status_paint.on(theme.text))
+                .with_placeholder_color(status_paint.on(theme.muted));
+        }
+        notes_widget.draw_with_id(
+            window,
+            &mut state.notes_input,
+            state.ui.cache_mut(),
+            ID_NOTES,
+        )?;
+        stream_y += notes_h;
+    }
+
+    // ==============================
+    // Streamer panel (under the notes, when scores aren't up)
+    // ==============================
+    let stream_h: u16 = 4;
+    if state.config.streamer_mode
+        && !show_scores
+        && state.game.in_progress()
+        && stream_y + stream_h < root_y + root_h
+    {
+        status_paint
+            .fill(Container::new())
+            .with_position_and_size(inner_x, stream_y, inner_w, stream_h)
+            .with_border()
+            .with_border_chars(theme.frames.side.border.chars(glyphs))
+            .with_border_color(status_paint.on(theme.panel_border))
+            .with_title(msg::STREAM_TITLE)
+            .with_title_alignment(theme.frames.side.title)
+            .with_padding(ContainerPadding::uniform(0))
+            .draw(window)?;
+
+        for (i, line) in stream_rows(state).iter().enumerate() {
+            window.write_str_colored(
+                stream_y + 1 + i as u16,
+                content_x,
+                line,
+                status_paint.on(theme.text),
+            )?;
+        }
+    }
+
+    // Draw tooltips (rendered last to appear on top. I'll add proper z-ordering to MinUI soon!)
+    if state.config.tooltips == TooltipMode::Overlay
+        && let Some(text) = hovered_tooltip(state)
+    {
+        let (x, y, lines) = place_tooltip(&text, state.mouse_pos, w, h);
+        for (i, line) in lines.iter().enumerate() {
+            window.write_str_colored(y + i as u16, x, line, theme.tooltip)?;
+        }
+    }
+
+    if let Some(&lesson) = state.lessons.first() {
+        draw_lesson(window, &theme, glyphs, lesson, w, h)?;
+    }
+
+    if state.confirming_quit {
+        draw_quit_dialog(window, &theme, glyphs, w, h)?;
+    }
+
+    finish_frame(state, window, &theme, glyphs, w)
+}
+
+/// What floats over every screen, then the frame goes out (which applies the cursor
+/// request)
+fn finish_frame(
+    state: &AppState,
+    window: &mut dyn Window,
+    theme: &Theme,
+    glyphs: &Glyphs,
+    w: u16,
+) -> minui::Result<()> {
+    if let Some(toast) = &state.toast {
+        draw_toast(window, theme, glyphs, toast, w)?;
+    }
+    window.end_frame()
+}
+
+/// Damage the choice in front of the player would deal: at the weapon prompt, whichever
+/// answer is typed (the weapon if nothing is yet), otherwise the monster under the mouse,
+/// typed or under the gamepad's focus
+fn damage_forecast(state: &AppState) -> Option<i32> {
+    let game = &state.game;
+    let typed = command::parse(state.input.text(), game, &state.config.commands);
+    let dmg = match game.state {
+        GameState::CardInteraction if game.awaiting_weapon_choice => {
+            let monster = game.current_monster?;
+            let dmg = match typed {
+                Some(Command::Game(Action::Fight { weapon: None })) => {
+                    game.monster_strength(monster)
+                }
+                Some(Command::Game(Action::Fight { weapon: Some(hand) })) => {
+                    game.hand_damage(hand, monster)
+                }
+                _ => game.weapon_damage(monster),
+            };
+            dmg - game.armor_soaks(dmg)
+        }
+        GameState::CardSelection => {
+            let slot = match typed {
+                Some(Command::Game(Action::Play(i))) => Some(i),
+                _ => (0..4).find(|&i| state.card_hovers[i].is_hovering()),
+            }
+            .or_else(|| focused_slot(state))?;
+            let card = game.visible_card(slot)?;
+            if !card.is_monster() {
+                return None;
+            }
+            advisor::damage(game, card)
+        }
+        _ => return None,
+    };
+    (dmg > 0).then_some(dmg)
+}
+
+/// "Status", plus the speedrun timer and splits when it's on,
+/// e.g. `Status · 1:23.4 · room 0:08.2 · last 0:14.9 · best 2:05.1`
+fn status_title(state: &AppState) -> String {
+    let Some(timer) = state
+        .speedrun
+        .as_ref()
+        .filter(|_| state.game.started_at_ms != 0)
+    else {
+        return "Status".to_string();
+    };
+    let game = &state.game;
+    let now = clock::now_ms();
+    let mut title = format!("Status · {}", locale::time(timer.elapsed(game, now)));
+    if timer.finished.is_none() {
+        title += &format!(" · room {}", locale::time(timer.room_time(game, now)));
+    }
+    if let Some(split) = timer.last_split() {
+        title += &format!(" · last {}", locale::time(split));
+    }
+    if let Some(&best) = state
+        .profile
+        .as_ref()
+        .and_then(|p| p.best_times.get(&game.seed))
+    {
+        title += &format!(" · best {}", locale::time(best));
+    }
+    title
+}
+
+/// Run timer, progress and score pace for the streamer panel
+fn stream_rows(state: &AppState) -> [String; 2] {
+    let game = &state.game;
+    let secs = game.play_clock.elapsed_ms(clock::now_ms()) / 1000;
+    let paused = if game.play_clock.is_running() || !game.in_progress() {
+        ""
+    } else {
+        " (paused)"
+    };
+    let rooms = game
+        .events
+        .iter()
+        .filter(|e| e.action == Action::Face)
+        .count();
+
+    let best = state
+        .scores
+        .iter()
+        .flatten()
+        .map(|e| e.score)
+        .max()
+        .map(|s| format!(" · best ever {s}"))
+        .unwrap_or_default();
+
+    [
+        format!(
+            "Run time {}:{:02}{paused} · room {} · {} cards to go",
+            secs / 60,
+            secs % 60,
+            rooms.max(1),
+            game.cards_remaining()
+        ),
+        format!(
+            "Pace: {} if you survive from here, -{} if you fall{best}",
+            game.health,
+            game.remaining_threat()
+        ),
+    ]
+}
 
-    // Shared geometry
-    let content_x = inner_x + 1;
+/// Lines for the high score panel; the run that just ended is flagged for highlighting
+fn score_rows(state: &AppState) -> Vec<(String, bool)> {
+    let Some(table) = &state.scores else {
+        return vec![(msg::SCORES_UNAVAILABLE.to_string(), false)];
+    };
+    if table.is_empty() {
+        return vec![(msg::NO_SCORES_YET.to_string(), false)];
+    }
 
-    // ==============================
-    // Status panel
-    // ==============================
-    let status_y = inner_y;
+    let latest = table
+        .last()
+        .filter(|_| state.game.state == GameState::GameOver);
+
+    // A query leads with its totals, then its best runs
+    let query = &state.score_query;
+    let picked = scores::select(table, query);
+    let mut rows = Vec::new();
+    let mut shown = scores::TOP_N;
+    if !query.is_empty() {
+        rows.push((scores::totals(&picked), false));
+        shown -= 1;
+    }
+    rows.extend(
+        scores::top(&picked, shown)
+            .iter()
+            .enumerate()
+            .map(|(i, e)| {
+                let outcome = if e.survived { "survived" } else { "died" };
+                let mut line = format!(
+                    "{:>2}. {:>4}  {:<8}  {}  {:<8}",
+                    i + 1,
+                    locale::int(e.score.into()),
+                    outcome,
+                    locale::date(e.at_ms),
+                    e.variant
+                );
+                if e.ironman {
+                    line.push_str("  ironman");
+                }
+                if let Some(tag) = &e.tag {
+                    line.push_str(&format!("  {tag}"));
+                }
+                (line, latest == Some(e))
+            }),
+    );
+    rows
+}
 
-    Container::new()
-        .with_position_and_size(inner_x, status_y, inner_w, status_h)
-        .with_layout_direction(LayoutDirection::Vertical)
-        .with_border()
-        .with_border_chars(BorderChars::single_line())
-        .with_border_color(ColorPair::new(Color::DarkGray, Color::Transparent))
-        .with_title("Status")
-        .with_title_alignment(TitleAlignment::Left)
-        .with_padding(ContainerPadding::uniform(0))
-        .draw(window)?;
+fn draw_weekly_briefing(
+    window: &mut dyn Window,
+    weekly: &Weekly,
+    streamer_mode: bool,
+) -> minui::Result<()> {
+    let muted = ColorPair::new(Color::DarkGray, Color::Transparent);
+    let emphasis = ColorPair::new(Color::White, Color::Transparent);
 
-    // Health line + color
-    let hp_line = health_line(state.game.health, state.game.max_health);
+    window.write_str(1, 2, msg::WEEKLY_TITLE)?;
     window.write_str_colored(
-        status_y + 1,
-        content_x,
-        &hp_line,
-        health_color(state.game.health),
+        2,
+        2,
+        &if streamer_mode {
+            format!("Week of {}", weekly.starts_on())
+        } else {
+            format!("Week of {} · seed {}", weekly.starts_on(), weekly.seed)
+        },
+        muted,
     )?;
 
-    // Weapon + deck lines
-    let weapon = weapon_line(state.game.weapon, state.game.last_monster_slain_with_weapon);
-    window.write_str(status_y + 2, content_x, &weapon)?;
+    window.write_str(4, 2, msg::WEEKLY_MUTATORS)?;
+    let mut y = 5u16;
+    for mutator in &weekly.mutators {
+        window.write_str_colored(y, 4, &format!("• {}", mutator.description()), emphasis)?;
+        y += 1;
+    }
 
-    let deck_line = format!("Cards left in Dungeon: {}", state.game.deck.len());
-    window.write_str(status_y + 3, content_x, &deck_line)?;
+    window.write_str_colored(y + 1, 2, msg::WEEKLY_HELP, muted)?;
+    Ok(())
+}
 
-    // ==============================
-    // Dungeon room panel
-    // ==============================
-    let room_y = status_y + status_h + 1;
+fn draw_custom_game(
+    window: &mut dyn Window,
+    state: &mut AppState,
+    w: u16,
+    h: u16,
+) -> minui::Result<()> {
+    let Some(custom) = &state.custom else {
+        return Ok(());
+    };
+    let muted = ColorPair::new(Color::DarkGray, Color::Transparent);
+    let emphasis = ColorPair::new(Color::White, Color::Transparent);
+
+    window.write_str(1, 2, msg::CUSTOM_TITLE)?;
+    window.write_str_colored(2, 2, &custom.message, muted)?;
+
+    // One row per knob: name, current value (bright if changed), accepted values, help
+    let standard = Ruleset::default();
+    let mut y = 4u16;
+    for knob in KNOBS {
+        let value = custom.rules.get(knob.key).unwrap_or_default();
+        let color = if standard.get(knob.key).as_deref() == Some(value.as_str()) {
+            muted
+        } else {
+            emphasis
+        };
+        window.write_str(y, 4, knob.key)?;
+        window.write_str_colored(y, 22, &value, color)?;
+        window.write_str_colored(y, 38, &format!("{}  ·  {}", knob.values, knob.help), muted)?;
+        y += 1;
+    }
 
-    Container::new()
-        .with_position_and_size(inner_x, room_y, inner_w, room_h)
-        .with_layout_direction(LayoutDirection::Vertical)
-        .with_border()
-        .with_border_chars(BorderChars::single_line())
-        .with_border_color(ColorPair::new(Color::LightBlue, Color::Transparent))
-        .with_title("Dungeon Room")
-        .with_title_alignment(TitleAlignment::Left)
-        .with_padding(ContainerPadding::uniform(0))
-        .draw(window)?;
+    y += 1;
+    window.write_str(y, 2, &format!("Summary: {}", custom.rules.summary()))?;
+    y += 1;
+    if let Some(balance) = &custom.balance {
+        let mut line = match &balance.report {
+            Some(report) => format!("Balance: {}", report.summary()),
+            None => format!("Balance: simulating {} bot games...", sim::GAMES),
+        };
+        if balance.rules != custom.rules {
+            line.push_str(" (before your last change; 'test' again)");
+        }
+        window.write_str_colored(y, 2, &line, muted)?;
+    }
+    y += 2;
+
+    window.write_str(y, 2, msg::PRESETS_TITLE)?;
+    y += 1;
+    match &state.presets {
+        None => window.write_str_colored(y, 4, msg::PRESETS_UNAVAILABLE, muted)?,
+        Some(list) if list.is_empty() => {
+            window.write_str_colored(y, 4, msg::NO_PRESETS_YET, muted)?
+        }
+        Some(list) => {
+            // Keep clear of the input box at the bottom
+            for preset in list.iter().take(h.saturating_sub(y + 5) as usize) {
+                let line = format!(
+                    "{:<24} {:<16} {}",
+                    preset.name,
+                    presets::to_code(&preset.rules),
+                    preset.rules.summary()
+                );
+                window.write_str(y, 4, &line)?;
+                y += 1;
+            }
+        }
+    }
 
-    // Cards (stable slots)
-    let card_area_x = inner_x + 1;
-    let card_area_y = room_y + 1;
+    let input_y = h.saturating_sub(4);
+    TextInput::new()
+        .with_position(2, input_y)
+        .with_width(w.saturating_sub(4).max(10))
+        .with_border(true)
+        .with_placeholder(msg::CUSTOM_PLACEHOLDER)
+        .draw_with_id(window, &mut state.input, state.ui.cache_mut(), ID_INPUT)?;
+    Ok(())
+}
 
-    let card_w: u16 = ((inner_w.saturating_sub(5)) / 4).max(10);
-    let card_h: u16 = 3;
-    let gap: u16 = 1;
+fn draw_sandbox(
+    window: &mut dyn Window,
+    state: &mut AppState,
+    w: u16,
+    h: u16,
+) -> minui::Result<()> {
+    let Some(sandbox) = &state.sandbox else {
+        return Ok(());
+    };
+    let setup = &sandbox.setup;
+    let theme = &state.config.theme;
+    let muted = ColorPair::new(Color::DarkGray, Color::Transparent);
 
-    for i in 0..4usize {
-        let x = card_area_x + (card_w + gap) * (i as u16);
-        let y0 = card_area_y;
+    window.write_str(1, 2, msg::SANDBOX_TITLE)?;
+    window.write_str_colored(2, 2, &sandbox.message, muted)?;
 
-        let id = match i {
-            0 => ID_CARD_1,
-            1 => ID_CARD_2,
-            2 => ID_CARD_3,
-            _ => ID_CARD_4,
+    let max = i32::from(setup.rules.max_health);
+    window.write_str_colored(
+        4,
+        2,
+        &health_line(setup.health, max),
+        health_color(setup.health, theme),
+    )?;
+    window.write_str(5, 2, &weapon_line(setup.weapon, setup.last_slain, None))?;
+    let cards = |cards: &[Card]| {
+        cards
+            .iter()
+            .map(|c| card_text(*c))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let room = match setup.room.len() {
+        0 => "dealt from the deck".to_string(),
+        1..=3 => format!("{}, topped up from the deck", cards(&setup.room)),
+        _ => cards(&setup.room),
+    };
+    window.write_str(6, 2, &format!("Room: {room}"))?;
+    let rest = if setup.fill {
+        "the rest shuffled"
+    } else {
+        "nothing else"
+    };
+    let deck = if setup.deck.is_empty() {
+        format!("Deck: {rest}")
+    } else {
+        format!("Deck: {}, then {rest}", cards(&setup.deck))
+    };
+    window.write_str(7, 2, &deck)?;
+
+    // The picker: every card, one suit per row. Placed cards are dimmed and marked with
+    // where they went.
+    window.write_str(
+        9,
+        2,
+        &format!(
+            "Click a card to put it in {}, again to take it back.",
+            sandbox.target.label()
+        ),
+    )?;
+    for (i, card) in logic::full_deck().into_iter().enumerate() {
+        let row = match card.suit {
+            'S' => 0,
+            'C' => 1,
+            'D' => 2,
+            _ => 3,
         };
-
-        Container::new()
-            .with_position_and_size(x, y0, card_w, card_h)
-            .with_layout_direction(LayoutDirection::Vertical)
-            .with_border()
-            .with_border_chars(BorderChars::single_line())
-            .with_border_color(ColorPair::new(Color::DarkGray, Color::Transparent))
-            .with_padding(ContainerPadding::uniform(0))
-            .draw(window)?;
-
-        let (label, colors) = match state.game.room_slots[i] {
-            Some(c) => (format!("[{}] {}", i + 1, card_text(c)), card_color(c)),
-            None => (
-                "[ ] empty".to_string(),
-                ColorPair::new(Color::DarkGray, Color::Transparent),
-            ),
+        let (x, y) = (4 + u16::from(card.value - 2) * 5, 10 + row);
+        let (mark, color) = if setup.weapon == Some(card) {
+            ("w", muted)
+        } else if setup.room.contains(&card) {
+            ("r", muted)
+        } else if setup.deck.contains(&card) {
+            ("d", muted)
+        } else {
+            ("", card_color(card, theme))
         };
-
-        window.write_str_colored(y0 + 1, x + 1, &label, colors)?;
-
-        // Click hitbox
+        window.write_str_colored(y, x, &format!("{}{mark}", card_text(card)), color)?;
         state.ui.cache_mut().register(
-            id,
+            ID_PICKER + i as InteractionId,
             WidgetArea {
                 x,
-                y: y0,
-                width: card_w,
-                height: card_h,
+                y,
+                width: 4,
+                height: 1,
             },
         );
     }
+    window.write_str_colored(15, 2, msg::SANDBOX_LEGEND, muted)?;
+    window.write_str_colored(16, 2, &format!("Rules: {}", setup.rules.summary()), muted)?;
 
-    // Room footer
-    let footer = match state.game.state {
-        GameState::CardSelection => Some(format!(
-            "Interactions left in this room: {}",
-            state.game.interactions_left_in_room
-        )),
-        _ => None,
-    };
+    let input_y = h.saturating_sub(4);
+    TextInput::new()
+        .with_position(2, input_y)
+        .with_width(w.saturating_sub(4).max(10))
+        .with_border(true)
+        .with_placeholder(msg::SANDBOX_PLACEHOLDER)
+        .draw_with_id(window, &mut state.input, state.ui.cache_mut(), ID_INPUT)?;
+    Ok(())
+}
 
-    if let Some(footer) = footer {
-        window.write_str_colored(
-            room_y + 4,
-            content_x,
-            &footer,
-            ColorPair::new(Color::DarkGray, Color::Transparent),
-        )?;
+fn draw_shop(window: &mut dyn Window, state: &mut AppState, w: u16, h: u16) -> minui::Result<()> {
+    let game = &state.game;
+    let muted = ColorPair::new(Color::DarkGray, Color::Transparent);
+    let emphasis = ColorPair::new(Color::White, Color::Transparent);
+
+    let title = if game.at_merchant {
+        msg::MERCHANT_TITLE
+    } else {
+        msg::SHOP_TITLE
+    };
+    window.write_str(1, 2, title)?;
+    window.write_str_colored(2, 2, &game.message, muted)?;
+
+    let weapon = game.weapon_name().unwrap_or_else(|| "nothing".to_string());
+    let purse = format!(
+        "Gold: {}   HP: {}/{}   Wielding: {weapon}   Skip tokens: {}",
+        game.gold, game.health, game.max_health, game.skip_tokens
+    );
+    window.write_str(4, 2, &purse)?;
+
+    // One row per ware; the ones you can't buy right now are greyed out
+    for (y, ware) in (6u16..).zip(game.wares()) {
+        let color = if game.can_buy(*ware) { emphasis } else { muted };
+        let line = format!(
+            "{:<8}{:>3} gold   {}",
+            ware.code(),
+            ware.price(),
+            ware.description()
+        );
+        window.write_str_colored(y, 4, &line, color)?;
     }
 
-    // ==============================
-    // Message panel (hint + message + previous input / score)
-    // ==============================
-    let msg_y = room_y + room_h + 1;
+    let placeholder = command_placeholder(game, state.has_save, &state.config.commands);
+    let input_y = h.saturating_sub(4);
+    TextInput::new()
+        .with_position(2, input_y)
+        .with_width(w.saturating_sub(4).max(10))
+        .with_border(true)
+        .with_placeholder(&placeholder)
+        .draw_with_id(window, &mut state.input, state.ui.cache_mut(), ID_INPUT)?;
+    Ok(())
+}
 
-    Container::new()
-        .with_position_and_size(inner_x, msg_y, inner_w, msg_h)
-        .with_layout_direction(LayoutDirection::Vertical)
-        .with_border()
-        .with_border_chars(BorderChars::single_line())
-        .with_border_color(ColorPair::new(Color::DarkGray, Color::Transparent))
-        .with_title("Message")
-        .with_title_alignment(TitleAlignment::Left)
-        .with_padding(ContainerPadding::uniform(0))
-        .draw(window)?;
+/// The move log, one turn a line. The latest turns win when they don't all fit.
+fn draw_move_log(window: &mut dyn Window, game: &Game, h: u16) -> minui::Result<()> {
+    let muted = ColorPair::new(Color::DarkGray, Color::Transparent);
 
-    // Hint line in message box
-    let hint = state_hint(&state.game);
-    window.write_str_colored(
-        msg_y + 1,
-        content_x,
-        hint,
-        ColorPair::new(Color::DarkGray, Color::Transparent),
-    )?;
+    window.write_str(1, 2, msg::LOG_TITLE)?;
+    window.write_str_colored(2, 2, msg::LOG_HELP, muted)?;
+    if game.turns() == 0 {
+        window.write_str_colored(4, 4, msg::LOG_EMPTY, muted)?;
+        return Ok(());
+    }
 
-    let message = if state.game.message.is_empty() {
-        match state.game.state {
-            GameState::MainMenu => "Welcome, Scoundrel.".to_string(),
-            GameState::RoomChoice => msg::NEED_FACE_OR_SKIP.to_string(),
-            GameState::CardSelection => "Choose a card.".to_string(),
-            GameState::CardInteraction => {
-                if state.game.awaiting_weapon_choice {
-                    msg::NEED_Y_OR_N.to_string()
-                } else {
-                    msg::HINT_INTERACTION_ACK.to_string()
-                }
-            }
-            GameState::GameOver => state.game.remaining_summary_line(),
+    let twist = ColorPair::new(Color::LightRed, Color::Transparent);
+    let rows = usize::from(h.saturating_sub(5)).max(1);
+    let skip = game.turns().saturating_sub(rows);
+    for (i, (turn, event)) in game.moves().enumerate().skip(skip).enumerate() {
+        let what = match (event.action, event.card) {
+            (Action::Skip, _) => "skip the room".to_string(),
+            (Action::Stash(_), Some(card)) => format!("stash {}", card_text(card)),
+            (Action::Use(_), Some(card)) => format!("use {} (pack)", card_text(card)),
+            (_, Some(card)) => format!("play {}", card_text(card)),
+            (Action::Play(slot), None) => format!("play card {}", slot + 1),
+            _ => continue,
+        };
+        // A monster's row covers the weapon prompt after it, where Golem and Dragon bite
+        let answer = game
+            .events
+            .iter()
+            .skip_while(|e| e.turn <= event.turn)
+            .take_while(|e| matches!(e.action, Action::Fight { .. } | Action::Continue))
+            .find(|e| matches!(e.action, Action::Fight { .. }));
+        let after = answer.unwrap_or(event);
+        let line = format!("Turn {:>3}  {what:<16} HP {}", turn + 1, after.health);
+        let y = 4 + i as u16;
+        window.write_str(y, 4, &line)?;
+        if let Some(ability) = event.ability.or(after.ability) {
+            let x = 4 + line.chars().count() as u16 + 2;
+            window.write_str_colored(y, x, &ability.text(), twist)?;
         }
-    } else {
-        state.game.message.clone()
+    }
+    Ok(())
+}
+
+fn draw_achievements(
+    window: &mut dyn Window,
+    profile: Option<&Profile>,
+    w: u16,
+) -> minui::Result<()> {
+    let muted = ColorPair::new(Color::DarkGray, Color::Transparent);
+    let done = ColorPair::new(Color::LightGreen, Color::Transparent);
+
+    window.write_str(1, 2, msg::ACHIEVEMENTS_TITLE)?;
+    window.write_str_colored(2, 2, msg::ACHIEVEMENTS_HELP, muted)?;
+    let Some(profile) = profile else {
+        window.write_str_colored(4, 4, msg::ACHIEVEMENTS_UNAVAILABLE, muted)?;
+        return Ok(());
     };
 
-    window.write_str(msg_y + 2, content_x, &message)?;
+    // Name and goal, then the bar under them; the bar takes what's left of the width
+    let bar_w = u32::from(w.saturating_sub(20).clamp(10, 40));
+    let mut y = 4u16;
+    for a in ACHIEVEMENTS {
+        let progress = (a.progress)(profile).min(a.target);
+        let complete = progress >= a.target;
+        let color = if complete {
+            done
+        } else {
+            ColorPair::new(Color::White, Color::Transparent)
+        };
+        window.write_str_colored(y, 4, a.name, color)?;
+        window.write_str_colored(y, 22, a.goal, muted)?;
+
+        let filled = (progress * bar_w / a.target) as usize;
+        let bar = format!(
+            "{}{}",
+            render::glyphs().bar_full.repeat(filled),
+            render::glyphs().bar_empty.repeat(bar_w as usize - filled)
+        );
+        let count = if complete {
+            "done!".to_string()
+        } else {
+            format!("{progress}/{}", a.target)
+        };
+        window.write_str_colored(y + 1, 4, &format!("|{bar}| {count}"), color)?;
+        y += 3;
+    }
+    Ok(())
+}
+
+fn draw_marathon_summary(
+    window: &mut dyn Window,
+    session: &Marathon,
+    board: Option<&[SessionEntry]>,
+    h: u16,
+) -> minui::Result<()> {
+    let muted = ColorPair::new(Color::DarkGray, Color::Transparent);
+    let emphasis = ColorPair::new(Color::White, Color::Transparent);
 
-    // Previous input / score line directly under message (no extra blank line)
-    if state.game.state == GameState::GameOver {
-        let score_line = format!("FINAL SCORE: {}", state.game.final_score());
-        window.write_str_colored(
-            msg_y + 3,
-            content_x,
-            &score_line,
-            ColorPair::new(Color::White, Color::Transparent),
-        )?;
-    } else if !state.game.last_command_feedback.is_empty() {
+    window.write_str(1, 2, msg::MARATHON_SUMMARY_TITLE)?;
+    window.write_str_colored(2, 2, msg::MARATHON_SUMMARY_HELP, muted)?;
+
+    let mut y = 4u16;
+    window.write_str_colored(
+        y,
+        2,
+        &format!(
+            "Total {} over {} runs ({} survived)",
+            session.total(),
+            session.runs.len(),
+            session.wins()
+        ),
+        emphasis,
+    )?;
+    y += 2;
+
+    // Leave room for the leaderboard below
+    let max_runs = (h / 2).saturating_sub(y) as usize;
+    for (i, (score, survived)) in session.runs.iter().enumerate().take(max_runs) {
+        let outcome = if *survived { "survived" } else { "died" };
+        window.write_str(y, 4, &format!("Run {:>2}: {score:>4}  {outcome}", i + 1))?;
+        y += 1;
+    }
+    if session.runs.len() > max_runs {
         window.write_str_colored(
-            msg_y + 3,
-            content_x,
-            &state.game.last_command_feedback,
-            ColorPair::new(Color::DarkGray, Color::Transparent),
+            y,
+            4,
+            &format!("... and {} more", session.runs.len() - max_runs),
+            muted,
         )?;
+        y += 1;
     }
 
-    // ==============================
-    // Command panel + TextInput
-    // ==============================
-    let cmd_y = msg_y + msg_h + 1;
+    y += 1;
+    window.write_str(y, 2, msg::MARATHON_BOARD_TITLE)?;
+    y += 1;
+    let Some(board) = board else {
+        window.write_str_colored(y, 4, msg::SCORES_UNAVAILABLE, muted)?;
+        return Ok(());
+    };
 
-    Container::new()
-        .with_position_and_size(inner_x, cmd_y, inner_w, cmd_h)
-        .with_layout_direction(LayoutDirection::Vertical)
+    // The session just stopped is the newest entry
+    let latest = board.last();
+    for (i, e) in marathon::top(board, scores::TOP_N).iter().enumerate() {
+        if y + 1 >= h {
+            break;
+        }
+        let line = format!(
+            "{:>2}. {:>5}  {} runs, {} survived  {}",
+            i + 1,
+            locale::int(e.total.into()),
+            e.runs,
+            e.wins,
+            locale::date(e.at_ms)
+        );
+        if latest == Some(e) {
+            window.write_str_colored(y, 4, &line, emphasis)?;
+        } else {
+            window.write_str(y, 4, &line)?;
+        }
+        y += 1;
+    }
+    Ok(())
+}
+
+fn draw_quit_dialog(
+    window: &mut dyn Window,
+    theme: &Theme,
+    glyphs: &Glyphs,
+    w: u16,
+    h: u16,
+) -> minui::Result<()> {
+    let box_w = (msg::QUIT_CONFIRM_BODY.chars().count() as u16 + 4).min(w);
+    let box_h = 4u16.min(h);
+    let x = w.saturating_sub(box_w) / 2;
+    let y = h.saturating_sub(box_h) / 2;
+
+    // Always filled: it has to hide the panels underneath
+    let paint = Paint(Some(theme.backgrounds.status));
+    paint
+        .fill(Container::new())
+        .with_position_and_size(x, y, box_w, box_h)
         .with_border()
-        .with_border_chars(BorderChars::single_line())
-        .with_border_color(ColorPair::new(Color::White, Color::Transparent))
-        .with_title("Command")
-        .with_title_alignment(TitleAlignment::Left)
+        .with_border_chars(theme.frames.dialog.border.chars(glyphs))
+        .with_border_color(paint.on(theme.command_border))
+        .with_title(msg::QUIT_CONFIRM_TITLE)
+        .with_title_alignment(theme.frames.dialog.title)
         .with_padding(ContainerPadding::uniform(0))
         .draw(window)?;
 
-    let input_x = content_x;
-    let input_y = cmd_y + 1;
-    let input_w = inner_w.saturating_sub(2).max(10);
+    window.write_str_colored(y + 1, x + 2, msg::QUIT_CONFIRM_BODY, paint.on(theme.text))?;
+    window.write_str_colored(y + 2, x + 2, msg::QUIT_CONFIRM_KEYS, paint.on(theme.muted))?;
+    Ok(())
+}
 
-    let input_widget = TextInput::new()
-        .with_position(input_x, input_y)
-        .with_width(input_w)
-        .with_border(true)
-        .with_placeholder(command_placeholder(&state.game));
+/// One line in a box in the top right corner
+fn draw_toast(
+    window: &mut dyn Window,
+    theme: &Theme,
+    glyphs: &Glyphs,
+    toast: &Toast,
+    w: u16,
+) -> minui::Result<()> {
+    let box_w = (toast
+        .text
+        .chars()
+        .count()
+        .max(toast.title.chars().count() + 4) as u16
+        + 4)
+    .min(w);
+    let x = w.saturating_sub(box_w + 2);
+    let y = 2;
+
+    // Always filled: it has to hide the panels underneath
+    let paint = Paint(Some(theme.backgrounds.status));
+    paint
+        .fill(Container::new())
+        .with_position_and_size(x, y, box_w, 3)
+        .with_border()
+        .with_border_chars(theme.frames.dialog.border.chars(glyphs))
+        .with_border_color(paint.on(theme.emphasis))
+        .with_title(toast.title)
+        .with_title_alignment(theme.frames.dialog.title)
+        .with_padding(ContainerPadding::uniform(0))
+        .draw(window)?;
 
-    input_widget.draw_with_id(window, &mut state.input, state.ui.cache_mut(), ID_INPUT)?;
+    window.write_str_colored(y + 1, x + 2, &toast.text, paint.on(theme.text))?;
+    Ok(())
+}
 
-    // Draw tooltips (rendered last to appear on top. I'll add proper z-ordering to MinUI soon!)
-    for i in 0..4usize {
-        if let Some(card) = state.game.room_slots[i] {
-            if state.card_hovers[i].should_show_tooltip(Duration::from_millis(300)) {
-                let tooltip_text = card_tooltip_text(card, &state.game);
-                let tooltip = Tooltip::new(&tooltip_text)
-                    .with_delay(Duration::from_millis(200))
-                    .with_color(ColorPair::new(Color::LightGray, Color::DarkGray));
+/// Centered box explaining a rule the first time it comes up
+fn draw_lesson(
+    window: &mut dyn Window,
+    theme: &Theme,
+    glyphs: &Glyphs,
+    lesson: Lesson,
+    w: u16,
+    h: u16,
+) -> minui::Result<()> {
+    let box_w = 60u16.min(w);
+    let lines = wrap_words(lesson.text(), box_w.saturating_sub(4) as usize);
+    let box_h = (lines.len() as u16 + 4).min(h);
+    let x = w.saturating_sub(box_w) / 2;
+    let y = h.saturating_sub(box_h) / 2;
+
+    let paint = Paint(Some(theme.backgrounds.status));
+    paint
+        .fill(Container::new())
+        .with_position_and_size(x, y, box_w, box_h)
+        .with_border()
+        .with_border_chars(theme.frames.dialog.border.chars(glyphs))
+        .with_border_color(paint.on(theme.command_border))
+        .with_title(lesson.title())
+        .with_title_alignment(theme.frames.dialog.title)
+        .with_padding(ContainerPadding::uniform(0))
+        .draw(window)?;
+
+    let mut row = y + 1;
+    for line in &lines {
+        window.write_str_colored(row, x + 2, line, paint.on(theme.text))?;
+        row += 1;
+    }
+    window.write_str_colored(row + 1, x + 2, msg::LESSON_HELP, paint.on(theme.muted))?;
+    Ok(())
+}
 
-                let (tooltip_x, tooltip_y) =
-                    tooltip.position_near_mouse(state.mouse_pos.0, state.mouse_pos.1, w, h);
+/// Greedy word wrap; a word longer than `width` gets a line to itself
+fn wrap_words(text: &str, width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
 
-                tooltip.draw_at(window, tooltip_x, tooltip_y)?;
-            }
+/// Test pattern of every theme's roles with their WCAG contrast ratio. A theme customized
+/// in `config.toml` is listed first.
+fn draw_contrast_audit(
+    window: &mut dyn Window,
+    w: u16,
+    h: u16,
+    config: &Config,
+) -> minui::Result<()> {
+    let solid = config.solid_backgrounds;
+    let muted = ColorPair::new(Color::DarkGray, Color::Transparent);
+    let help = if solid {
+        msg::CONTRAST_AUDIT_HELP_SOLID
+    } else {
+        msg::CONTRAST_AUDIT_HELP
+    };
+    window.write_str(1, 2, msg::CONTRAST_AUDIT_TITLE)?;
+    window.write_str_colored(2, 2, help, muted)?;
+
+    let col_w: u16 = 48;
+    let (mut x, mut y) = (2u16, 4u16);
+
+    let active = &config.theme;
+    let customized = !theme::BUILTIN.contains(active);
+    let themes = customized
+        .then_some(active)
+        .into_iter()
+        .chain(theme::BUILTIN);
+
+    for t in themes {
+        // With solid backgrounds on, transparent roles are measured against the panel color
+        let paint = Paint::new(solid, t.backgrounds.status);
+        let roles: Vec<_> = t
+            .roles()
+            .into_iter()
+            .map(|(name, pair, kind)| (name, paint.on(pair), kind))
+            .collect();
+        let block_h = roles.len() as u16 + 2;
+
+        // Wrap to a new row of columns when this theme wouldn't fit to the right
+        if x > 2 && x + col_w > w {
+            x = 2;
+            y += block_h + 1;
+        }
+        if y + block_h > h {
+            break;
+        }
+
+        let failing = roles
+            .iter()
+            .filter(|(_, pair, kind)| theme::contrast_ratio(*pair) < kind.min_ratio())
+            .count();
+        let name = if customized && std::ptr::eq(t, active) {
+            format!("{} (customized)", t.name)
+        } else {
+            t.name.to_string()
+        };
+        window.write_str(y, x, &format!("{name} ({failing} below threshold)"))?;
+
+        for (i, (name, pair, kind)) in roles.iter().enumerate() {
+            let row = y + 1 + i as u16;
+            let ratio = theme::contrast_ratio(*pair);
+            let sample = match kind {
+                theme::RoleKind::Border => "──┤ Aa ├──",
+                theme::RoleKind::Text => &format!(" Aa 10 {} ", config.glyphs.suits[0]),
+            };
+            let verdict = if ratio >= kind.min_ratio() {
+                ("ok", ColorPair::new(Color::Green, Color::Transparent))
+            } else {
+                ("LOW", ColorPair::new(Color::Red, Color::Transparent))
+            };
+
+            window.write_str_colored(row, x, sample, *pair)?;
+            window.write_str(row, x + 12, &format!("{name:<17}{ratio:>5.1}:1"))?;
+            window.write_str_colored(row, x + 38, verdict.0, verdict.1)?;
         }
+
+        x += col_w;
     }
 
-    // End frame applies cursor request
-    window.end_frame()?;
     Ok(())
 }
 
 fn state_hint(game: &Game) -> &'static str {
     match game.state {
         GameState::MainMenu => msg::HINT_MAIN,
+        GameState::ResumePrompt => msg::HINT_RESUME,
         GameState::RoomChoice => {
-            if game.can_skip {
+            if game.legal_actions().contains(&Action::Skip) {
                 msg::HINT_ROOM_CHOICE_CAN_SKIP
             } else {
                 msg::HINT_ROOM_CHOICE_NO_SKIP
@@ -679,44 +3749,222 @@ fn state_hint(game: &Game) -> &'static str {
         }
         GameState::CardSelection => msg::HINT_CARD_SELECTION,
         GameState::CardInteraction => {
-            if game.awaiting_weapon_choice {
+            if game.choosing_hand() {
+                msg::HINT_PROMPT_HANDS
+            } else if game.awaiting_weapon_choice {
                 msg::HINT_PROMPT_WEAPON
             } else {
                 msg::HINT_INTERACTION_ACK
             }
         }
+        GameState::Shop => msg::HINT_SHOP,
+        GameState::Trap => msg::HINT_TRAP,
+        GameState::GameOver if game.survived && !game.sandbox => msg::HINT_GAME_WON,
         GameState::GameOver => msg::HINT_GAME_OVER,
     }
 }
 
-fn card_tooltip_text(card: crate::logic::Card, game: &Game) -> String {
+/// The tooltip for the room card under the mouse, once it's been there long enough.
+/// Streamer mode leaves them off, so the stream doesn't spell out every card.
+fn hovered_tooltip(state: &AppState) -> Option<String> {
+    if state.config.streamer_mode {
+        return None;
+    }
+    let slot = (0..4).find(|&i| {
+        !state.game.is_hidden(i)
+            && state.card_hovers[i].should_show_tooltip(state.pacing.tooltip_delay())
+    })?;
+    let card = state.game.room_slots[slot]?;
+    if state.game.is_dark(slot) {
+        return Some(card_tooltip_text(card, false, None, &state.game));
+    }
+    let delta = state.game.pick_hp_delta(slot);
+    let mut text = card_tooltip_text(card, true, delta.as_ref(), &state.game);
+    if let Some(ability) = state.game.ability_of(card) {
+        text += &format!(" - {}", ability.text());
+    }
+    if state.game.rules.treasure_score && state.game.is_treasure(card) {
+        text += &format!(" - Treasure: +{} score", card.value);
+    }
+    if state.game.traps.contains(&card) {
+        text += &match Trap::of(card) {
+            Trap::Spikes => format!(" - Trap: spikes, {} damage", Trap::damage(card)),
+            Trap::Snare => " - Trap: a snare, no weapon for a pick".to_string(),
+        };
+    }
+    if state.game.merchants.contains(&card) {
+        text = format!(
+            "{}: Merchant - opens a shop instead of playing (you have {} gold)",
+            card_text(card),
+            state.game.gold
+        );
+    } else if !card.is_monster()
+        && let Some(HpDelta::Fixed(hp)) = delta
+        && hp != 0
+    {
+        text += &format!(" - Picking it now: {} HP", signed(hp));
+    }
+    Some(text)
+}
+
+/// `+5`, `-3` or `0`
+fn signed(hp: i32) -> String {
+    if hp == 0 {
+        "0".to_string()
+    } else {
+        format!("{hp:+}")
+    }
+}
+
+/// The `preview` tag under a room card: `-8 HP`, or `-3 / -8 HP` with the weapon first
+fn hp_delta_tag(delta: &HpDelta) -> String {
+    match delta {
+        HpDelta::Fixed(hp) => format!("{} HP", signed(*hp)),
+        HpDelta::Choice(outcomes) => {
+            let hps: Vec<String> = outcomes.iter().map(|&(_, hp)| signed(hp)).collect();
+            format!("{} HP", hps.join(" / "))
+        }
+    }
+}
+
+/// A monster's tooltip numbers: each way of fighting it now, and what it costs
+fn fight_delta_text(delta: &HpDelta, monster: Card, game: &Game) -> String {
+    let two_weapons = game.loadout.hands.iter().flatten().count() > 1;
+    match delta {
+        HpDelta::Fixed(hp) if game.weapon().is_some() => {
+            format!("Your weapon can't take it: {} HP", signed(*hp))
+        }
+        HpDelta::Fixed(hp) => format!("Picking it now: {} HP", signed(*hp)),
+        HpDelta::Choice(outcomes) => outcomes
+            .iter()
+            .map(|&(weapon, hp)| match weapon {
+                Some(hand) => format!(
+                    "With {}: {} HP{}",
+                    if two_weapons {
+                        format!("weapon {}", hand + 1)
+                    } else {
+                        "weapon".to_string()
+                    },
+                    signed(hp),
+                    if game.hand_breaks_on(hand, monster) {
+                        " (weapon breaks)"
+                    } else {
+                        ""
+                    }
+                ),
+                None => format!("bare-handed: {} HP", signed(hp)),
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+    }
+}
+
+/// Widest a tooltip gets before it wraps
+const TOOLTIP_MAX_W: u16 = 44;
+/// Tallest a tooltip gets; anything past this is cut short with `…`
+const TOOLTIP_MAX_LINES: usize = 4;
+
+/// Lay a tooltip out to stay whole on screen: wrapped to `TOOLTIP_MAX_W` columns (fewer on
+/// a narrow terminal), below the mouse or above it near the bottom, and pulled left off
+/// the right edge. Lines come back padded to one width so the background is a solid box.
+fn place_tooltip(text: &str, mouse: (u16, u16), w: u16, h: u16) -> (u16, u16, Vec<String>) {
+    let width = usize::from(TOOLTIP_MAX_W.min(w.saturating_sub(2)).max(1));
+    let mut lines: Vec<String> = wrap_words(text, width)
+        .iter()
+        .map(|line| clip(line, width))
+        .collect();
+    if lines.len() > TOOLTIP_MAX_LINES {
+        lines.truncate(TOOLTIP_MAX_LINES);
+        let last = lines.pop().unwrap_or_default();
+        lines.push(clip(&format!("{last}…"), width));
+    }
+
+    let box_w = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    for line in &mut lines {
+        let pad = box_w - line.chars().count();
+        line.push_str(&" ".repeat(pad));
+    }
+    let box_h = lines.len() as u16;
+    let (mouse_x, mouse_y) = mouse;
+    let x = mouse_x.min(w.saturating_sub(box_w as u16));
+    let y = if mouse_y + 1 + box_h <= h {
+        mouse_y + 1
+    } else {
+        mouse_y.saturating_sub(box_h)
+    };
+    (x, y, lines)
+}
+
+/// `text` cut to `width` columns, ending in `…` if anything was lost
+fn clip(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut out: String = text.chars().take(width.saturating_sub(1)).collect();
+    out.push('…');
+    out
+}
+
+/// What a room card does. Unless `revealed` (a `dark` room) only its suit is known. A
+/// monster's numbers come from `delta`, what picking it now would really do, when it can
+/// be picked.
+fn card_tooltip_text(
+    card: crate::logic::Card,
+    revealed: bool,
+    delta: Option<&HpDelta>,
+    game: &Game,
+) -> String {
+    if !revealed {
+        return match card.suit {
+            'S' | 'C' => "Monster (ATK ?) - Too dark to tell how strong".to_string(),
+            'D' if game.rules.armor > 0 => "Weapon or armor (?) - Too dark to tell".to_string(),
+            'D' => "Weapon (ATK ?) - Too dark to tell".to_string(),
+            'H' => "Potion (Heal for ?)".to_string(),
+            _ => "Unknown card".to_string(),
+        };
+    }
     match card.suit {
+        'S' | 'C' if let Some(delta) = delta => format!(
+            "Monster (ATK {}) - {}",
+            card.value,
+            fight_delta_text(delta, card, game)
+        ),
         'S' | 'C' => {
             let base_damage = card.value as i32;
 
-            if let Some(weapon) = game.weapon {
+            if game.weapon().is_some() {
                 if game.can_use_weapon_on(card) {
-                    let weapon_value = weapon.value as i32;
-                    let damage = (base_damage - weapon_value).max(0);
+                    let damage = game.weapon_damage(card);
+                    let damage = damage - game.armor_soaks(damage);
+                    let breaks = if game.weapon_breaks_on(card) {
+                        " (weapon breaks)"
+                    } else {
+                        ""
+                    };
                     format!(
-                        "Monster (ATK {}) - With weapon: {} damage",
-                        base_damage, damage
+                        "Monster (ATK {}) - With weapon: {} damage{}",
+                        base_damage, damage, breaks
                     )
                 } else {
                     //let limit = game.last_monster_slain_with_weapon.unwrap_or(0);
                     format!(
                         "Monster (ATK {}) - Weapon degraded. Will take {} damage",
-                        base_damage, base_damage
+                        base_damage,
+                        base_damage - game.armor_soaks(base_damage)
                     )
                 }
             } else {
                 format!("Monster (ATK {})", base_damage)
             }
         }
+        'D' if game.is_armor(card) => {
+            let fresh = crate::logic::Armor::new(card).left();
+            format!("Armor (DEF {}, soaks up {fresh} in all)", card.value)
+        }
         'D' => {
             let weapon_value = card.value as i32;
             let limit_text = game
-                .last_monster_slain_with_weapon
+                .last_slain()
                 .map(|l| format!(" (updates to < {})", l))
                 .unwrap_or_else(|| " (no restriction)".to_string());
 
@@ -0,0 +1,57 @@
+//! Weekly challenge
+//!
+//! Everyone playing in the same week (Monday to Sunday, UTC) gets the same dungeon: the
+//! seed and the one to three mutators are both derived from the week's number.
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::locale;
+use crate::logic::{Game, MUTATORS, Mutator};
+use crate::rules::Ruleset;
+
+const DAY_MS: u64 = 86_400_000;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Weekly {
+    /// Weeks since the one containing 1970-01-01
+    pub week: u64,
+    pub seed: u64,
+    /// In registry order
+    pub mutators: Vec<Mutator>,
+}
+
+impl Weekly {
+    pub fn for_time(ms: u64) -> Self {
+        // 1970-01-01 was a Thursday; shifting by 3 days makes weeks start on Monday
+        let week = (ms / DAY_MS + 3) / 7;
+        let seed = week.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+
+        // Mutators come from their own stream so they don't disturb the deal
+        let mut rng = StdRng::seed_from_u64(seed ^ 0x5EED_F00D);
+        let count = rng.gen_range(1..=3);
+        let mut mutators: Vec<Mutator> =
+            MUTATORS.choose_multiple(&mut rng, count).copied().collect();
+        mutators.sort_by_key(|m| MUTATORS.iter().position(|x| x == m));
+
+        Self {
+            week,
+            seed,
+            mutators,
+        }
+    }
+
+    /// The Monday this week started, e.g. `2026-10-12`
+    pub fn starts_on(&self) -> String {
+        locale::date((self.week * 7).saturating_sub(3) * DAY_MS)
+    }
+
+    /// The challenge on the main menu, ready for `start`. Always the standard rules, so
+    /// everyone's week is the same.
+    pub fn game(&self) -> Game {
+        let mut game = Game::with_seed(Ruleset::default(), self.seed);
+        game.mutators = self.mutators.clone();
+        game
+    }
+}